@@ -0,0 +1,20 @@
+-- Campaign, user and role IDs are snowflakes, so the interview tables need BIGINT
+-- columns to reference them. Timeslot IDs are switched to snowflakes as well.
+ALTER TABLE interview_timeslot_users
+    DROP CONSTRAINT fk_timeslot_user_timeslot;
+
+ALTER TABLE interview_timeslots
+    ALTER COLUMN id DROP DEFAULT,
+    ALTER COLUMN id TYPE BIGINT,
+    ALTER COLUMN campaign_id TYPE BIGINT;
+
+DROP SEQUENCE IF EXISTS interview_timeslots_id_seq;
+
+ALTER TABLE interview_timeslot_users
+    ALTER COLUMN interview_timeslot_id TYPE BIGINT,
+    ALTER COLUMN user_id TYPE BIGINT,
+    ALTER COLUMN role_id TYPE BIGINT,
+    ADD CONSTRAINT fk_timeslot_user_timeslot
+        FOREIGN KEY (interview_timeslot_id)
+        REFERENCES interview_timeslots(id)
+        ON DELETE CASCADE;
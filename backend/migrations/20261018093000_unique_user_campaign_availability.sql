@@ -0,0 +1,3 @@
+-- Each user has at most one availability grid per campaign, so that it can be replaced atomically.
+ALTER TABLE user_campaign_availabilities
+    ADD CONSTRAINT unique_user_campaign_availability UNIQUE (user_id, campaign_id);
@@ -0,0 +1,16 @@
+CREATE TABLE campaign_role_interviewers (
+    campaign_role_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    PRIMARY KEY (campaign_role_id, user_id),
+    CONSTRAINT fk_role_interviewer_role
+        FOREIGN KEY (campaign_role_id)
+        REFERENCES campaign_roles(id)
+        ON DELETE CASCADE,
+    CONSTRAINT fk_role_interviewer_user
+        FOREIGN KEY (user_id)
+        REFERENCES users(id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX idx_campaign_role_interviewers_user
+    ON campaign_role_interviewers(user_id);
@@ -0,0 +1,10 @@
+CREATE TYPE calendar_method AS ENUM ('Request', 'Cancel');
+
+-- Queued emails can carry an iCalendar event, sent as a text/calendar part and an .ics attachment.
+ALTER TABLE email_queue
+    ADD COLUMN calendar_method calendar_method,
+    ADD COLUMN calendar_ics TEXT;
+
+-- Incremented every time a calendar event is sent for the timeslot, so clients apply updates in order.
+ALTER TABLE interview_timeslots
+    ADD COLUMN calendar_sequence INT NOT NULL DEFAULT 0;
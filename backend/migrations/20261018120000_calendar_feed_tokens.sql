@@ -0,0 +1,10 @@
+-- Each user has at most one calendar feed token. Rotating it replaces the row, revoking deletes it.
+CREATE TABLE calendar_feed_tokens (
+    user_id BIGINT PRIMARY KEY,
+    token TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_calendar_feed_token_user
+        FOREIGN KEY (user_id)
+        REFERENCES users(id)
+        ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+CREATE TYPE email_queue_status AS ENUM ('Pending', 'Failed');
+
+-- Failed sends are retried with exponential backoff until they are moved to 'Failed'.
+ALTER TABLE email_queue
+    ADD COLUMN status email_queue_status NOT NULL DEFAULT 'Pending',
+    ADD COLUMN attempts INT NOT NULL DEFAULT 0,
+    ADD COLUMN last_error TEXT,
+    ADD COLUMN next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP;
+
+CREATE INDEX idx_email_queue_status_next_attempt
+    ON email_queue(status, next_attempt_at);
@@ -0,0 +1,4 @@
+-- Workers lease a batch of emails before sending them, so concurrent workers
+-- never send the same email and a crashed worker's batch is picked up again.
+ALTER TABLE email_queue
+    ADD COLUMN locked_until TIMESTAMPTZ;
@@ -0,0 +1,40 @@
+CREATE TYPE sent_email_status AS ENUM ('Queued', 'Sent', 'Failed');
+
+-- Permanent record of every queued email, kept after the queue row is deleted.
+CREATE TABLE sent_emails (
+    id BIGSERIAL PRIMARY KEY,
+    campaign_id BIGINT,
+    application_id BIGINT,
+    offer_id BIGINT,
+    recipient_name TEXT,
+    recipient_email_address TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    body TEXT NOT NULL,
+    status sent_email_status NOT NULL DEFAULT 'Queued',
+    attempts INT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    queued_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    sent_at TIMESTAMPTZ,
+    failed_at TIMESTAMPTZ,
+    CONSTRAINT FK_sent_emails_campaigns
+        FOREIGN KEY(campaign_id)
+            REFERENCES campaigns(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT FK_sent_emails_applications
+        FOREIGN KEY(application_id)
+            REFERENCES applications(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT FK_sent_emails_offers
+        FOREIGN KEY(offer_id)
+            REFERENCES offers(id)
+            ON DELETE SET NULL
+            ON UPDATE CASCADE
+);
+
+CREATE INDEX IDX_sent_emails_application on sent_emails(application_id);
+CREATE INDEX IDX_sent_emails_campaign on sent_emails(campaign_id);
+
+ALTER TABLE email_queue
+    ADD COLUMN sent_email_id BIGINT REFERENCES sent_emails(id) ON DELETE SET NULL;
@@ -0,0 +1,8 @@
+ALTER TYPE sent_email_status ADD VALUE 'Cancelled';
+
+-- Outcome and offer emails are held in the queue until their campaign's
+-- outcomes_released_at has passed.
+ALTER TABLE email_queue
+    ADD COLUMN release_campaign_id BIGINT REFERENCES campaigns(id) ON DELETE CASCADE;
+
+CREATE INDEX IDX_email_queue_release_campaign on email_queue(release_campaign_id);
@@ -0,0 +1,26 @@
+ALTER TYPE offer_status ADD VALUE 'Expired';
+
+-- Whether offers made from a role's waitlist are sent straight away or left as drafts.
+ALTER TABLE campaign_roles
+    ADD COLUMN waitlist_auto_send BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TABLE campaign_role_waitlist (
+    campaign_role_id BIGINT NOT NULL,
+    application_id BIGINT NOT NULL,
+    rank INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (campaign_role_id, application_id),
+    UNIQUE (campaign_role_id, rank),
+    CONSTRAINT FK_campaign_role_waitlist_campaign_roles
+        FOREIGN KEY(campaign_role_id)
+            REFERENCES campaign_roles(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT FK_campaign_role_waitlist_applications
+        FOREIGN KEY(application_id)
+            REFERENCES applications(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+);
+
+CREATE INDEX IDX_offers_status_expiry on offers(status, expiry);
@@ -0,0 +1,17 @@
+-- How many hours before an offer expires each reminder is sent, and the template used.
+ALTER TABLE campaigns
+    ADD COLUMN offer_reminder_hours INTEGER[] NOT NULL DEFAULT '{}',
+    ADD COLUMN offer_reminder_template_id BIGINT REFERENCES email_templates(id) ON DELETE SET NULL;
+
+CREATE TABLE offer_reminders (
+    offer_id BIGINT NOT NULL,
+    hours_before INTEGER NOT NULL,
+    sent_email_id BIGINT REFERENCES sent_emails(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (offer_id, hours_before),
+    CONSTRAINT FK_offer_reminders_offers
+        FOREIGN KEY(offer_id)
+            REFERENCES offers(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Whether applicants who accept an offer are added to the organisation as members.
+ALTER TABLE campaigns
+    ADD COLUMN onboard_accepted_applicants BOOLEAN NOT NULL DEFAULT false;
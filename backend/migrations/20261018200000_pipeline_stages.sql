@@ -0,0 +1,73 @@
+-- Ordered recruitment stages a campaign's applications move through.
+CREATE TABLE campaign_stages (
+    id BIGINT PRIMARY KEY,
+    campaign_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    -- The fixed status applications and roles report while in this stage.
+    status application_status NOT NULL DEFAULT 'Pending',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT FK_campaign_stages_campaign
+        FOREIGN KEY(campaign_id)
+            REFERENCES campaigns(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    -- Deferred so stages can be reordered and renamed in a single update.
+    UNIQUE (campaign_id, position) DEFERRABLE INITIALLY DEFERRED,
+    UNIQUE (campaign_id, name) DEFERRABLE INITIALLY DEFERRED
+);
+
+-- The stages an application in a stage is allowed to move to.
+CREATE TABLE campaign_stage_transitions (
+    from_stage_id BIGINT NOT NULL,
+    to_stage_id BIGINT NOT NULL,
+    PRIMARY KEY (from_stage_id, to_stage_id),
+    CONSTRAINT FK_campaign_stage_transitions_from
+        FOREIGN KEY(from_stage_id)
+            REFERENCES campaign_stages(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT FK_campaign_stage_transitions_to
+        FOREIGN KEY(to_stage_id)
+            REFERENCES campaign_stages(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+);
+
+ALTER TABLE applications
+    ADD COLUMN stage_id BIGINT REFERENCES campaign_stages(id) ON DELETE SET NULL;
+
+ALTER TABLE application_roles
+    ADD COLUMN stage_id BIGINT REFERENCES campaign_stages(id) ON DELETE SET NULL;
+
+-- Every stage move. Stage names are copied so history survives stages being removed.
+CREATE TABLE application_stage_history (
+    id BIGSERIAL PRIMARY KEY,
+    application_id BIGINT NOT NULL,
+    -- NULL when the application as a whole was moved.
+    campaign_role_id BIGINT,
+    from_stage_id BIGINT REFERENCES campaign_stages(id) ON DELETE SET NULL,
+    from_stage_name TEXT,
+    to_stage_id BIGINT REFERENCES campaign_stages(id) ON DELETE SET NULL,
+    to_stage_name TEXT NOT NULL,
+    moved_by BIGINT NOT NULL,
+    moved_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT FK_application_stage_history_application
+        FOREIGN KEY(application_id)
+            REFERENCES applications(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT FK_application_stage_history_role
+        FOREIGN KEY(campaign_role_id)
+            REFERENCES campaign_roles(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT FK_application_stage_history_user
+        FOREIGN KEY(moved_by)
+            REFERENCES users(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+);
+
+CREATE INDEX IDX_application_stage_history_application
+    ON application_stage_history(application_id, moved_at);
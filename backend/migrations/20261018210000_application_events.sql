@@ -0,0 +1,46 @@
+CREATE TYPE application_event_type AS ENUM (
+    'Submitted',
+    'StatusChanged',
+    'PrivateStatusChanged',
+    'RoleStatusChanged',
+    'StageChanged',
+    'RatingCreated',
+    'RatingUpdated',
+    'CommentCreated',
+    'OfferCreated',
+    'OfferSent',
+    'OfferAccepted',
+    'OfferDeclined',
+    'OfferExpired'
+);
+
+-- Append-only log of everything that happens to an application.
+CREATE TABLE application_events (
+    id BIGSERIAL PRIMARY KEY,
+    application_id BIGINT NOT NULL,
+    event_type application_event_type NOT NULL,
+    -- The user who caused the event, or NULL for background jobs.
+    actor_id BIGINT,
+    -- The role the event concerns, if any.
+    campaign_role_id BIGINT,
+    data JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT FK_application_events_application
+        FOREIGN KEY(application_id)
+            REFERENCES applications(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT FK_application_events_actor
+        FOREIGN KEY(actor_id)
+            REFERENCES users(id)
+            ON DELETE SET NULL
+            ON UPDATE CASCADE,
+    CONSTRAINT FK_application_events_role
+        FOREIGN KEY(campaign_role_id)
+            REFERENCES campaign_roles(id)
+            ON DELETE SET NULL
+            ON UPDATE CASCADE
+);
+
+CREATE INDEX IDX_application_events_application
+    ON application_events(application_id, created_at);
@@ -0,0 +1,4 @@
+-- When private statuses were last promoted to public statuses for the campaign's
+-- outcome release. Promotion runs again if the release is rescheduled past this.
+ALTER TABLE campaigns
+    ADD COLUMN outcomes_promoted_at TIMESTAMPTZ;
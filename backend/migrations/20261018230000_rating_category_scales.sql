@@ -0,0 +1,20 @@
+-- Rating categories carry a weight in the weighted total, a bounded scale, and
+-- optional descriptors explaining what each score on the scale means.
+ALTER TABLE campaign_rating_categories
+    ADD COLUMN weight DOUBLE PRECISION NOT NULL DEFAULT 1,
+    ADD COLUMN min_rating INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN max_rating INTEGER NOT NULL DEFAULT 5,
+    ADD CONSTRAINT CK_campaign_rating_categories_weight CHECK (weight >= 0),
+    ADD CONSTRAINT CK_campaign_rating_categories_scale CHECK (min_rating < max_rating);
+
+CREATE TABLE campaign_rating_category_rubrics (
+    campaign_rating_category_id BIGINT NOT NULL,
+    score INTEGER NOT NULL,
+    description TEXT NOT NULL,
+    PRIMARY KEY (campaign_rating_category_id, score),
+    CONSTRAINT FK_campaign_rating_category_rubrics_category
+        FOREIGN KEY(campaign_rating_category_id)
+            REFERENCES campaign_rating_categories(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+);
@@ -0,0 +1,68 @@
+-- Reviewers assigned to every application for a role.
+CREATE TABLE reviewer_role_assignments (
+    campaign_role_id BIGINT NOT NULL,
+    reviewer_id BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (campaign_role_id, reviewer_id),
+    CONSTRAINT FK_reviewer_role_assignments_campaign_roles
+        FOREIGN KEY(campaign_role_id)
+            REFERENCES campaign_roles(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT FK_reviewer_role_assignments_users
+        FOREIGN KEY(reviewer_id)
+            REFERENCES users(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+);
+
+CREATE INDEX IDX_reviewer_role_assignments_reviewer on reviewer_role_assignments(reviewer_id);
+
+-- Reviewers assigned to specific applications, either by an admin or by auto-assign.
+CREATE TABLE reviewer_application_assignments (
+    application_id BIGINT NOT NULL,
+    reviewer_id BIGINT NOT NULL,
+    auto_assigned BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (application_id, reviewer_id),
+    CONSTRAINT FK_reviewer_application_assignments_applications
+        FOREIGN KEY(application_id)
+            REFERENCES applications(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT FK_reviewer_application_assignments_users
+        FOREIGN KEY(reviewer_id)
+            REFERENCES users(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+);
+
+CREATE INDEX IDX_reviewer_application_assignments_reviewer on reviewer_application_assignments(reviewer_id);
+
+-- Reviewers who must not review an application because of a conflict of interest.
+CREATE TABLE review_conflicts (
+    id BIGINT PRIMARY KEY,
+    application_id BIGINT NOT NULL,
+    reviewer_id BIGINT NOT NULL,
+    note TEXT,
+    declared_by BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT UQ_review_conflicts_application_reviewer UNIQUE (application_id, reviewer_id),
+    CONSTRAINT FK_review_conflicts_applications
+        FOREIGN KEY(application_id)
+            REFERENCES applications(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT FK_review_conflicts_reviewers
+        FOREIGN KEY(reviewer_id)
+            REFERENCES users(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT FK_review_conflicts_declared_by
+        FOREIGN KEY(declared_by)
+            REFERENCES users(id)
+            ON DELETE SET NULL
+            ON UPDATE CASCADE
+);
+
+CREATE INDEX IDX_review_conflicts_reviewer on review_conflicts(reviewer_id);
@@ -0,0 +1,5 @@
+-- Blind review hides applicant identity from reviewer-facing endpoints. Applications
+-- that reach the unblind stage, or any stage after it, are shown with identity again.
+ALTER TABLE campaigns
+    ADD COLUMN blind_review BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN unblind_stage_id BIGINT REFERENCES campaign_stages(id) ON DELETE SET NULL;
@@ -0,0 +1,29 @@
+-- Reviewers who must not review an application because of a conflict of interest.
+-- The table is created with reviewer assignments, so this only covers databases
+-- that ran the reviewer assignments migration without it.
+CREATE TABLE IF NOT EXISTS review_conflicts (
+    id BIGINT PRIMARY KEY,
+    application_id BIGINT NOT NULL,
+    reviewer_id BIGINT NOT NULL,
+    note TEXT,
+    declared_by BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT UQ_review_conflicts_application_reviewer UNIQUE (application_id, reviewer_id),
+    CONSTRAINT FK_review_conflicts_applications
+        FOREIGN KEY(application_id)
+            REFERENCES applications(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT FK_review_conflicts_reviewers
+        FOREIGN KEY(reviewer_id)
+            REFERENCES users(id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT FK_review_conflicts_declared_by
+        FOREIGN KEY(declared_by)
+            REFERENCES users(id)
+            ON DELETE SET NULL
+            ON UPDATE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS IDX_review_conflicts_reviewer on review_conflicts(reviewer_id);
@@ -0,0 +1,39 @@
+-- Under blind review each applicant is shown as "Applicant N", numbered in order of
+-- application within their campaign, so no two applicants in a campaign share a
+-- pseudonym.
+ALTER TABLE campaigns ADD COLUMN next_pseudonym_number INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE applications ADD COLUMN pseudonym_number INTEGER;
+
+UPDATE applications a
+SET pseudonym_number = numbered.number
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY campaign_id ORDER BY created_at, id) AS number
+    FROM applications
+) numbered
+WHERE numbered.id = a.id;
+
+UPDATE campaigns c
+SET next_pseudonym_number = COALESCE(
+    (SELECT MAX(pseudonym_number) FROM applications WHERE campaign_id = c.id), 0
+) + 1;
+
+CREATE OR REPLACE FUNCTION assign_application_pseudonym_number()
+RETURNS TRIGGER AS $$
+BEGIN
+    -- Updating the campaign row serialises concurrent applications to a campaign.
+    UPDATE campaigns
+    SET next_pseudonym_number = next_pseudonym_number + 1
+    WHERE id = NEW.campaign_id
+    RETURNING next_pseudonym_number - 1 INTO NEW.pseudonym_number;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_assign_application_pseudonym_number
+BEFORE INSERT ON applications
+FOR EACH ROW
+EXECUTE FUNCTION assign_application_pseudonym_number();
+
+ALTER TABLE applications ALTER COLUMN pseudonym_number SET NOT NULL;
+ALTER TABLE applications
+    ADD CONSTRAINT UQ_applications_campaign_pseudonym UNIQUE (campaign_id, pseudonym_number);
@@ -0,0 +1,2 @@
+-- Offers whose held emails are cancelled before the outcome release are withdrawn.
+ALTER TYPE application_event_type ADD VALUE 'OfferWithdrawn';
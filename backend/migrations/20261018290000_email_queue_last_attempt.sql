@@ -0,0 +1,2 @@
+-- When each queued email was last tried, shown alongside failed emails.
+ALTER TABLE email_queue ADD COLUMN last_attempt_at TIMESTAMPTZ;
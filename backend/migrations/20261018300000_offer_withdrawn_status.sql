@@ -0,0 +1,2 @@
+-- Offers withdrawn before the applicant could reply are kept for their history.
+ALTER TYPE offer_status ADD VALUE 'Withdrawn';
@@ -0,0 +1,9 @@
+-- Stage history outlives the admins who moved applications, as application events do.
+ALTER TABLE application_stage_history ALTER COLUMN moved_by DROP NOT NULL;
+
+ALTER TABLE application_stage_history DROP CONSTRAINT FK_application_stage_history_user;
+ALTER TABLE application_stage_history ADD CONSTRAINT FK_application_stage_history_user
+    FOREIGN KEY(moved_by)
+        REFERENCES users(id)
+        ON DELETE SET NULL
+        ON UPDATE CASCADE;
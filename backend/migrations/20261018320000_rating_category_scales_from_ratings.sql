@@ -0,0 +1,12 @@
+-- Categories that existed before scales were added were all given a 1..5 scale.
+-- Widen each one to cover the ratings already given in it, so legacy ratings stay
+-- within their category's scale.
+UPDATE campaign_rating_categories c
+SET min_rating = LEAST(c.min_rating, r.min_rating),
+    max_rating = GREATEST(c.max_rating, r.max_rating)
+FROM (
+    SELECT campaign_rating_category_id, MIN(rating) AS min_rating, MAX(rating) AS max_rating
+    FROM application_rating_category_ratings
+    GROUP BY campaign_rating_category_id
+) r
+WHERE r.campaign_rating_category_id = c.id;
@@ -5,11 +5,16 @@
 //! - Updating application status and roles
 //! - Submitting applications
 //! - Managing application ratings
+//! - Viewing application timelines
+//! - Viewing released outcomes as an applicant
 
 use crate::models::app::{AppMessage, AppState};
+use crate::models::applicant_status::ApplicantStatus;
 use crate::models::application::{
     Application, ApplicationRoleUpdate, ApplicationStatus, OpenApplicationByApplicationId,
 };
+use crate::models::application_event::ApplicationEvent;
+use crate::models::application_listing::ApplicationListQuery;
 use crate::models::auth::{
     ApplicationAdmin, ApplicationOwner, ApplicationOwnerOrReviewer,
     ApplicationReviewerGivenApplicationId, AuthUser, CampaignAdmin,
@@ -17,7 +22,7 @@ use crate::models::auth::{
 use crate::models::error::ChaosError;
 use crate::models::rating::{NewRating, Rating};
 use crate::models::transaction::DBTransaction;
-use axum::extract::{Json, Path, State};
+use axum::extract::{Json, Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use serde_json::json;
@@ -138,7 +143,7 @@ impl ApplicationHandler {
     ///
     /// * `state` - The application state
     /// * `application_id` - The ID of the application to update
-    /// * `_admin` - The authenticated user (must be an application admin)
+    /// * `admin` - The authenticated user (must be an application admin)
     /// * `data` - The new application status
     ///
     /// # Returns
@@ -146,11 +151,11 @@ impl ApplicationHandler {
     /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
     pub async fn set_status(
         Path(application_id): Path<i64>,
-        _admin: ApplicationAdmin,
+        admin: ApplicationAdmin,
         mut transaction: DBTransaction<'_>,
         Json(data): Json<ApplicationStatus>,
     ) -> Result<impl IntoResponse, ChaosError> {
-        Application::set_status(application_id, data, &mut transaction.tx).await?;
+        Application::set_status(application_id, data, admin.user_id, &mut transaction.tx).await?;
         transaction.tx.commit().await?;
         Ok(AppMessage::OkMessage("Status successfully updated"))
     }
@@ -163,7 +168,7 @@ impl ApplicationHandler {
     ///
     /// * `state` - The application state
     /// * `application_id` - The ID of the application to update
-    /// * `_admin` - The authenticated user (must be an application admin)
+    /// * `admin` - The authenticated user (must be an application admin)
     /// * `data` - The new private status
     ///
     /// # Returns
@@ -171,11 +176,12 @@ impl ApplicationHandler {
     /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
     pub async fn set_private_status(
         Path(application_id): Path<i64>,
-        _admin: ApplicationAdmin,
+        admin: ApplicationAdmin,
         mut transaction: DBTransaction<'_>,
         Json(data): Json<ApplicationStatus>,
     ) -> Result<impl IntoResponse, ChaosError> {
-        Application::set_private_status(application_id, data, &mut transaction.tx).await?;
+        Application::set_private_status(application_id, data, admin.user_id, &mut transaction.tx)
+            .await?;
         transaction.tx.commit().await?;
         Ok(AppMessage::OkMessage("Private Status successfully updated"))
     }
@@ -202,6 +208,29 @@ impl ApplicationHandler {
         Ok(Json(applications))
     }
 
+    /// Retrieves the released outcomes of all applications for the current user.
+    ///
+    /// Outcomes are hidden until each campaign's outcome release, and released offers
+    /// that can still be replied to are included so the applicant can accept or
+    /// decline them.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The authenticated user
+    /// * `transaction` - Database transaction
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - List of application outcomes or error
+    pub async fn get_status_from_curr_user(
+        user: AuthUser,
+        mut transaction: DBTransaction<'_>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let statuses = ApplicantStatus::get_for_user(user.user_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+        Ok((StatusCode::OK, Json(statuses)))
+    }
+
     /// Retrieves all roles associated with a specific application.
     ///
     /// This handler allows application owners to view all roles they have applied for
@@ -420,28 +449,82 @@ impl ApplicationHandler {
         Ok((StatusCode::OK, Json(ratings)))
     }
 
-    /// Retrieves the average ratings for all users in an application.
+    /// Retrieves the ratings for applications in a campaign.
     ///
-    /// This handler allows application reviewers to view the average ratings for all users in an application.
+    /// This handler allows campaign admins to view the ratings of applications, filtered,
+    /// sorted and paged by the query parameters. The cursor for the next page, if any,
+    /// is returned in the `X-Next-Cursor` header.
     ///
     /// # Arguments
     ///
-    /// * `_user` - The authenticated user (must be an application reviewer)
-    /// * `application_id` - The ID of the application
+    /// * `admin` - The authenticated user (must be a campaign admin)
+    /// * `campaign_id` - The ID of the campaign
+    /// * `query` - Filters, sort and page to list
     /// * `transaction` - Database transaction
     ///
     /// # Returns
     ///
     /// * `Result<impl IntoResponse, ChaosError>` - List of average ratings or error
     pub async fn get_application_ratings_summary(
-        _: CampaignAdmin,
+        admin: CampaignAdmin,
         Path(campaign_id): Path<i64>,
+        Query(query): Query<ApplicationListQuery>,
+        mut transaction: DBTransaction<'_>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let (avg_applications_ratings, page) = Application::get_application_ratings_summary(
+            campaign_id,
+            admin.user_id,
+            &query,
+            &mut transaction.tx,
+        )
+        .await?;
+        transaction.tx.commit().await?;
+
+        Ok((page.headers(), Json(avg_applications_ratings)))
+    }
+
+    /// Retrieves an application's full timeline of events.
+    ///
+    /// # Arguments
+    ///
+    /// * `application_id` - The ID of the application
+    /// * `_user` - The authenticated user (must be an application reviewer)
+    /// * `transaction` - Database transaction
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - List of events or error
+    pub async fn get_timeline(
+        Path(application_id): Path<i64>,
+        _user: ApplicationReviewerGivenApplicationId,
+        mut transaction: DBTransaction<'_>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let events = ApplicationEvent::get_timeline(application_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(events)))
+    }
+
+    /// Retrieves the part of an application's timeline that its applicant can see.
+    ///
+    /// # Arguments
+    ///
+    /// * `application_id` - The ID of the application
+    /// * `_user` - The authenticated user (must be the application owner)
+    /// * `transaction` - Database transaction
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - List of events or error
+    pub async fn get_applicant_timeline(
+        Path(application_id): Path<i64>,
+        _user: ApplicationOwner,
         mut transaction: DBTransaction<'_>,
     ) -> Result<impl IntoResponse, ChaosError> {
-        let avg_applications_ratings =
-            Application::get_application_ratings_summary(campaign_id, &mut transaction.tx).await?;
+        let events =
+            ApplicationEvent::get_applicant_timeline(application_id, &mut transaction.tx).await?;
         transaction.tx.commit().await?;
 
-        Ok(Json(avg_applications_ratings))
+        Ok((StatusCode::OK, Json(events)))
     }
 }
@@ -0,0 +1,163 @@
+//! Availability handler for the Chaos application.
+//!
+//! This module provides HTTP request handlers for managing interview availability, including:
+//! - Submitting and replacing applicant and interviewer availability grids
+//! - Retrieving and removing a user's own availability
+//! - Retrieving the aggregated availability of a campaign
+
+use crate::models::app::{AppMessage, AppState};
+use crate::models::auth::{AuthUser, CampaignAdmin, CampaignApplicant, CampaignOrgMember};
+use crate::models::availabilities::{Availabilities, AvailabilityUpdate};
+use crate::models::error::ChaosError;
+use crate::models::transaction::DBTransaction;
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+
+/// Handler for availability-related HTTP requests.
+pub struct AvailabilityHandler;
+
+impl AvailabilityHandler {
+    /// Retrieves the current user's availability for a campaign.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `user` - The authenticated user
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - List of availability slots or error
+    pub async fn get_own(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        user: AuthUser,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let availabilities =
+            Availabilities::get_user_availabilities(user.user_id, campaign_id, &mut transaction.tx)
+                .await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(availabilities)))
+    }
+
+    /// Removes some of the current user's availability slots for a campaign.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `user` - The authenticated user
+    /// * `data` - The slots to remove
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn delete_own(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        user: AuthUser,
+        Json(data): Json<AvailabilityUpdate>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        Availabilities::delete_availabilities(
+            user.user_id,
+            campaign_id,
+            data.availabilities,
+            &mut transaction.tx,
+        )
+        .await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully removed availability"))
+    }
+
+    /// Replaces an applicant's availability for a campaign.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `state` - The application state
+    /// * `campaign_id` - The ID of the campaign
+    /// * `user` - The authenticated user (must have applied to the campaign)
+    /// * `data` - The complete new availability grid
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn update_applicant(
+        mut transaction: DBTransaction<'_>,
+        State(mut state): State<AppState>,
+        Path(campaign_id): Path<i64>,
+        user: CampaignApplicant,
+        Json(data): Json<AvailabilityUpdate>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        Availabilities::replace_availabilities(
+            user.user_id,
+            campaign_id,
+            data.availabilities,
+            &mut state.snowflake_generator,
+            &mut transaction.tx,
+        )
+        .await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully updated availability"))
+    }
+
+    /// Replaces an interviewer's availability for a campaign.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `state` - The application state
+    /// * `campaign_id` - The ID of the campaign
+    /// * `user` - The authenticated user (must be a member of the campaign's organisation)
+    /// * `data` - The complete new availability grid
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn update_interviewer(
+        mut transaction: DBTransaction<'_>,
+        State(mut state): State<AppState>,
+        Path(campaign_id): Path<i64>,
+        user: CampaignOrgMember,
+        Json(data): Json<AvailabilityUpdate>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        Availabilities::replace_availabilities(
+            user.user_id,
+            campaign_id,
+            data.availabilities,
+            &mut state.snowflake_generator,
+            &mut transaction.tx,
+        )
+        .await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully updated availability"))
+    }
+
+    /// Retrieves the aggregated availability of all users in a campaign.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Aggregated availability slots or error
+    pub async fn get_by_campaign(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let slots =
+            Availabilities::get_campaign_availabilities(campaign_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(slots)))
+    }
+}
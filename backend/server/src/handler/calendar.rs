@@ -0,0 +1,109 @@
+//! Calendar handler for the Chaos application.
+//!
+//! This module provides HTTP request handlers for users' interview calendar feeds, including:
+//! - Creating, retrieving and revoking calendar feed tokens
+//! - Serving the read-only `.ics` feed for a token
+
+use crate::models::app::AppMessage;
+use crate::models::auth::AuthUser;
+use crate::models::calendar::{render_feed, CalendarFeed};
+use crate::models::error::ChaosError;
+use crate::models::interview::InterviewTimeslot;
+use crate::models::transaction::DBTransaction;
+use axum::extract::{Json, Path};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+
+/// Handler for calendar-related HTTP requests.
+pub struct CalendarHandler;
+
+impl CalendarHandler {
+    /// Retrieves the current user's calendar feed token.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `user` - The authenticated user
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Feed token or error
+    pub async fn get_token(
+        mut transaction: DBTransaction<'_>,
+        user: AuthUser,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let token = CalendarFeed::get_token(user.user_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(token)))
+    }
+
+    /// Creates a new calendar feed token for the current user, revoking any previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `user` - The authenticated user
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - New feed token or error
+    pub async fn create_token(
+        mut transaction: DBTransaction<'_>,
+        user: AuthUser,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let token = CalendarFeed::create_token(user.user_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(token)))
+    }
+
+    /// Revokes the current user's calendar feed token.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `user` - The authenticated user
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn revoke_token(
+        mut transaction: DBTransaction<'_>,
+        user: AuthUser,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        CalendarFeed::revoke_token(user.user_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully revoked calendar feed"))
+    }
+
+    /// Serves the interview calendar feed belonging to a token.
+    ///
+    /// This handler is not behind login, as calendar apps fetch it without the
+    /// user's cookies. The token itself grants read-only access to the feed.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `token` - The calendar feed token
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - The `.ics` feed or error
+    pub async fn get_feed(
+        mut transaction: DBTransaction<'_>,
+        Path(token): Path<String>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let user_id = CalendarFeed::get_user_id(&token, &mut transaction.tx).await?;
+        let events =
+            InterviewTimeslot::get_calendar_events_by_user(user_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+            render_feed("Chaos interviews", &events),
+        ))
+    }
+}
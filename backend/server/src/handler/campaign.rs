@@ -6,13 +6,16 @@
 //! - Application management
 //! - Offer management
 //! - Banner image handling
+//! - Blind review settings
 
 use crate::models;
 use crate::models::app::{AppMessage, AppState};
 use crate::models::application::Application;
 use crate::models::application::NewApplication;
+use crate::models::application_listing::ApplicationListQuery;
 use crate::models::auth::AuthUser;
 use crate::models::auth::CampaignAdmin;
+use crate::models::blind_review::{BlindReview, BlindReviewSettings};
 use crate::models::campaign::{
     AttachmentResponse, Campaign, CampaignAttachment, CampaignDetailsResponse, NewAttachment,
     OpenCampaign,
@@ -22,7 +25,7 @@ use crate::models::offer::Offer;
 use crate::models::role::{Role, RoleUpdate};
 use crate::models::storage::Storage;
 use crate::models::transaction::DBTransaction;
-use axum::extract::{Json, Path, State};
+use axum::extract::{Json, Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 
@@ -316,14 +319,17 @@ impl CampaignHandler {
         Ok(AppMessage::OkMessage("Successfully created application"))
     }
 
-    /// Retrieves all applications for a campaign.
+    /// Retrieves applications for a campaign.
     ///
-    /// This handler allows campaign admins to view all applications.
+    /// This handler allows campaign admins to view applications, filtered, sorted and
+    /// paged by the query parameters. The cursor for the next page, if any, is
+    /// returned in the `X-Next-Cursor` header.
     ///
     /// # Arguments
     ///
     /// * `id` - The ID of the campaign
-    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `admin` - The authenticated user (must be a campaign admin)
+    /// * `query` - Filters, sort and page to list
     /// * `transaction` - Database transaction
     ///
     /// # Returns
@@ -332,12 +338,14 @@ impl CampaignHandler {
     pub async fn get_applications(
         Path(id): Path<i64>,
         admin: CampaignAdmin,
+        Query(query): Query<ApplicationListQuery>,
         mut transaction: DBTransaction<'_>,
     ) -> Result<impl IntoResponse, ChaosError> {
-        let applications =
-            Application::get_from_campaign_id(id, admin.user_id, &mut transaction.tx).await?;
+        let (applications, page) =
+            Application::get_from_campaign_id(id, admin.user_id, &query, &mut transaction.tx)
+                .await?;
         transaction.tx.commit().await?;
-        Ok((StatusCode::OK, Json(applications)))
+        Ok((StatusCode::OK, page.headers(), Json(applications)))
     }
 
     /// Creates a new offer for an application.
@@ -524,4 +532,55 @@ impl CampaignHandler {
         transaction.tx.commit().await?;
         Ok(())
     }
+
+    /// Retrieves a campaign's blind review settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Blind review settings or error
+    pub async fn get_blind_review_settings(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let settings = BlindReview::get_settings(campaign_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(settings)))
+    }
+
+    /// Updates a campaign's blind review settings.
+    ///
+    /// Turning blind review off, or choosing an unblind stage, reveals applicant
+    /// identity to reviewers.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `settings` - The new blind review settings
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn update_blind_review_settings(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        Json(settings): Json<BlindReviewSettings>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        BlindReview::update_settings(campaign_id, settings, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage(
+            "Successfully updated blind review settings",
+        ))
+    }
 }
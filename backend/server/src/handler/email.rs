@@ -0,0 +1,85 @@
+//! Email handler for the Chaos application.
+//!
+//! This module provides HTTP request handlers for managing the email queue, including:
+//! - Listing emails that failed to send
+//! - Re-queuing failed emails
+//! - Viewing the emails sent about an application
+
+use crate::models::app::AppMessage;
+use crate::models::auth::{ApplicationAdmin, SuperUser};
+use crate::models::email::{EmailQueue, SentEmailLog};
+use crate::models::error::ChaosError;
+use crate::models::transaction::DBTransaction;
+use axum::extract::{Json, Path};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+
+/// Handler for email-related HTTP requests.
+pub struct EmailHandler;
+
+impl EmailHandler {
+    /// Retrieves every email that has exhausted its retries.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `_user` - The authenticated user (must be a super user)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - List of failed emails or error
+    pub async fn get_failed(
+        mut transaction: DBTransaction<'_>,
+        _user: SuperUser,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let emails = EmailQueue::get_failed(&mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(emails)))
+    }
+
+    /// Moves a failed email back into the queue to be sent again.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `id` - The ID of the failed email
+    /// * `_user` - The authenticated user (must be a super user)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn requeue(
+        mut transaction: DBTransaction<'_>,
+        Path(id): Path<i32>,
+        _user: SuperUser,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        EmailQueue::requeue(id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully re-queued email"))
+    }
+
+    /// Retrieves every email the campaign has sent, or tried to send, about an application.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `application_id` - The ID of the application
+    /// * `_admin` - The authenticated user (must be an application admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - List of logged emails or error
+    pub async fn get_by_application(
+        mut transaction: DBTransaction<'_>,
+        Path(application_id): Path<i64>,
+        _admin: ApplicationAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let emails =
+            SentEmailLog::get_all_by_application(application_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(emails)))
+    }
+}
@@ -0,0 +1,439 @@
+//! Interview handler for the Chaos application.
+//!
+//! This module provides HTTP request handlers for managing interviews, including:
+//! - Creating, updating and deleting interview timeslots
+//! - Assigning interviewers and applicants to timeslots
+//! - Booking and cancelling timeslots as an applicant
+//! - Assigning interviewers to roles
+//! - Proposing and committing automatically generated schedules
+
+use crate::models::app::{AppMessage, AppState};
+use crate::models::auth::{
+    AuthUser, CampaignAdmin, InterviewTimeslotAdmin, RoleAdmin, RoleApplicantOrAdmin,
+};
+use crate::models::error::ChaosError;
+use crate::models::interview::{
+    InterviewBooking, InterviewTimeslot, InterviewTimeslotAssignment, NewInterviewTimeslot,
+    RoleInterviewer, RoleInterviewerUpdate,
+};
+use crate::models::interview_schedule::{InterviewScheduler, ScheduleCommit, ScheduleOptions};
+use crate::models::transaction::DBTransaction;
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+
+/// Handler for interview-related HTTP requests.
+pub struct InterviewHandler;
+
+impl InterviewHandler {
+    /// Creates a new interview timeslot in a campaign.
+    ///
+    /// This handler allows campaign admins to create interview timeslots.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `state` - The application state
+    /// * `campaign_id` - The ID of the campaign to create the timeslot in
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `data` - The timeslot details
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn create_timeslot(
+        mut transaction: DBTransaction<'_>,
+        State(mut state): State<AppState>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        Json(data): Json<NewInterviewTimeslot>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        InterviewTimeslot::create(
+            campaign_id,
+            data,
+            &mut state.snowflake_generator,
+            &mut transaction.tx,
+        )
+        .await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully created interview timeslot"))
+    }
+
+    /// Retrieves all interview timeslots in a campaign.
+    ///
+    /// This handler allows campaign admins to view every timeslot along with
+    /// the interviewers and applicants assigned to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - List of timeslots or error
+    pub async fn get_timeslots_by_campaign(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let timeslots =
+            InterviewTimeslot::get_all_by_campaign(campaign_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(timeslots)))
+    }
+
+    /// Retrieves the interview timeslots that can still be booked for a role.
+    ///
+    /// This handler allows applicants to view open timeslots for a role they applied to.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `role_id` - The ID of the role
+    /// * `_user` - The authenticated user (must have applied to the role or be an admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - List of open timeslots or error
+    pub async fn get_open_timeslots_by_role(
+        mut transaction: DBTransaction<'_>,
+        Path(role_id): Path<i64>,
+        _user: RoleApplicantOrAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let timeslots = InterviewTimeslot::get_open_by_role(role_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(timeslots)))
+    }
+
+    /// Retrieves the interview timeslots the current user has booked in a campaign.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `user` - The authenticated user
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - List of booked timeslots or error
+    pub async fn get_bookings(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        user: AuthUser,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let timeslots =
+            InterviewTimeslot::get_booked_by_user(campaign_id, user.user_id, &mut transaction.tx)
+                .await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(timeslots)))
+    }
+
+    /// Retrieves an interview timeslot and its participants.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `timeslot_id` - The ID of the timeslot
+    /// * `_admin` - The authenticated user (must be a timeslot admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Timeslot details or error
+    pub async fn get_timeslot(
+        mut transaction: DBTransaction<'_>,
+        Path(timeslot_id): Path<i64>,
+        _admin: InterviewTimeslotAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let timeslot = InterviewTimeslot::get(timeslot_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(timeslot)))
+    }
+
+    /// Updates an interview timeslot.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `timeslot_id` - The ID of the timeslot to update
+    /// * `_admin` - The authenticated user (must be a timeslot admin)
+    /// * `data` - The new timeslot details
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn update_timeslot(
+        mut transaction: DBTransaction<'_>,
+        Path(timeslot_id): Path<i64>,
+        _admin: InterviewTimeslotAdmin,
+        Json(data): Json<NewInterviewTimeslot>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        InterviewTimeslot::update(timeslot_id, data, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully updated interview timeslot"))
+    }
+
+    /// Deletes an interview timeslot.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `timeslot_id` - The ID of the timeslot to delete
+    /// * `_admin` - The authenticated user (must be a timeslot admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn delete_timeslot(
+        mut transaction: DBTransaction<'_>,
+        Path(timeslot_id): Path<i64>,
+        _admin: InterviewTimeslotAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        InterviewTimeslot::delete(timeslot_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully deleted interview timeslot"))
+    }
+
+    /// Assigns an interviewer or applicant to an interview timeslot.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `timeslot_id` - The ID of the timeslot
+    /// * `_admin` - The authenticated user (must be a timeslot admin)
+    /// * `assignment` - The user to assign and their role in the interview
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn assign_user(
+        mut transaction: DBTransaction<'_>,
+        Path(timeslot_id): Path<i64>,
+        _admin: InterviewTimeslotAdmin,
+        Json(assignment): Json<InterviewTimeslotAssignment>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        InterviewTimeslot::assign_user(timeslot_id, assignment, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully assigned user to interview timeslot"))
+    }
+
+    /// Removes an interviewer or applicant from an interview timeslot.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `timeslot_id` - The ID of the timeslot
+    /// * `user_id` - The ID of the user to remove
+    /// * `_admin` - The authenticated user (must be a timeslot admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn remove_user(
+        mut transaction: DBTransaction<'_>,
+        Path((timeslot_id, user_id)): Path<(i64, i64)>,
+        _admin: InterviewTimeslotAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        InterviewTimeslot::remove_user(timeslot_id, user_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully removed user from interview timeslot"))
+    }
+
+    /// Books an open interview timeslot for the current user.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `timeslot_id` - The ID of the timeslot to book
+    /// * `user` - The authenticated user
+    /// * `booking` - The role the user is interviewing for
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn book(
+        mut transaction: DBTransaction<'_>,
+        Path(timeslot_id): Path<i64>,
+        user: AuthUser,
+        Json(booking): Json<InterviewBooking>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        InterviewTimeslot::book(
+            timeslot_id,
+            user.user_id,
+            booking.role_id,
+            &mut transaction.tx,
+        )
+        .await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully booked interview timeslot"))
+    }
+
+    /// Cancels the current user's booking of an interview timeslot.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `timeslot_id` - The ID of the booked timeslot
+    /// * `user` - The authenticated user
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn cancel_booking(
+        mut transaction: DBTransaction<'_>,
+        Path(timeslot_id): Path<i64>,
+        user: AuthUser,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        InterviewTimeslot::cancel_booking(timeslot_id, user.user_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully cancelled interview booking"))
+    }
+
+    /// Retrieves the interviewers assigned to a role.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `role_id` - The ID of the role
+    /// * `_admin` - The authenticated user (must be a role admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - List of interviewers or error
+    pub async fn get_role_interviewers(
+        mut transaction: DBTransaction<'_>,
+        Path(role_id): Path<i64>,
+        _admin: RoleAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let interviewers = RoleInterviewer::get_all_by_role(role_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(interviewers)))
+    }
+
+    /// Assigns an organisation member as an interviewer for a role.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `role_id` - The ID of the role
+    /// * `_admin` - The authenticated user (must be a role admin)
+    /// * `request_body` - The interviewer to add
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn add_role_interviewer(
+        mut transaction: DBTransaction<'_>,
+        Path(role_id): Path<i64>,
+        _admin: RoleAdmin,
+        Json(request_body): Json<RoleInterviewerUpdate>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        RoleInterviewer::add(role_id, request_body.user_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage(
+            "Successfully added interviewer to role",
+        ))
+    }
+
+    /// Removes an interviewer from a role.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `role_id` - The ID of the role
+    /// * `_admin` - The authenticated user (must be a role admin)
+    /// * `request_body` - The interviewer to remove
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn remove_role_interviewer(
+        mut transaction: DBTransaction<'_>,
+        Path(role_id): Path<i64>,
+        _admin: RoleAdmin,
+        Json(request_body): Json<RoleInterviewerUpdate>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        RoleInterviewer::remove(role_id, request_body.user_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage(
+            "Successfully removed interviewer from role",
+        ))
+    }
+
+    /// Generates a proposed interview schedule for a campaign.
+    ///
+    /// Nothing is saved; the proposal is returned for admins to review and
+    /// edit before committing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `options` - The slot length and panel size to schedule with
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Proposed schedule or error
+    pub async fn propose_schedule(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        Json(options): Json<ScheduleOptions>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let proposal =
+            InterviewScheduler::propose(campaign_id, options, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(proposal)))
+    }
+
+    /// Commits a reviewed interview schedule, creating all of its timeslots at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `state` - The application state
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `schedule` - The reviewed schedule
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn commit_schedule(
+        mut transaction: DBTransaction<'_>,
+        State(mut state): State<AppState>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        Json(schedule): Json<ScheduleCommit>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        InterviewScheduler::commit(
+            campaign_id,
+            schedule,
+            &mut state.snowflake_generator,
+            &mut transaction.tx,
+        )
+        .await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage(
+            "Successfully committed interview schedule",
+        ))
+    }
+}
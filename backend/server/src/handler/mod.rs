@@ -7,27 +7,45 @@
 //! - `answer`: Handles requests related to application answers
 //! - `application`: Processes application-related requests
 //! - `auth`: Manages authentication and authorization requests
+//! - `availability`: Handles interview availability requests
+//! - `calendar`: Handles interview calendar feed requests
 //! - `campaign`: Handles campaign-related requests
+//! - `email`: Handles email queue requests
 //! - `email_template`: Processes email template requests
+//! - `interview`: Handles interview timeslot and booking requests
 //! - `offer`: Handles offer-related requests
 //! - `organisation`: Processes organisation-related requests
+//! - `outcome_release`: Handles scheduled outcome release requests
+//! - `pipeline`: Handles recruitment pipeline stage requests
 //! - `invite`: Handles invite-related requests
 //! - `question`: Handles question-related requests
 //! - `rating`: Processes rating-related requests
+//! - `review_conflict`: Handles conflict of interest requests
+//! - `reviewer_assignment`: Handles reviewer assignment and review queue requests
 //! - `role`: Handles role-related requests
 //! - `user`: Processes user-related requests
+//! - `waitlist`: Handles role waitlist requests
 
 pub mod answer;
 pub mod application;
 pub mod auth;
+pub mod availability;
+pub mod calendar;
 pub mod campaign;
 pub mod comment;
+pub mod email;
 pub mod email_template;
+pub mod interview;
 pub mod invite;
 pub mod offer;
 pub mod organisation;
+pub mod outcome_release;
+pub mod pipeline;
 pub mod question;
 pub mod rating;
+pub mod review_conflict;
+pub mod reviewer_assignment;
 pub mod role;
 pub mod role_status;
 pub mod user;
+pub mod waitlist;
@@ -5,12 +5,19 @@
 //! - Replying to offers
 //! - Previewing and sending offer emails
 //! - Queuing offer emails for the background worker (`EmailQueue`)
+//! - Creating offers and queueing outcome emails in bulk
+//! - Configuring offer reminders and what happens when an offer is accepted
+//! - Proposing and committing role allocations
 
+use crate::models::allocation::{AllocationCommit, AllocationOptions, RoleAllocator};
 use crate::models::app::{AppMessage, AppState};
 use crate::models::auth::{CampaignAdmin, OfferAdmin, OfferRecipient};
-use crate::models::email::{EmailQueue, EmailType};
+use crate::models::bulk_offer::{BulkOffer, BulkOfferRequest, OutcomeEmail};
+use crate::models::email::EmailType;
 use crate::models::error::ChaosError;
 use crate::models::offer::{Offer, OfferReply};
+use crate::models::offer_acceptance::{OfferAcceptance, OfferAcceptanceSettings};
+use crate::models::offer_reminder::{OfferReminder, OfferReminderSettings};
 use crate::models::transaction::DBTransaction;
 use axum::extract::{Json, Path, State};
 use axum::http::StatusCode;
@@ -21,25 +28,17 @@ use serde::Deserialize;
 /// Handler for offer-related HTTP requests.
 pub struct OfferHandler;
 
-/// One outcome email row (subject/body already resolved on the client).
+/// One outcome email to queue. The email is rendered on the server from the
+/// template and sent to the applicant's own address.
 #[derive(Deserialize)]
-#[allow(dead_code)]
 pub struct QueueOutcomeEmailItem {
-    #[serde(default)]
-    pub id: Option<String>,
     #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
     pub application_id: i64,
-    pub email: String,
-    pub name: String,
-    #[serde(default)]
-    pub role: Option<String>,
     #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
     pub role_id: i64,
     #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
     pub email_template_id: i64,
     pub expiry: DateTime<Utc>,
-    pub subject: String,
-    pub body: String,
     pub email_type: EmailType,
 }
 
@@ -104,6 +103,7 @@ impl OfferHandler {
     /// # Arguments
     ///
     /// * `transaction` - Database transaction
+    /// * `state` - The application state
     /// * `id` - The ID of the offer to reply to
     /// * `_user` - The authenticated user (must be the offer recipient)
     /// * `reply` - The recipient's response
@@ -113,11 +113,18 @@ impl OfferHandler {
     /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
     pub async fn reply(
         mut transaction: DBTransaction<'_>,
+        State(mut state): State<AppState>,
         Path(id): Path<i64>,
         _user: OfferRecipient,
         Json(reply): Json<OfferReply>,
     ) -> Result<impl IntoResponse, ChaosError> {
-        Offer::reply(id, reply.accept, &mut transaction.tx).await?;
+        Offer::reply(
+            id,
+            reply.accept,
+            &mut state.snowflake_generator,
+            &mut transaction.tx,
+        )
+        .await?;
         transaction.tx.commit().await?;
 
         Ok(AppMessage::OkMessage("Successfully accepted offer"))
@@ -156,7 +163,6 @@ impl OfferHandler {
     /// * `transaction` - Database transaction
     /// * `id` - The ID of the offer to send
     /// * `_user` - The authenticated user (must be an offer admin)
-    /// * `state` - The application state containing email credentials
     ///
     /// # Returns
     ///
@@ -165,9 +171,8 @@ impl OfferHandler {
         mut transaction: DBTransaction<'_>,
         Path(id): Path<i64>,
         _user: OfferAdmin,
-        State(state): State<AppState>,
     ) -> Result<impl IntoResponse, ChaosError> {
-        Offer::send_offer(id, &mut transaction.tx, state.email_credentials).await?;
+        Offer::send_offer(id, &mut transaction.tx).await?;
         transaction.tx.commit().await?;
 
         Ok(AppMessage::OkMessage("Successfully sent offer"))
@@ -175,7 +180,22 @@ impl OfferHandler {
 
     /// Queues outcome emails for the worker (`EmailQueue`, same pipeline as offer email queue).
     ///
-    /// Auth matches viewing application ratings summary: org member for the campaign.
+    /// Each email is rendered from its template and sent to the applicant's own
+    /// address, and an offer is created for each acceptance. The emails are held until
+    /// the campaign's `outcomes_released_at`, and can be previewed, rescheduled or
+    /// cancelled until then.
+    ///
+    /// # Arguments
+    ///
+    /// * `_user` - The authenticated user (must be a campaign admin)
+    /// * `campaign_id` - The ID of the campaign
+    /// * `transaction` - Database transaction
+    /// * `state` - The application state
+    /// * `body` - The outcomes to queue
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
     pub async fn queue_outcome_emails(
         _user: CampaignAdmin,
         Path(campaign_id): Path<i64>,
@@ -183,49 +203,218 @@ impl OfferHandler {
         State(mut state): State<AppState>,
         Json(body): Json<QueueOutcomeEmailsRequest>,
     ) -> Result<impl IntoResponse, ChaosError> {
-        if body.emails.is_empty() {
-            return Err(ChaosError::BadRequestWithMessage(
-                "No emails to queue".to_string(),
-            ));
-        }
-
-        let count = body.emails.len();
-        for item in body.emails {
-            // Create an offer record for acceptances (audit / accept-decline flow).
-            if matches!(item.email_type, EmailType::Accept) {
-                Offer::create(
-                    campaign_id,
-                    item.application_id,
-                    item.email_template_id,
-                    item.role_id,
-                    item.expiry,
-                    &mut transaction.tx,
-                    &mut state.snowflake_generator,
-                )
-                .await?;
-            }
-
-            // Queue the frontend-rendered subject/body for all outcome types.
-            // Accept emails are HTML (React Email); rejects remain plaintext for now.
-            if state.is_dev_env {
-                let email = item.email;
-                let email_type = format!("{:?}", item.email_type);
-                println!("Queuing {email_type} email to {email}");
-            } else {
-                EmailQueue::add_to_queue(
-                    Some(item.name),
-                    item.email,
-                    item.subject,
-                    item.body,
-                    &mut transaction.tx,
-                )
-                .await?;
-            }
-        }
+        let outcomes = body
+            .emails
+            .into_iter()
+            .map(|item| OutcomeEmail {
+                application_id: item.application_id,
+                role_id: item.role_id,
+                outcome: item.email_type,
+                email_template_id: item.email_template_id,
+                expiry: item.expiry,
+            })
+            .collect();
+
+        let report = BulkOffer::queue_outcomes(
+            campaign_id,
+            outcomes,
+            false,
+            &mut state.snowflake_generator,
+            &mut transaction.tx,
+        )
+        .await?;
 
         transaction.tx.commit().await?;
         Ok(AppMessage::OkMessage(format!(
-            "Queued {count} email(s) for delivery"
+            "Queued {} email(s) for release with the campaign's outcomes",
+            report.recipients.len()
         )))
     }
+
+    /// Creates offers and queues rendered outcome emails for a batch of applicants.
+    ///
+    /// With `dry_run` set, nothing is created or queued and the report shows exactly
+    /// who would receive which email.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `state` - The application state
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `request` - The outcomes to process
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Report of the emails or error
+    pub async fn create_bulk(
+        mut transaction: DBTransaction<'_>,
+        State(mut state): State<AppState>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        Json(request): Json<BulkOfferRequest>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let report = BulkOffer::process(
+            campaign_id,
+            request,
+            &mut state.snowflake_generator,
+            &mut transaction.tx,
+        )
+        .await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(report)))
+    }
+
+    /// Proposes which role each applicant in a campaign should be offered.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `options` - Locks to apply before allocating
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Proposed allocation or error
+    pub async fn propose_allocation(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        Json(options): Json<AllocationOptions>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let proposal = RoleAllocator::propose(campaign_id, options, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(proposal)))
+    }
+
+    /// Turns a reviewed allocation into offer drafts.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `state` - The application state
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `allocation` - The reviewed allocation
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn commit_allocation(
+        mut transaction: DBTransaction<'_>,
+        State(mut state): State<AppState>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        Json(allocation): Json<AllocationCommit>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        RoleAllocator::commit(
+            campaign_id,
+            allocation,
+            &mut state.snowflake_generator,
+            &mut transaction.tx,
+        )
+        .await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully created offer drafts"))
+    }
+
+    /// Retrieves a campaign's offer reminder schedule.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Reminder schedule or error
+    pub async fn get_reminder_settings(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let settings = OfferReminder::get_settings(campaign_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(settings)))
+    }
+
+    /// Replaces a campaign's offer reminder schedule.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `settings` - The new reminder schedule
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn update_reminder_settings(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        Json(settings): Json<OfferReminderSettings>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        OfferReminder::update_settings(campaign_id, settings, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage(
+            "Successfully updated offer reminders",
+        ))
+    }
+
+    /// Retrieves a campaign's settings for accepted offers.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Acceptance settings or error
+    pub async fn get_acceptance_settings(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let settings = OfferAcceptance::get_settings(campaign_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(settings)))
+    }
+
+    /// Updates a campaign's settings for accepted offers.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `settings` - The new acceptance settings
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn update_acceptance_settings(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        Json(settings): Json<OfferAcceptanceSettings>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        OfferAcceptance::update_settings(campaign_id, settings, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage(
+            "Successfully updated offer acceptance settings",
+        ))
+    }
 }
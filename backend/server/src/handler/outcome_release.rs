@@ -0,0 +1,91 @@
+//! Outcome release handler for the Chaos application.
+//!
+//! This module provides HTTP request handlers for managing a campaign's scheduled
+//! outcome release, including:
+//! - Previewing the emails held for release
+//! - Rescheduling the release
+//! - Cancelling the pending emails
+
+use crate::models::app::AppMessage;
+use crate::models::auth::CampaignAdmin;
+use crate::models::error::ChaosError;
+use crate::models::outcome_release::{OutcomeRelease, OutcomeReleaseUpdate};
+use crate::models::transaction::DBTransaction;
+use axum::extract::{Json, Path};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+
+/// Handler for outcome release-related HTTP requests.
+pub struct OutcomeReleaseHandler;
+
+impl OutcomeReleaseHandler {
+    /// Retrieves the release time and the emails held for a campaign's outcome release.
+    ///
+    /// # Arguments
+    ///
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `transaction` - Database transaction
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - The pending release or error
+    pub async fn get(
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        mut transaction: DBTransaction<'_>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let release = OutcomeRelease::get(campaign_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(release)))
+    }
+
+    /// Moves a campaign's outcome release to a new time.
+    ///
+    /// # Arguments
+    ///
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `transaction` - Database transaction
+    /// * `data` - The new release time
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn reschedule(
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        mut transaction: DBTransaction<'_>,
+        Json(data): Json<OutcomeReleaseUpdate>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        OutcomeRelease::reschedule(campaign_id, data, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage(
+            "Successfully rescheduled outcome release",
+        ))
+    }
+
+    /// Cancels every email held for a campaign's outcome release.
+    ///
+    /// # Arguments
+    ///
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `transaction` - Database transaction
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn cancel(
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        mut transaction: DBTransaction<'_>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let count = OutcomeRelease::cancel(campaign_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage(format!("Cancelled {count} email(s)")))
+    }
+}
@@ -0,0 +1,126 @@
+//! Pipeline handler for the Chaos application.
+//!
+//! This module provides HTTP request handlers for recruitment pipelines, including:
+//! - Viewing and replacing a campaign's pipeline stages
+//! - Moving applications and their roles between stages
+//! - Viewing an application's stage history
+
+use crate::models::app::{AppMessage, AppState};
+use crate::models::auth::{ApplicationAdmin, CampaignAdmin};
+use crate::models::error::ChaosError;
+use crate::models::pipeline::{Pipeline, PipelineUpdate, StageMove};
+use crate::models::transaction::DBTransaction;
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+
+/// Handler for pipeline-related HTTP requests.
+pub struct PipelineHandler;
+
+impl PipelineHandler {
+    /// Retrieves a campaign's pipeline.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - The pipeline or error
+    pub async fn get(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let pipeline = Pipeline::get(campaign_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(pipeline)))
+    }
+
+    /// Replaces a campaign's pipeline.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `state` - The application state
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `update` - The new pipeline
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn set(
+        mut transaction: DBTransaction<'_>,
+        State(mut state): State<AppState>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        Json(update): Json<PipelineUpdate>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        Pipeline::set(
+            campaign_id,
+            update,
+            &mut state.snowflake_generator,
+            &mut transaction.tx,
+        )
+        .await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully updated pipeline"))
+    }
+
+    /// Moves an application, or one of its roles, to a pipeline stage.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `application_id` - The ID of the application
+    /// * `admin` - The authenticated user (must be an application admin)
+    /// * `stage_move` - The stage to move to
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn move_application(
+        mut transaction: DBTransaction<'_>,
+        Path(application_id): Path<i64>,
+        admin: ApplicationAdmin,
+        Json(stage_move): Json<StageMove>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        Pipeline::move_application(
+            application_id,
+            stage_move,
+            admin.user_id,
+            &mut transaction.tx,
+        )
+        .await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully moved application"))
+    }
+
+    /// Retrieves every stage move made for an application.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `application_id` - The ID of the application
+    /// * `_admin` - The authenticated user (must be an application admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - The stage history or error
+    pub async fn get_history(
+        mut transaction: DBTransaction<'_>,
+        Path(application_id): Path<i64>,
+        _admin: ApplicationAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let history = Pipeline::get_history(application_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(history)))
+    }
+}
@@ -4,14 +4,16 @@
 //! - Updating ratings
 //! - Retrieving rating details
 //! - Deleting ratings
+//! - Normalised scores and reviewer calibration for a campaign
 
 use crate::models::app::{AppMessage, AppState};
 use crate::models::auth::{ApplicationReviewerGivenApplicationId, CampaignAdmin, RatingCreator};
 use crate::models::error::ChaosError;
 use crate::models::rating::{
     NewApplicationCategoryRating, NewApplicationRating, NewCategoryRating, NewRating, Rating,
-    UpdateCategoryRating,
+    RatingCategoryUpdate, UpdateCategoryRating,
 };
+use crate::models::rating_analytics::RatingAnalytics;
 use crate::models::transaction::DBTransaction;
 use axum::extract::{Json, Path, State};
 use axum::http::StatusCode;
@@ -40,7 +42,7 @@ impl RatingHandler {
         Json(data): Json<NewCategoryRating>,
     ) -> Result<impl IntoResponse, ChaosError> {
         let category = Rating::create_category(
-            data.name,
+            data,
             campaign_id,
             &mut state.snowflake_generator,
             &mut transaction.tx,
@@ -72,7 +74,26 @@ impl RatingHandler {
         Ok((StatusCode::OK, Json(categories)))
     }
 
-    /// Updates a category's name.
+    /// Retrieves normalised scores, rankings and reviewer agreement for a campaign.
+    ///
+    /// # Arguments
+    ///
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `transaction` - Database transaction
+    pub async fn get_analytics(
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        mut transaction: DBTransaction<'_>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let analytics = RatingAnalytics::get(campaign_id, &mut transaction.tx).await?;
+
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(analytics)))
+    }
+
+    /// Updates a category's name, weight, scale and rubric.
     ///
     /// # Arguments
     ///
@@ -80,18 +101,18 @@ impl RatingHandler {
     /// * `category_id` - The ID of the category to update
     /// * `_admin` - The Campaign admin (must be creator of the camapaign)
     /// * `transaction` - Database transaction
-    /// * `data` - The updated rating comment
+    /// * `data` - The updated category details
     pub async fn update_category(
         Path((_campaign_id, category_id)): Path<(i64, i64)>,
         _admin: CampaignAdmin,
         mut transaction: DBTransaction<'_>,
-        Json(data): Json<NewCategoryRating>,
+        Json(data): Json<RatingCategoryUpdate>,
     ) -> Result<impl IntoResponse, ChaosError> {
-        Rating::update_category(category_id, data.name, &mut transaction.tx).await?;
+        Rating::update_category(category_id, data, &mut transaction.tx).await?;
 
         transaction.tx.commit().await?;
 
-        Ok(AppMessage::OkMessage("Successfully updated category name."))
+        Ok(AppMessage::OkMessage("Successfully updated category."))
     }
 
     /// Deletes a rating category from a campaign.
@@ -0,0 +1,80 @@
+//! Review conflict handler for the Chaos application.
+//!
+//! This module provides HTTP request handlers for conflicts of interest, including:
+//! - Letting reviewers declare their own conflicts of interest
+//! - Reporting every conflict of interest in a campaign
+
+use crate::models::app::AppState;
+use crate::models::auth::{CampaignAdmin, CampaignOrgMember};
+use crate::models::error::ChaosError;
+use crate::models::review_conflict::{ConflictDeclaration, ReviewConflict};
+use crate::models::transaction::DBTransaction;
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde_json::json;
+
+/// Handler for review conflict HTTP requests.
+pub struct ReviewConflictHandler;
+
+impl ReviewConflictHandler {
+    /// Declares that the current user has a conflict of interest with an application.
+    ///
+    /// Once declared, the user can no longer rate, comment on or read the comments of
+    /// the application, and any rating they already gave is left out of its scores.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `state` - The application state
+    /// * `campaign_id` - The ID of the campaign
+    /// * `application_id` - The ID of the application
+    /// * `user` - The authenticated user (must be a member of the campaign's organisation)
+    /// * `declaration` - An optional note explaining the conflict
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - The conflict ID or error
+    pub async fn declare(
+        mut transaction: DBTransaction<'_>,
+        State(mut state): State<AppState>,
+        Path((campaign_id, application_id)): Path<(i64, i64)>,
+        user: CampaignOrgMember,
+        Json(declaration): Json<ConflictDeclaration>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let id = ReviewConflict::declare(
+            campaign_id,
+            application_id,
+            declaration,
+            user.user_id,
+            &mut state.snowflake_generator,
+            &mut transaction.tx,
+        )
+        .await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(json!({ "id": id.to_string() }))))
+    }
+
+    /// Retrieves a report of every conflict of interest declared in a campaign.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - The conflict report or error
+    pub async fn get_report(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let report = ReviewConflict::get_report(campaign_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(report)))
+    }
+}
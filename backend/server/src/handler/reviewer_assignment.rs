@@ -0,0 +1,205 @@
+//! Reviewer assignment handler for the Chaos application.
+//!
+//! This module provides HTTP request handlers for reviewer assignment, including:
+//! - Assigning reviewers to roles and applications
+//! - Auto-assigning reviewers across a campaign
+//! - Recording and removing conflicts of interest
+//! - Viewing a reviewer's queue of unrated applications
+
+use crate::models::app::{AppMessage, AppState};
+use crate::models::auth::{ApplicationAdmin, AuthUser, CampaignAdmin, RoleAdmin};
+use crate::models::error::ChaosError;
+use crate::models::reviewer_assignment::{
+    AutoAssignRequest, NewReviewConflict, ReviewerAssignment, ReviewerList,
+};
+use crate::models::transaction::DBTransaction;
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde_json::json;
+
+/// Handler for reviewer assignment HTTP requests.
+pub struct ReviewerAssignmentHandler;
+
+impl ReviewerAssignmentHandler {
+    /// Retrieves every reviewer assignment and conflict in a campaign.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - The assignments or error
+    pub async fn get(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let assignments = ReviewerAssignment::get(campaign_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(assignments)))
+    }
+
+    /// Auto-assigns reviewers across a campaign.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    /// * `request` - The number of reviewers per application and the reviewer pool
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - The assignment report or error
+    pub async fn auto_assign(
+        mut transaction: DBTransaction<'_>,
+        Path(campaign_id): Path<i64>,
+        _admin: CampaignAdmin,
+        Json(request): Json<AutoAssignRequest>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let report =
+            ReviewerAssignment::auto_assign(campaign_id, request, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(report)))
+    }
+
+    /// Replaces the reviewers assigned to every application for a role.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `role_id` - The ID of the role
+    /// * `_admin` - The authenticated user (must be a role admin)
+    /// * `data` - The new reviewers
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn set_role_reviewers(
+        mut transaction: DBTransaction<'_>,
+        Path(role_id): Path<i64>,
+        _admin: RoleAdmin,
+        Json(data): Json<ReviewerList>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        ReviewerAssignment::set_role_reviewers(role_id, data.reviewer_ids, &mut transaction.tx)
+            .await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage(
+            "Successfully assigned role reviewers",
+        ))
+    }
+
+    /// Replaces the reviewers assigned to a specific application.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `application_id` - The ID of the application
+    /// * `_admin` - The authenticated user (must be an application admin)
+    /// * `data` - The new reviewers
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn set_application_reviewers(
+        mut transaction: DBTransaction<'_>,
+        Path(application_id): Path<i64>,
+        _admin: ApplicationAdmin,
+        Json(data): Json<ReviewerList>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        ReviewerAssignment::set_application_reviewers(
+            application_id,
+            data.reviewer_ids,
+            &mut transaction.tx,
+        )
+        .await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage(
+            "Successfully assigned application reviewers",
+        ))
+    }
+
+    /// Records that a reviewer has a conflict of interest with an application.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `state` - The application state
+    /// * `application_id` - The ID of the application
+    /// * `admin` - The authenticated user (must be an application admin)
+    /// * `conflict` - The conflicted reviewer and an optional note
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - The conflict ID or error
+    pub async fn create_conflict(
+        mut transaction: DBTransaction<'_>,
+        State(mut state): State<AppState>,
+        Path(application_id): Path<i64>,
+        admin: ApplicationAdmin,
+        Json(conflict): Json<NewReviewConflict>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let id = ReviewerAssignment::create_conflict(
+            application_id,
+            conflict,
+            admin.user_id,
+            &mut state.snowflake_generator,
+            &mut transaction.tx,
+        )
+        .await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(json!({ "id": id.to_string() }))))
+    }
+
+    /// Removes a conflict of interest from a campaign.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `campaign_id` - The ID of the campaign
+    /// * `conflict_id` - The ID of the conflict
+    /// * `_admin` - The authenticated user (must be a campaign admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn delete_conflict(
+        mut transaction: DBTransaction<'_>,
+        Path((campaign_id, conflict_id)): Path<(i64, i64)>,
+        _admin: CampaignAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        ReviewerAssignment::delete_conflict(campaign_id, conflict_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully removed conflict"))
+    }
+
+    /// Retrieves the current user's queue of assigned, unrated applications.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `user` - The authenticated user
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - The review queue or error
+    pub async fn get_queue(
+        mut transaction: DBTransaction<'_>,
+        user: AuthUser,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let queue = ReviewerAssignment::get_queue(user.user_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(queue)))
+    }
+}
@@ -7,11 +7,12 @@
 
 use crate::models::app::AppMessage;
 use crate::models::application::Application;
+use crate::models::application_listing::ApplicationListQuery;
 use crate::models::auth::{AuthUser, RoleAdmin};
 use crate::models::error::ChaosError;
 use crate::models::role::{Role, RoleUpdate};
 use crate::models::transaction::DBTransaction;
-use axum::extract::{Json, Path};
+use axum::extract::{Json, Path, Query};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 
@@ -93,14 +94,17 @@ impl RoleHandler {
         Ok(AppMessage::OkMessage("Successfully updated role"))
     }
 
-    /// Retrieves all applications for a specific role.
+    /// Retrieves applications for a specific role.
     ///
-    /// This handler allows role admins to view all applications for a role.
+    /// This handler allows role admins to view applications for a role, filtered,
+    /// sorted and paged by the query parameters. The cursor for the next page, if
+    /// any, is returned in the `X-Next-Cursor` header.
     ///
     /// # Arguments
     ///
     /// * `id` - The ID of the role
-    /// * `_admin` - The authenticated user (must be a role admin)
+    /// * `admin` - The authenticated user (must be a role admin)
+    /// * `query` - Filters, sort and page to list
     /// * `transaction` - Database transaction
     ///
     /// # Returns
@@ -109,11 +113,12 @@ impl RoleHandler {
     pub async fn get_applications(
         Path(id): Path<i64>,
         admin: RoleAdmin,
+        Query(query): Query<ApplicationListQuery>,
         mut transaction: DBTransaction<'_>,
     ) -> Result<impl IntoResponse, ChaosError> {
-        let applications =
-            Application::get_from_role_id(id, admin.user_id, &mut transaction.tx).await?;
+        let (applications, page) =
+            Application::get_from_role_id(id, admin.user_id, &query, &mut transaction.tx).await?;
         transaction.tx.commit().await?;
-        Ok((StatusCode::OK, Json(applications)))
+        Ok((StatusCode::OK, page.headers(), Json(applications)))
     }
 }
@@ -19,7 +19,7 @@ impl RoleStatusHandler {
     /// # Arguments
     /// * `application_id` - ID of the application whose status is being set.
     /// * `campaign_role_id` - ID of the campaign role whose per-role status is being set.
-    /// * `_admin` - Authenticated user allowed to set the application's per-role status.
+    /// * `admin` - Authenticated user allowed to set the application's per-role status.
     /// * `transaction` - Database transaction wrapper.
     /// * `data` - Update role payload.
     ///
@@ -28,7 +28,7 @@ impl RoleStatusHandler {
     pub async fn update_role_status(
         Path((application_id, campaign_role_id)): Path<(i64, i64)>,
         // TODO: Replace the AuthUser extractor with something that enforces the desired permissions.
-        _admin: AuthUser,
+        admin: AuthUser,
         mut transaction: DBTransaction<'_>,
         Json(data): Json<UpdateRoleStatus>,
     ) -> Result<impl IntoResponse, ChaosError> {
@@ -36,6 +36,7 @@ impl RoleStatusHandler {
             application_id,
             campaign_role_id,
             data.status,
+            admin.user_id,
             &mut transaction.tx,
         )
         .await?;
@@ -0,0 +1,65 @@
+//! Waitlist handler for the Chaos application.
+//!
+//! This module provides HTTP request handlers for managing role waitlists, including:
+//! - Viewing a role's waitlist
+//! - Replacing a role's waitlist
+
+use crate::models::app::AppMessage;
+use crate::models::auth::RoleAdmin;
+use crate::models::error::ChaosError;
+use crate::models::transaction::DBTransaction;
+use crate::models::waitlist::{Waitlist, WaitlistUpdate};
+use axum::extract::{Json, Path};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+
+/// Handler for waitlist-related HTTP requests.
+pub struct WaitlistHandler;
+
+impl WaitlistHandler {
+    /// Retrieves a role's waitlist.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `role_id` - The ID of the role
+    /// * `_admin` - The authenticated user (must be a role admin)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - The waitlist or error
+    pub async fn get(
+        mut transaction: DBTransaction<'_>,
+        Path(role_id): Path<i64>,
+        _admin: RoleAdmin,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        let waitlist = Waitlist::get(role_id, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok((StatusCode::OK, Json(waitlist)))
+    }
+
+    /// Replaces a role's waitlist.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - Database transaction
+    /// * `role_id` - The ID of the role
+    /// * `_admin` - The authenticated user (must be a role admin)
+    /// * `data` - The new waitlist, highest ranked first
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl IntoResponse, ChaosError>` - Success message or error
+    pub async fn set(
+        mut transaction: DBTransaction<'_>,
+        Path(role_id): Path<i64>,
+        _admin: RoleAdmin,
+        Json(data): Json<WaitlistUpdate>,
+    ) -> Result<impl IntoResponse, ChaosError> {
+        Waitlist::set(role_id, data, &mut transaction.tx).await?;
+        transaction.tx.commit().await?;
+
+        Ok(AppMessage::OkMessage("Successfully updated waitlist"))
+    }
+}
@@ -1,6 +1,9 @@
 use crate::models::app::app;
-use crate::models::email::EmailQueue;
+use crate::models::email_worker::EmailWorker;
 use crate::models::error::ChaosError;
+use crate::models::offer::Offer;
+use crate::models::offer_reminder::OfferReminder;
+use crate::models::outcome_release::OutcomeRelease;
 use crate::models::seeder::Seeder;
 
 mod constants;
@@ -24,27 +27,94 @@ async fn main() -> Result<(), ChaosError> {
     let mut seeder = Seeder::init().await;
     seeder.seed_database(super_user_email).await?;
 
-    let email_db = state_clone.db.clone();
-    let email_task = tokio::spawn(async move {
+    let email_worker = EmailWorker::new(
+        state_clone.db.clone(),
+        state_clone.mailer.clone(),
+        state_clone.email_worker_config.clone(),
+    );
+    let email_task = tokio::spawn(email_worker.run());
+
+    let jobs_db = state_clone.db.clone();
+    let mut jobs_snowflake_generator = state_clone.snowflake_generator;
+    let scheduled_jobs_task = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
         loop {
-            let mut transaction = email_db.begin().await.unwrap();
-            if let Err(e) =
-                EmailQueue::send_next(state_clone.email_credentials.clone(), &mut transaction).await
-            {
+            interval.tick().await;
+
+            // Errors are logged rather than propagated, so one failure does not stop
+            // expiry, reminders or release promotion until the next restart.
+            let mut transaction = match jobs_db.begin().await {
+                Ok(transaction) => transaction,
+                Err(e) => {
+                    ChaosError::from(e).print();
+                    continue;
+                }
+            };
+            let overdue = Offer::get_overdue(&mut transaction).await;
+            if let Err(e) = transaction.commit().await {
+                ChaosError::from(e).print();
+                continue;
+            }
+
+            match overdue {
+                Ok(offer_ids) => {
+                    // Expire each offer on its own, so one failure does not roll back the rest.
+                    for offer_id in offer_ids {
+                        let mut transaction = match jobs_db.begin().await {
+                            Ok(transaction) => transaction,
+                            Err(e) => {
+                                ChaosError::from(e).print();
+                                continue;
+                            }
+                        };
+                        let expired = Offer::expire(
+                            offer_id,
+                            &mut jobs_snowflake_generator,
+                            &mut transaction,
+                        )
+                        .await;
+                        if let Err(e) = expired {
+                            e.print();
+                        } else if let Err(e) = transaction.commit().await {
+                            ChaosError::from(e).print();
+                        }
+                    }
+                }
+                Err(e) => e.print(),
+            }
+
+            let mut transaction = match jobs_db.begin().await {
+                Ok(transaction) => transaction,
+                Err(e) => {
+                    ChaosError::from(e).print();
+                    continue;
+                }
+            };
+            if let Err(e) = OfferReminder::queue_due(&mut transaction).await {
                 e.print();
-            } else {
-                transaction.commit().await.unwrap();
+            } else if let Err(e) = transaction.commit().await {
+                ChaosError::from(e).print();
             }
 
-            // Small delay to prevent excessive CPU usage
-            tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
+            let mut transaction = match jobs_db.begin().await {
+                Ok(transaction) => transaction,
+                Err(e) => {
+                    ChaosError::from(e).print();
+                    continue;
+                }
+            };
+            if let Err(e) = OutcomeRelease::promote_due(&mut transaction).await {
+                e.print();
+            } else if let Err(e) = transaction.commit().await {
+                ChaosError::from(e).print();
+            }
         }
     });
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await.unwrap();
     let server_task = axum::serve(listener, app);
 
-    let _ = tokio::join!(server_task, email_task);
+    let _ = tokio::join!(server_task, email_task, scheduled_jobs_task);
 
     Ok(())
 }
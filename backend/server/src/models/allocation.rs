@@ -0,0 +1,654 @@
+//! Preference-aware role allocation for Chaos.
+//!
+//! This module proposes which role, if any, each applicant in a campaign should be
+//! offered, and turns a reviewed allocation into `Offer` drafts.
+//!
+//! The proposal is an applicant-proposing stable matching (deferred acceptance).
+//! Applicants rank the roles they applied for by preference percentage, and each
+//! role ranks its applicants by average rating, breaking ties by how strongly the
+//! applicant preferred the role. Roles hold at most `max_available` applicants. The
+//! result is stable: no applicant and role would both rather be matched to each
+//! other than to their current allocation, and no applicant can get a more
+//! preferred role without a higher-ranked applicant losing it.
+
+use crate::models::error::ChaosError;
+use crate::models::offer::Offer;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use snowflake::SnowflakeIdGenerator;
+use sqlx::{Postgres, Transaction};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::DerefMut;
+
+/// An admin decision applied before the allocation is computed.
+#[derive(Deserialize)]
+pub struct AllocationLock {
+    /// ID of the application being locked
+    #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
+    pub application_id: i64,
+    /// Role the applicant is locked to, or `None` to leave them unallocated
+    #[serde(
+        default,
+        deserialize_with = "crate::models::serde_string::deserialize_option"
+    )]
+    pub role_id: Option<i64>,
+}
+
+/// Options for generating an allocation.
+#[derive(Deserialize)]
+pub struct AllocationOptions {
+    /// Decisions that override the computed allocation
+    #[serde(default)]
+    pub locks: Vec<AllocationLock>,
+}
+
+/// A single applicant's proposed role.
+#[derive(Serialize)]
+pub struct ProposedAllocation {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub application_id: i64,
+    pub user_name: String,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub role_id: i64,
+    pub role_name: String,
+    /// Average of every category rating the application received
+    pub score: Option<f64>,
+    /// Share of the applicant's preference given to this role
+    pub preference_percentage: i32,
+    /// Whether the allocation came from an admin lock
+    pub locked: bool,
+    /// Why the applicant was given this role
+    pub explanation: String,
+}
+
+/// An applicant who was not allocated a role.
+#[derive(Serialize)]
+pub struct UnallocatedApplicant {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub application_id: i64,
+    pub user_name: String,
+    pub score: Option<f64>,
+    /// Whether the applicant was left out by an admin lock
+    pub locked: bool,
+    /// Why the applicant was not given a role
+    pub explanation: String,
+}
+
+/// How full a role is in the proposed allocation.
+#[derive(Serialize)]
+pub struct RoleAllocationSummary {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub role_id: i64,
+    pub name: String,
+    pub min_available: i32,
+    pub max_available: i32,
+    /// Existing offers plus applicants allocated in this proposal
+    pub allocated: i32,
+    /// Set when the role could not be filled to `min_available`
+    pub warning: Option<String>,
+}
+
+/// A proposed allocation for admins to review.
+#[derive(Serialize)]
+pub struct AllocationProposal {
+    pub allocations: Vec<ProposedAllocation>,
+    pub unallocated: Vec<UnallocatedApplicant>,
+    pub roles: Vec<RoleAllocationSummary>,
+}
+
+/// A single reviewed allocation to be turned into an offer.
+#[derive(Deserialize)]
+pub struct AllocationAssignment {
+    #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
+    pub application_id: i64,
+    #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
+    pub role_id: i64,
+}
+
+/// A reviewed allocation to be turned into offer drafts.
+#[derive(Deserialize)]
+pub struct AllocationCommit {
+    /// Email template used for every created offer
+    #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
+    pub email_template_id: i64,
+    /// When every created offer expires
+    pub expiry: DateTime<Utc>,
+    pub allocations: Vec<AllocationAssignment>,
+}
+
+/// A role and its capacity.
+struct AllocationRole {
+    id: i64,
+    name: String,
+    min_available: i32,
+    max_available: i32,
+    offered: i64,
+}
+
+/// An applicant and the roles they applied for, most preferred first.
+struct AllocationCandidate {
+    application_id: i64,
+    user_name: String,
+    score: Option<f64>,
+    preferences: Vec<(i64, i32)>,
+}
+
+impl AllocationCandidate {
+    fn preference_for(&self, role_id: i64) -> i32 {
+        self.preferences
+            .iter()
+            .find(|(id, _)| *id == role_id)
+            .map(|(_, percentage)| *percentage)
+            .unwrap_or(0)
+    }
+
+    /// Orders candidates from most to least preferred by a role.
+    fn compare_for_role(&self, other: &AllocationCandidate, role_id: i64) -> Ordering {
+        let score = |candidate: &AllocationCandidate| candidate.score.unwrap_or(f64::NEG_INFINITY);
+
+        score(other)
+            .total_cmp(&score(self))
+            .then_with(|| {
+                other
+                    .preference_for(role_id)
+                    .cmp(&self.preference_for(role_id))
+            })
+            .then_with(|| self.application_id.cmp(&other.application_id))
+    }
+}
+
+pub struct RoleAllocator;
+
+impl RoleAllocator {
+    /// Proposes a role allocation for a campaign.
+    ///
+    /// Every submitted application is considered for each role it applied for, except
+    /// roles it has already been rejected from or offered. Applicants who already have
+    /// an open or accepted offer in the campaign are left out, and their offers count
+    /// towards role capacity. Locks may not take a role past its `max_available`.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign to allocate roles for
+    /// * `options` - Locks to apply before allocating
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(AllocationProposal)` - The proposed allocation, with explanations
+    /// * `Err(ChaosError)` - An error if a lock is invalid or retrieval fails
+    pub async fn propose(
+        campaign_id: i64,
+        options: AllocationOptions,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<AllocationProposal, ChaosError> {
+        let roles = sqlx::query!(
+            r#"
+                SELECT r.id, r.name, r.min_available, r.max_available,
+                    (
+                        SELECT COUNT(*) FROM offers o
+                        WHERE o.role_id = r.id
+                        AND o.status NOT IN ('Declined', 'Expired', 'Withdrawn')
+                    ) AS "offered!"
+                FROM campaign_roles r
+                WHERE r.campaign_id = $1
+                ORDER BY r.id
+            "#,
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let mut capacity: HashMap<i64, usize> = roles
+            .iter()
+            .map(|role| {
+                let remaining = (role.max_available as i64 - role.offered).max(0);
+                (role.id, remaining as usize)
+            })
+            .collect();
+        let roles: Vec<AllocationRole> = roles
+            .into_iter()
+            .map(|role| AllocationRole {
+                id: role.id,
+                name: role.name,
+                min_available: role.min_available,
+                max_available: role.max_available,
+                offered: role.offered,
+            })
+            .collect();
+
+        let rows = sqlx::query!(
+            r#"
+                SELECT a.id AS application_id, u.name AS user_name,
+                    ar.campaign_role_id, ar.preference_percentage,
+                    (
+                        SELECT AVG(arc.rating)::float8
+                        FROM application_ratings rt
+                        JOIN application_rating_category_ratings arc
+                            ON arc.application_rating_id = rt.id
+                        WHERE rt.application_id = a.id
+                        AND NOT EXISTS (
+                            SELECT 1 FROM review_conflicts rc
+                            WHERE rc.application_id = a.id AND rc.reviewer_id = rt.rater_id
+                        )
+                    ) AS score
+                FROM applications a
+                JOIN users u ON u.id = a.user_id
+                JOIN application_roles ar ON ar.application_id = a.id
+                WHERE a.campaign_id = $1 AND a.submitted = true
+                AND ar.role_status <> 'Rejected'
+                -- Applicants with an open or accepted offer are already placed, and a
+                -- declined or expired offer is not made again for the same role.
+                -- Withdrawn offers never reached the applicant, so do not count.
+                AND NOT EXISTS (
+                    SELECT 1 FROM offers o
+                    WHERE o.application_id = a.id
+                    AND (
+                        o.status NOT IN ('Declined', 'Expired', 'Withdrawn')
+                        OR (o.role_id = ar.campaign_role_id AND o.status <> 'Withdrawn')
+                    )
+                )
+                ORDER BY a.id, ar.preference_percentage DESC, ar.campaign_role_id
+            "#,
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let mut candidates: Vec<AllocationCandidate> = Vec::new();
+        for row in rows {
+            match candidates.last_mut() {
+                Some(candidate) if candidate.application_id == row.application_id => {
+                    candidate
+                        .preferences
+                        .push((row.campaign_role_id, row.preference_percentage));
+                }
+                _ => candidates.push(AllocationCandidate {
+                    application_id: row.application_id,
+                    user_name: row.user_name,
+                    score: row.score,
+                    preferences: vec![(row.campaign_role_id, row.preference_percentage)],
+                }),
+            }
+        }
+
+        let role_names: HashMap<i64, &str> = roles
+            .iter()
+            .map(|role| (role.id, role.name.as_str()))
+            .collect();
+        let candidate_index: HashMap<i64, usize> = candidates
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| (candidate.application_id, index))
+            .collect();
+
+        // Locks are applied first and take capacity away from the matching.
+        let mut locked: HashMap<usize, Option<i64>> = HashMap::new();
+        for lock in options.locks {
+            let index = *candidate_index.get(&lock.application_id).ok_or_else(|| {
+                ChaosError::BadRequestWithMessage(format!(
+                    "Application {} cannot be allocated in this campaign",
+                    lock.application_id
+                ))
+            })?;
+
+            if let Some(role_id) = lock.role_id {
+                if !candidates[index]
+                    .preferences
+                    .iter()
+                    .any(|(id, _)| *id == role_id)
+                {
+                    return Err(ChaosError::BadRequestWithMessage(format!(
+                        "Application {} did not apply for role {role_id}",
+                        lock.application_id
+                    )));
+                }
+
+                // Locks cannot push a role past its `max_available`.
+                let remaining = capacity.entry(role_id).or_insert(0);
+                if *remaining == 0 {
+                    return Err(ChaosError::BadRequestWithMessage(format!(
+                        "{} has no capacity left for application {}",
+                        role_names.get(&role_id).copied().unwrap_or_default(),
+                        lock.application_id
+                    )));
+                }
+                *remaining -= 1;
+            }
+
+            if locked.insert(index, lock.role_id).is_some() {
+                return Err(ChaosError::BadRequestWithMessage(format!(
+                    "Application {} is locked more than once",
+                    lock.application_id
+                )));
+            }
+        }
+
+        // Deferred acceptance: free applicants propose to their next preferred role, and
+        // each role keeps its best applicants up to capacity.
+        let mut next_choice = vec![0usize; candidates.len()];
+        let mut held: HashMap<i64, Vec<usize>> = HashMap::new();
+        let mut passed_over: Vec<Vec<i64>> = vec![Vec::new(); candidates.len()];
+        let mut free: VecDeque<usize> = (0..candidates.len())
+            .filter(|index| !locked.contains_key(index))
+            .collect();
+
+        while let Some(index) = free.pop_front() {
+            let Some(&(role_id, _)) = candidates[index].preferences.get(next_choice[index]) else {
+                continue;
+            };
+            next_choice[index] += 1;
+
+            let role_capacity = capacity.get(&role_id).copied().unwrap_or(0);
+            let holding = held.entry(role_id).or_default();
+            holding.push(index);
+            holding.sort_by(|a, b| candidates[*a].compare_for_role(&candidates[*b], role_id));
+
+            while holding.len() > role_capacity {
+                let rejected = holding.pop().expect("holding is longer than capacity");
+                passed_over[rejected].push(role_id);
+                free.push_back(rejected);
+            }
+        }
+
+        let mut allocated_role: HashMap<usize, i64> = HashMap::new();
+        for (role_id, holding) in &held {
+            for index in holding {
+                allocated_role.insert(*index, *role_id);
+            }
+        }
+
+        let mut allocations = Vec::new();
+        let mut unallocated = Vec::new();
+        for (index, candidate) in candidates.iter().enumerate() {
+            let (role_id, is_locked) = match locked.get(&index) {
+                Some(role_id) => (*role_id, true),
+                None => (allocated_role.get(&index).copied(), false),
+            };
+
+            let Some(role_id) = role_id else {
+                let explanation = if is_locked {
+                    "Left unallocated by an admin".to_string()
+                } else {
+                    "Every role they applied for was filled by higher-ranked applicants".to_string()
+                };
+                unallocated.push(UnallocatedApplicant {
+                    application_id: candidate.application_id,
+                    user_name: candidate.user_name.clone(),
+                    score: candidate.score,
+                    locked: is_locked,
+                    explanation,
+                });
+                continue;
+            };
+
+            let role_name = role_names.get(&role_id).copied().unwrap_or_default();
+            let explanation = if is_locked {
+                format!("Locked to {role_name} by an admin")
+            } else {
+                Self::explain(
+                    candidate,
+                    role_id,
+                    &candidates,
+                    &passed_over[index],
+                    &role_names,
+                )
+            };
+
+            allocations.push(ProposedAllocation {
+                application_id: candidate.application_id,
+                user_name: candidate.user_name.clone(),
+                role_id,
+                role_name: role_name.to_string(),
+                score: candidate.score,
+                preference_percentage: candidate.preference_for(role_id),
+                locked: is_locked,
+                explanation,
+            });
+        }
+
+        let roles = roles
+            .into_iter()
+            .map(|role| {
+                let allocated = allocations
+                    .iter()
+                    .filter(|allocation| allocation.role_id == role.id)
+                    .count() as i32
+                    + role.offered as i32;
+                let warning = (allocated < role.min_available).then(|| {
+                    format!(
+                        "Only {allocated} of the minimum {} positions could be filled",
+                        role.min_available
+                    )
+                });
+
+                RoleAllocationSummary {
+                    role_id: role.id,
+                    name: role.name,
+                    min_available: role.min_available,
+                    max_available: role.max_available,
+                    allocated,
+                    warning,
+                }
+            })
+            .collect();
+
+        Ok(AllocationProposal {
+            allocations,
+            unallocated,
+            roles,
+        })
+    }
+
+    /// Describes why a candidate was matched to a role.
+    fn explain(
+        candidate: &AllocationCandidate,
+        role_id: i64,
+        candidates: &[AllocationCandidate],
+        passed_over: &[i64],
+        role_names: &HashMap<i64, &str>,
+    ) -> String {
+        let choice = candidate
+            .preferences
+            .iter()
+            .position(|(id, _)| *id == role_id)
+            .unwrap_or(0)
+            + 1;
+
+        let mut applicants: Vec<&AllocationCandidate> = candidates
+            .iter()
+            .filter(|other| other.preferences.iter().any(|(id, _)| *id == role_id))
+            .collect();
+        applicants.sort_by(|a, b| a.compare_for_role(b, role_id));
+        let rank = applicants
+            .iter()
+            .position(|other| other.application_id == candidate.application_id)
+            .unwrap_or(0)
+            + 1;
+
+        let score = match candidate.score {
+            Some(score) => format!("an average rating of {score:.2}"),
+            None => "no ratings yet".to_string(),
+        };
+
+        let mut explanation = format!(
+            "Choice {choice} ({}% preference); ranked {rank} of {} applicants for {} with {score}",
+            candidate.preference_for(role_id),
+            applicants.len(),
+            role_names.get(&role_id).copied().unwrap_or_default()
+        );
+
+        if !passed_over.is_empty() {
+            let preferred: Vec<&str> = passed_over
+                .iter()
+                .map(|id| role_names.get(id).copied().unwrap_or_default())
+                .collect();
+            explanation.push_str(&format!(
+                ". Preferred {} filled by higher-ranked applicants",
+                preferred.join(", ")
+            ));
+        }
+
+        explanation
+    }
+
+    /// Turns a reviewed allocation into offer drafts.
+    ///
+    /// Each applicant may be allocated at most one role, must have applied for it, and
+    /// must not already have an open or accepted offer in the campaign, or any offer
+    /// for that role. Role capacities, including existing offers that have not been
+    /// declined or expired, are enforced.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `allocation` - The reviewed allocation
+    /// * `snowflake_generator` - Generator for the new offer IDs
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If every offer draft was created
+    /// * `Err(ChaosError)` - An error if any allocation is invalid
+    pub async fn commit(
+        campaign_id: i64,
+        allocation: AllocationCommit,
+        snowflake_generator: &mut SnowflakeIdGenerator,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        if allocation.expiry <= Utc::now() {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Offer expiry must be in the future".to_string(),
+            ));
+        }
+
+        let template_exists = sqlx::query!(
+            "
+                SELECT EXISTS(
+                    SELECT 1 FROM email_templates t
+                    JOIN campaigns c ON c.organisation_id = t.organisation_id
+                    WHERE t.id = $1 AND c.id = $2
+                )
+            ",
+            allocation.email_template_id,
+            campaign_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .exists
+        .expect("`exists` should always exist in this query result");
+
+        if !template_exists {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Email template does not belong to this campaign's organisation".to_string(),
+            ));
+        }
+
+        let mut seen = HashSet::new();
+        for assignment in &allocation.allocations {
+            if !seen.insert(assignment.application_id) {
+                return Err(ChaosError::BadRequestWithMessage(format!(
+                    "Application {} is allocated more than once",
+                    assignment.application_id
+                )));
+            }
+
+            let applied = sqlx::query!(
+                "
+                    SELECT EXISTS(
+                        SELECT 1 FROM applications a
+                        JOIN application_roles ar ON ar.application_id = a.id
+                        WHERE a.id = $1 AND a.campaign_id = $2 AND a.submitted = true
+                        AND ar.campaign_role_id = $3
+                    )
+                ",
+                assignment.application_id,
+                campaign_id,
+                assignment.role_id
+            )
+            .fetch_one(transaction.deref_mut())
+            .await?
+            .exists
+            .expect("`exists` should always exist in this query result");
+
+            if !applied {
+                return Err(ChaosError::BadRequestWithMessage(format!(
+                    "Application {} did not apply for role {}",
+                    assignment.application_id, assignment.role_id
+                )));
+            }
+
+            let has_offer = sqlx::query!(
+                "
+                    SELECT EXISTS(
+                        SELECT 1 FROM offers
+                        WHERE application_id = $1
+                        AND (
+                            status NOT IN ('Declined', 'Expired', 'Withdrawn')
+                            OR (role_id = $2 AND status <> 'Withdrawn')
+                        )
+                    )
+                ",
+                assignment.application_id,
+                assignment.role_id
+            )
+            .fetch_one(transaction.deref_mut())
+            .await?
+            .exists
+            .expect("`exists` should always exist in this query result");
+
+            if has_offer {
+                return Err(ChaosError::BadRequestWithMessage(format!(
+                    "Application {} already has an offer for this or another role",
+                    assignment.application_id
+                )));
+            }
+        }
+
+        let mut per_role: HashMap<i64, i64> = HashMap::new();
+        for assignment in &allocation.allocations {
+            *per_role.entry(assignment.role_id).or_insert(0) += 1;
+        }
+
+        for (role_id, count) in per_role {
+            let role = sqlx::query!(
+                r#"
+                    SELECT r.name, r.max_available,
+                        (
+                            SELECT COUNT(*) FROM offers o
+                            WHERE o.role_id = r.id
+                        AND o.status NOT IN ('Declined', 'Expired', 'Withdrawn')
+                        ) AS "offered!"
+                    FROM campaign_roles r
+                    WHERE r.id = $1 AND r.campaign_id = $2
+                "#,
+                role_id,
+                campaign_id
+            )
+            .fetch_one(transaction.deref_mut())
+            .await?;
+
+            if role.offered + count > role.max_available as i64 {
+                return Err(ChaosError::BadRequestWithMessage(format!(
+                    "{} can only take {} more offer(s)",
+                    role.name,
+                    (role.max_available as i64 - role.offered).max(0)
+                )));
+            }
+        }
+
+        for assignment in allocation.allocations {
+            Offer::create(
+                campaign_id,
+                assignment.application_id,
+                allocation.email_template_id,
+                assignment.role_id,
+                allocation.expiry,
+                transaction,
+                snowflake_generator,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,22 +1,33 @@
 use crate::handler::answer::AnswerHandler;
 use crate::handler::application::ApplicationHandler;
 use crate::handler::auth::{google_auth_init, google_callback, logout, DevLoginHandler};
+use crate::handler::availability::AvailabilityHandler;
+use crate::handler::calendar::CalendarHandler;
 use crate::handler::campaign::CampaignHandler;
 use crate::handler::comment::CommentHandler;
+use crate::handler::email::EmailHandler;
 use crate::handler::email_template::EmailTemplateHandler;
+use crate::handler::interview::InterviewHandler;
 use crate::handler::invite::InviteHandler;
 use crate::handler::offer::OfferHandler;
 use crate::handler::organisation::OrganisationHandler;
+use crate::handler::outcome_release::OutcomeReleaseHandler;
+use crate::handler::pipeline::PipelineHandler;
 use crate::handler::question::QuestionHandler;
 use crate::handler::rating::RatingHandler;
+use crate::handler::review_conflict::ReviewConflictHandler;
+use crate::handler::reviewer_assignment::ReviewerAssignmentHandler;
 use crate::handler::role::RoleHandler;
 use crate::handler::role_status::RoleStatusHandler;
 use crate::handler::user::UserHandler;
-use crate::models::email::{ChaosEmail, EmailCredentials};
+use crate::handler::waitlist::WaitlistHandler;
+use crate::models::application_listing::NEXT_CURSOR_HEADER;
+use crate::models::email_transport::Mailer;
+use crate::models::email_worker::EmailWorkerConfig;
 use crate::models::error::ChaosError;
 use crate::models::storage::Storage;
 use crate::service::oauth2::build_oauth_client;
-use axum::http::{header, Method, StatusCode};
+use axum::http::{header, HeaderName, Method, StatusCode};
 use axum::response::IntoResponse;
 use axum::routing::{delete, get, patch, post, put};
 use axum::{Json, Router};
@@ -104,7 +115,8 @@ pub struct AppState {
     pub snowflake_generator: SnowflakeIdGenerator,
     pub storage_bucket: Bucket,
     pub is_dev_env: bool,
-    pub email_credentials: EmailCredentials,
+    pub mailer: Mailer,
+    pub email_worker_config: EmailWorkerConfig,
 }
 
 pub async fn init_app_state() -> AppState {
@@ -158,8 +170,10 @@ pub async fn init_app_state() -> AppState {
     // Initialise S3 bucket
     let storage_bucket = Storage::init_bucket();
 
-    // Initialise email credentials
-    let email_credentials = ChaosEmail::setup_credentials();
+    // Initialise email transport, sized for the email worker's concurrency
+    let email_worker_config = EmailWorkerConfig::from_env();
+    let mailer = Mailer::from_env(is_dev_env, email_worker_config.concurrency as u32)
+        .expect("Cannot set up email transport");
 
     // Add all data to AppState
 
@@ -174,7 +188,8 @@ pub async fn init_app_state() -> AppState {
         snowflake_generator,
         storage_bucket,
         is_dev_env,
-        email_credentials,
+        mailer,
+        email_worker_config,
     }
 }
 
@@ -196,6 +211,7 @@ pub async fn app() -> Result<(Router, AppState), ChaosError> {
             header::SET_COOKIE,
             header::CONTENT_TYPE,
         ])
+        .expose_headers([HeaderName::from_static(NEXT_CURSOR_HEADER)])
         .allow_credentials(true)
         .allow_origin([
             "http://localhost".parse().unwrap(),
@@ -232,10 +248,57 @@ pub async fn app() -> Result<(Router, AppState), ChaosError> {
         .route("/api/v1/user/gender", patch(UserHandler::update_gender))
         .route("/api/v1/user/zid", patch(UserHandler::update_zid))
         .route("/api/v1/user/degree", patch(UserHandler::update_degree))
+        .route(
+            "/api/v1/user/calendar/token",
+            get(CalendarHandler::get_token)
+                .post(CalendarHandler::create_token)
+                .delete(CalendarHandler::revoke_token),
+        )
+        .route(
+            "/api/v1/calendar/feed/:token",
+            get(CalendarHandler::get_feed),
+        )
+        .route("/api/v1/emails/failed", get(EmailHandler::get_failed))
+        .route(
+            "/api/v1/email/:email_id/requeue",
+            post(EmailHandler::requeue),
+        )
+        .route(
+            "/api/v1/application/:application_id/emails",
+            get(EmailHandler::get_by_application),
+        )
+        .route(
+            "/api/v1/application/:application_id/timeline",
+            get(ApplicationHandler::get_timeline),
+        )
+        .route(
+            "/api/v1/application/:application_id/timeline/applicant",
+            get(ApplicationHandler::get_applicant_timeline),
+        )
+        .route(
+            "/api/v1/application/:application_id/stage",
+            post(PipelineHandler::move_application),
+        )
+        .route(
+            "/api/v1/application/:application_id/stage/history",
+            get(PipelineHandler::get_history),
+        )
+        .route(
+            "/api/v1/campaign/:campaign_id/pipeline",
+            get(PipelineHandler::get).put(PipelineHandler::set),
+        )
         .route(
             "/api/v1/user/applications",
             get(ApplicationHandler::get_from_curr_user),
         )
+        .route(
+            "/api/v1/user/applications/status",
+            get(ApplicationHandler::get_status_from_curr_user),
+        )
+        .route(
+            "/api/v1/user/review_queue",
+            get(ReviewerAssignmentHandler::get_queue),
+        )
         .route(
             "/api/v1/user/organisations",
             get(OrganisationHandler::get_all_for_user),
@@ -318,6 +381,44 @@ pub async fn app() -> Result<(Router, AppState), ChaosError> {
             "/api/v1/campaign/:campaign_id/rating_categories",
             get(RatingHandler::get_categories_by_campaign),
         )
+        .route(
+            "/api/v1/campaign/:campaign_id/rating_analytics",
+            get(RatingHandler::get_analytics),
+        )
+        // Reviewer Assignment
+        .route(
+            "/api/v1/campaign/:campaign_id/reviewers",
+            get(ReviewerAssignmentHandler::get),
+        )
+        .route(
+            "/api/v1/campaign/:campaign_id/reviewers/auto_assign",
+            post(ReviewerAssignmentHandler::auto_assign),
+        )
+        .route(
+            "/api/v1/campaign/:campaign_id/conflict/:conflict_id",
+            delete(ReviewerAssignmentHandler::delete_conflict),
+        )
+        .route(
+            "/api/v1/role/:role_id/reviewers",
+            put(ReviewerAssignmentHandler::set_role_reviewers),
+        )
+        .route(
+            "/api/v1/application/:application_id/reviewers",
+            put(ReviewerAssignmentHandler::set_application_reviewers),
+        )
+        .route(
+            "/api/v1/application/:application_id/conflict",
+            post(ReviewerAssignmentHandler::create_conflict),
+        )
+        // Review Conflicts
+        .route(
+            "/api/v1/campaign/:campaign_id/conflicts",
+            get(ReviewConflictHandler::get_report),
+        )
+        .route(
+            "/api/v1/campaign/:campaign_id/application/:application_id/conflict/declare",
+            post(ReviewConflictHandler::declare),
+        )
         .route(
             "/api/v1/campaign/:campaign_id/rating_category/:category_id",
             patch(RatingHandler::update_category).delete(RatingHandler::delete_category),
@@ -380,6 +481,11 @@ pub async fn app() -> Result<(Router, AppState), ChaosError> {
             "/api/v1/campaign/:campaign_id/publish",
             patch(CampaignHandler::publish),
         )
+        .route(
+            "/api/v1/campaign/:campaign_id/blind_review",
+            get(CampaignHandler::get_blind_review_settings)
+                .put(CampaignHandler::update_blind_review_settings),
+        )
         .route(
             "/api/v1/organisation/slug/:organisation_slug/campaign/slug/:campaign_slug",
             get(CampaignHandler::get_by_slugs),
@@ -429,6 +535,90 @@ pub async fn app() -> Result<(Router, AppState), ChaosError> {
             "/api/v1/campaign/:campaign_id/offers",
             get(CampaignHandler::get_offers),
         )
+        .route(
+            "/api/v1/campaign/:campaign_id/offers/bulk",
+            post(OfferHandler::create_bulk),
+        )
+        .route(
+            "/api/v1/campaign/:campaign_id/offer/reminders",
+            get(OfferHandler::get_reminder_settings).put(OfferHandler::update_reminder_settings),
+        )
+        .route(
+            "/api/v1/campaign/:campaign_id/offer/acceptance",
+            get(OfferHandler::get_acceptance_settings)
+                .put(OfferHandler::update_acceptance_settings),
+        )
+        // Interview availability
+        .route(
+            "/api/v1/campaign/:campaign_id/availability",
+            get(AvailabilityHandler::get_own)
+                .put(AvailabilityHandler::update_applicant)
+                .delete(AvailabilityHandler::delete_own),
+        )
+        .route(
+            "/api/v1/campaign/:campaign_id/availability/interviewer",
+            put(AvailabilityHandler::update_interviewer),
+        )
+        .route(
+            "/api/v1/campaign/:campaign_id/availabilities",
+            get(AvailabilityHandler::get_by_campaign),
+        )
+        // Interview timeslots
+        .route(
+            "/api/v1/campaign/:campaign_id/interview/timeslot",
+            post(InterviewHandler::create_timeslot),
+        )
+        .route(
+            "/api/v1/campaign/:campaign_id/interview/timeslots",
+            get(InterviewHandler::get_timeslots_by_campaign),
+        )
+        .route(
+            "/api/v1/campaign/:campaign_id/interview/bookings",
+            get(InterviewHandler::get_bookings),
+        )
+        .route(
+            "/api/v1/role/:role_id/interview/timeslots",
+            get(InterviewHandler::get_open_timeslots_by_role),
+        )
+        .route(
+            "/api/v1/campaign/:campaign_id/interview/schedule/preview",
+            post(InterviewHandler::propose_schedule),
+        )
+        .route(
+            "/api/v1/campaign/:campaign_id/interview/schedule",
+            post(InterviewHandler::commit_schedule),
+        )
+        .route(
+            "/api/v1/role/:role_id/interviewers",
+            get(InterviewHandler::get_role_interviewers),
+        )
+        .route(
+            "/api/v1/role/:role_id/waitlist",
+            get(WaitlistHandler::get).put(WaitlistHandler::set),
+        )
+        .route(
+            "/api/v1/role/:role_id/interviewer",
+            post(InterviewHandler::add_role_interviewer)
+                .delete(InterviewHandler::remove_role_interviewer),
+        )
+        .route(
+            "/api/v1/interview/timeslot/:timeslot_id",
+            get(InterviewHandler::get_timeslot)
+                .patch(InterviewHandler::update_timeslot)
+                .delete(InterviewHandler::delete_timeslot),
+        )
+        .route(
+            "/api/v1/interview/timeslot/:timeslot_id/user",
+            post(InterviewHandler::assign_user),
+        )
+        .route(
+            "/api/v1/interview/timeslot/:timeslot_id/user/:user_id",
+            delete(InterviewHandler::remove_user),
+        )
+        .route(
+            "/api/v1/interview/timeslot/:timeslot_id/book",
+            post(InterviewHandler::book).delete(InterviewHandler::cancel_booking),
+        )
         .route(
             "/api/v1/application/:application_id",
             get(ApplicationHandler::get),
@@ -553,6 +743,20 @@ pub async fn app() -> Result<(Router, AppState), ChaosError> {
             "/api/v1/offer/:campaign_id/outcome-emails/queue",
             post(OfferHandler::queue_outcome_emails),
         )
+        .route(
+            "/api/v1/campaign/:campaign_id/allocation/preview",
+            post(OfferHandler::propose_allocation),
+        )
+        .route(
+            "/api/v1/campaign/:campaign_id/allocation",
+            post(OfferHandler::commit_allocation),
+        )
+        .route(
+            "/api/v1/campaign/:campaign_id/outcomes/release",
+            get(OutcomeReleaseHandler::get)
+                .put(OutcomeReleaseHandler::reschedule)
+                .delete(OutcomeReleaseHandler::cancel),
+        )
         // Invite routes
         // - GET  /api/v1/invite/:code  -> invite details
         // - POST /api/v1/invite/:code  -> accept invite
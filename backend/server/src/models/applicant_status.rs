@@ -0,0 +1,156 @@
+//! Applicant-facing view of application outcomes.
+//!
+//! Applicants see the public status of each of their applications and roles only
+//! once the campaign's `outcomes_released_at` has passed. Until then, including for
+//! campaigns with no release time, every outcome other than an interview is shown
+//! as pending. Offers appear once their emails are no longer held for a release,
+//! and those still awaiting a reply can be accepted or declined through the offer
+//! reply endpoint.
+
+use crate::models::application::ApplicationStatus;
+use crate::models::error::ChaosError;
+use crate::models::offer::OfferStatus;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Postgres, Transaction};
+use std::ops::DerefMut;
+
+/// The released outcome of one role in an application.
+#[derive(Serialize)]
+pub struct ApplicantRoleStatus {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub campaign_role_id: i64,
+    pub role_name: String,
+    /// Applicant's preference for the role, as a percentage
+    pub preference_percentage: i32,
+    pub status: ApplicationStatus,
+}
+
+/// A released offer, as seen by its recipient.
+#[derive(Serialize)]
+pub struct ApplicantOffer {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub id: i64,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub role_id: i64,
+    pub role_name: String,
+    pub expiry: DateTime<Utc>,
+    pub status: OfferStatus,
+    /// Whether the offer can still be accepted or declined
+    pub can_reply: bool,
+}
+
+/// The released outcomes of an application, as seen by its applicant.
+#[derive(Serialize)]
+pub struct ApplicantApplicationStatus {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub application_id: i64,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub campaign_id: i64,
+    pub campaign_name: String,
+    pub organisation_name: String,
+    /// When the campaign's outcomes are released, if a time has been set
+    pub outcomes_released_at: Option<DateTime<Utc>>,
+    /// Whether the campaign's outcomes have been released
+    pub outcomes_released: bool,
+    pub status: ApplicationStatus,
+    pub roles: Vec<ApplicantRoleStatus>,
+    pub offers: Vec<ApplicantOffer>,
+}
+
+pub struct ApplicantStatus;
+
+impl ApplicantStatus {
+    /// Retrieves the released outcomes of every application a user has submitted.
+    ///
+    /// # Arguments
+    /// * `user_id` - The ID of the applicant
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<ApplicantApplicationStatus>)` - The applicant's applications, newest first
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get_for_user(
+        user_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<ApplicantApplicationStatus>, ChaosError> {
+        let applications = sqlx::query!(
+            r#"
+                SELECT a.id, a.campaign_id, c.name AS campaign_name,
+                    o.name AS organisation_name, c.outcomes_released_at,
+                    COALESCE(c.outcomes_released_at <= NOW(), false) AS "outcomes_released!",
+                    NOT COALESCE(c.outcomes_released_at > NOW(), false) AS "offers_released!",
+                    CASE WHEN COALESCE(c.outcomes_released_at <= NOW(), false)
+                        OR a.status = 'Interview'
+                        THEN a.status
+                        ELSE 'Pending'::application_status
+                    END AS "status!: ApplicationStatus"
+                FROM applications a
+                JOIN campaigns c ON c.id = a.campaign_id
+                JOIN organisations o ON o.id = c.organisation_id
+                WHERE a.user_id = $1 AND a.submitted = true
+                ORDER BY a.created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let mut statuses = Vec::with_capacity(applications.len());
+        for application in applications {
+            let roles = sqlx::query_as!(
+                ApplicantRoleStatus,
+                r#"
+                    SELECT ar.campaign_role_id, r.name AS role_name, ar.preference_percentage,
+                        CASE WHEN $2 OR ar.role_status = 'Interview'
+                            THEN ar.role_status
+                            ELSE 'Pending'::application_status
+                        END AS "status!: ApplicationStatus"
+                    FROM application_roles ar
+                    JOIN campaign_roles r ON r.id = ar.campaign_role_id
+                    WHERE ar.application_id = $1
+                    ORDER BY ar.preference_percentage DESC
+                "#,
+                application.id,
+                application.outcomes_released
+            )
+            .fetch_all(transaction.deref_mut())
+            .await?;
+
+            let offers = if application.offers_released {
+                sqlx::query_as!(
+                    ApplicantOffer,
+                    r#"
+                        SELECT off.id, off.role_id, r.name AS role_name, off.expiry,
+                            off.status AS "status: OfferStatus",
+                            (off.status = 'Sent' AND off.expiry > NOW()) AS "can_reply!"
+                        FROM offers off
+                        JOIN campaign_roles r ON r.id = off.role_id
+                        WHERE off.application_id = $1 AND off.status NOT IN ('Draft', 'Withdrawn')
+                        ORDER BY off.created_at ASC
+                    "#,
+                    application.id
+                )
+                .fetch_all(transaction.deref_mut())
+                .await?
+            } else {
+                Vec::new()
+            };
+
+            statuses.push(ApplicantApplicationStatus {
+                application_id: application.id,
+                campaign_id: application.campaign_id,
+                campaign_name: application.campaign_name,
+                organisation_name: application.organisation_name,
+                outcomes_released_at: application.outcomes_released_at,
+                outcomes_released: application.outcomes_released,
+                status: application.status,
+                roles,
+                offers,
+            });
+        }
+
+        Ok(statuses)
+    }
+}
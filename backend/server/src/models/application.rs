@@ -5,6 +5,9 @@
 //! application status management and role preferences.
 
 use crate::models::app::AppState;
+use crate::models::application_event::{ApplicationEvent, ApplicationEventType};
+use crate::models::application_listing::{ApplicationListQuery, ApplicationPage};
+use crate::models::blind_review::BlindReview;
 use crate::models::campaign::Campaign;
 use crate::models::error::ChaosError;
 use crate::models::rating::RatingDetails;
@@ -16,6 +19,7 @@ use axum::http::request::Parts;
 use axum::{async_trait, RequestPartsExt};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use snowflake::SnowflakeIdGenerator;
 use sqlx::types::Json;
 use sqlx::{FromRow, Postgres, Transaction};
@@ -218,6 +222,9 @@ pub struct ApplicationRatingSummary {
     pub updated_at: DateTime<Utc>,
     /// All ratings the application has received
     pub ratings: sqlx::types::Json<Vec<RatingDetails>>,
+    /// Weighted mean of the per-category average ratings, each scaled from its
+    /// category's rating range to between 0 and 1, or `None` if unrated
+    pub weighted_total: Option<f64>,
 }
 
 impl Application {
@@ -414,7 +421,7 @@ impl Application {
         .fetch_all(transaction.deref_mut())
         .await?;
 
-        Ok(ApplicationDetails {
+        let mut details = ApplicationDetails {
             id: application_data.id,
             campaign_id: application_data.campaign_id,
             status: application_data.status,
@@ -431,7 +438,16 @@ impl Application {
                 degree_name: application_data.user_degree_name,
                 degree_starting_year: application_data.user_degree_starting_year,
             },
-        })
+        };
+
+        if let Some(pseudonym) = BlindReview::blinded_applications(&[id], transaction)
+            .await?
+            .get(&id)
+        {
+            BlindReview::redact_user(&mut details.user, pseudonym);
+        }
+
+        Ok(details)
     }
 
     /// Retrieves an application by its ID regardless of submission status.
@@ -512,21 +528,28 @@ impl Application {
         })
     }
 
-    /// Retrieves all applications for a specific role.
+    /// Retrieves a filtered, sorted page of applications for a specific role.
     ///
     /// # Arguments
     ///
     /// * `role_id` - ID of the role to get applications for
+    /// * `current_user` - ID of the user listing applications
+    /// * `query` - Filters, sort and page to list
     /// * `transaction` - Database transaction to use
     ///
     /// # Returns
     ///
-    /// * `Result<Vec<ApplicationDetails>, ChaosError>` - List of applications or error
+    /// * `Result<(Vec<ApplicationDetails>, ApplicationPage), ChaosError>` - The page of applications and its cursor, or error
     pub async fn get_from_role_id(
         role_id: i64,
         current_user: i64,
+        query: &ApplicationListQuery,
         transaction: &mut Transaction<'_, Postgres>,
-    ) -> Result<Vec<ApplicationDetails>, ChaosError> {
+    ) -> Result<(Vec<ApplicationDetails>, ApplicationPage), ChaosError> {
+        let page = query
+            .page(None, Some(role_id), current_user, transaction)
+            .await?;
+
         let application_data_list = sqlx::query_as!(
             ApplicationData,
             "
@@ -540,11 +563,14 @@ impl Application {
                 JOIN users u ON u.id = a.user_id
                 JOIN application_roles ar on ar.application_id = a.id
                 JOIN campaigns c on c.id = a.campaign_id
+                JOIN UNNEST($3::BIGINT[]) WITH ORDINALITY AS page(id, position) ON page.id = a.id
                 LEFT JOIN application_ratings arating ON arating.application_id = a.id AND arating.rater_id = $2
-                WHERE ar.campaign_role_id = $1 AND a.submitted = true
+                WHERE ar.campaign_role_id = $1
+                ORDER BY page.position
             ",
             role_id,
-            current_user
+            current_user,
+            &page.application_ids
         )
             .fetch_all(transaction.deref_mut())
             .await?;
@@ -589,24 +615,33 @@ impl Application {
             application_details_list.push(details);
         }
 
-        Ok(application_details_list)
+        Self::redact_blinded(&mut application_details_list, transaction).await?;
+
+        Ok((application_details_list, page))
     }
 
-    /// Retrieves all applications for a specific campaign.
+    /// Retrieves a filtered, sorted page of applications for a specific campaign.
     ///
     /// # Arguments
     ///
     /// * `campaign_id` - ID of the campaign to get applications for
+    /// * `current_user` - ID of the user listing applications
+    /// * `query` - Filters, sort and page to list
     /// * `transaction` - Database transaction to use
     ///
     /// # Returns
     ///
-    /// * `Result<Vec<ApplicationDetails>, ChaosError>` - List of applications or error
+    /// * `Result<(Vec<ApplicationDetails>, ApplicationPage), ChaosError>` - The page of applications and its cursor, or error
     pub async fn get_from_campaign_id(
         campaign_id: i64,
         current_user: i64,
+        query: &ApplicationListQuery,
         transaction: &mut Transaction<'_, Postgres>,
-    ) -> Result<Vec<ApplicationDetails>, ChaosError> {
+    ) -> Result<(Vec<ApplicationDetails>, ApplicationPage), ChaosError> {
+        let page = query
+            .page(Some(campaign_id), None, current_user, transaction)
+            .await?;
+
         let application_data_list = sqlx::query_as!(
             ApplicationData,
             "
@@ -619,11 +654,14 @@ impl Application {
                 FROM applications a
                 JOIN users u ON u.id = a.user_id
                 JOIN campaigns c ON c.id = a.campaign_id
+                JOIN UNNEST($3::BIGINT[]) WITH ORDINALITY AS page(id, position) ON page.id = a.id
                 LEFT JOIN application_ratings ar ON ar.application_id = a.id AND ar.rater_id = $2
-                WHERE a.campaign_id = $1 AND a.submitted = true
+                WHERE a.campaign_id = $1
+                ORDER BY page.position
             ",
             campaign_id,
-            current_user
+            current_user,
+            &page.application_ids
         )
         .fetch_all(transaction.deref_mut())
         .await?;
@@ -669,14 +707,34 @@ impl Application {
             application_details_list.push(details)
         }
 
-        Ok(application_details_list)
+        Self::redact_blinded(&mut application_details_list, transaction).await?;
+
+        Ok((application_details_list, page))
     }
 
+    /// Retrieves the ratings of a filtered, sorted page of applications in a campaign.
+    ///
+    /// # Arguments
+    ///
+    /// * `campaign_id` - ID of the campaign to summarise
+    /// * `current_user` - ID of the user listing applications
+    /// * `query` - Filters, sort and page to list
+    /// * `transaction` - Database transaction to use
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Vec<ApplicationRatingSummary>, ApplicationPage), ChaosError>` - The page of rating summaries and its cursor, or error
     pub async fn get_application_ratings_summary(
         campaign_id: i64,
+        current_user: i64,
+        query: &ApplicationListQuery,
         transaction: &mut Transaction<'_, Postgres>,
-    ) -> Result<Vec<ApplicationRatingSummary>, ChaosError> {
-        let application_users_avg_ratings = sqlx::query_as!(
+    ) -> Result<(Vec<ApplicationRatingSummary>, ApplicationPage), ChaosError> {
+        let page = query
+            .page(Some(campaign_id), None, current_user, transaction)
+            .await?;
+
+        let mut application_users_avg_ratings = sqlx::query_as!(
             ApplicationRatingSummary,
             "
                 SELECT
@@ -715,23 +773,82 @@ impl Application {
                             ) FILTER (WHERE ar.id IS NOT NULL)
                         ),
                         '[]'::jsonb
-                    ) AS \"ratings!: Json<Vec<RatingDetails>>\"
+                    ) AS \"ratings!: Json<Vec<RatingDetails>>\",
+
+                    (
+                        SELECT SUM(
+                            crc.weight * (category_avg.rating - crc.min_rating)
+                                / (crc.max_rating - crc.min_rating)
+                        ) / NULLIF(SUM(crc.weight), 0)
+                        FROM (
+                            SELECT arc.campaign_rating_category_id,
+                                AVG(arc.rating)::DOUBLE PRECISION AS rating
+                            FROM application_rating_category_ratings arc
+                            JOIN application_ratings rated ON rated.id = arc.application_rating_id
+                            WHERE rated.application_id = a.id
+                            AND NOT EXISTS (
+                                SELECT 1 FROM review_conflicts rc
+                                WHERE rc.application_id = a.id AND rc.reviewer_id = rated.rater_id
+                            )
+                            GROUP BY arc.campaign_rating_category_id
+                        ) category_avg
+                        JOIN campaign_rating_categories crc
+                            ON crc.id = category_avg.campaign_rating_category_id
+                    ) AS weighted_total
                 FROM applications a
                 JOIN application_roles applied_roles ON applied_roles.application_id = a.id
                 JOIN campaign_roles ON campaign_roles.id = applied_roles.campaign_role_id
                 LEFT JOIN application_ratings ar ON ar.application_id = a.id
+                    -- Ratings by reviewers with a conflict of interest are left out.
+                    AND NOT EXISTS (
+                        SELECT 1 FROM review_conflicts rc
+                        WHERE rc.application_id = a.id AND rc.reviewer_id = ar.rater_id
+                    )
                 JOIN users u ON u.id = a.user_id
                 LEFT JOIN users AS reviewer ON reviewer.id = ar.rater_id
-                WHERE a.campaign_id = $1 AND a.submitted = true
-                GROUP BY a.id, u.name, u.email, a.status, a.private_status, a.updated_at
-                ORDER BY a.id ASC
+                JOIN UNNEST($2::BIGINT[]) WITH ORDINALITY AS page(id, position) ON page.id = a.id
+                WHERE a.campaign_id = $1
+                GROUP BY a.id, u.name, u.email, a.status, a.private_status, a.updated_at, page.position
+                ORDER BY page.position
             ",
             campaign_id,
+            &page.application_ids
         )
         .fetch_all(transaction.deref_mut())
         .await?;
 
-        Ok(application_users_avg_ratings)
+        let application_ids: Vec<i64> = application_users_avg_ratings
+            .iter()
+            .map(|summary| summary.application_id)
+            .collect();
+        let blinded = BlindReview::blinded_applications(&application_ids, transaction).await?;
+        for summary in application_users_avg_ratings.iter_mut() {
+            if let Some(pseudonym) = blinded.get(&summary.application_id) {
+                summary.user_name = pseudonym.clone();
+                summary.user_email = String::new();
+            }
+        }
+
+        Ok((application_users_avg_ratings, page))
+    }
+
+    /// Hides applicant identity on applications whose campaign is under blind review.
+    async fn redact_blinded(
+        applications: &mut [ApplicationDetails],
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let application_ids: Vec<i64> = applications
+            .iter()
+            .map(|application| application.id)
+            .collect();
+        let blinded = BlindReview::blinded_applications(&application_ids, transaction).await?;
+        for application in applications.iter_mut() {
+            if let Some(pseudonym) = blinded.get(&application.id) {
+                BlindReview::redact_user(&mut application.user, pseudonym);
+            }
+        }
+
+        Ok(())
     }
 
     /// Retrieves all applications submitted by a specific user.
@@ -818,7 +935,8 @@ impl Application {
     ///
     /// * `id` - ID of the application to update
     /// * `new_status` - New status to set
-    /// * `pool` - Database connection pool
+    /// * `changed_by` - ID of the user making the change
+    /// * `transaction` - Database transaction to use
     ///
     /// # Returns
     ///
@@ -826,18 +944,33 @@ impl Application {
     pub async fn set_status(
         id: i64,
         new_status: ApplicationStatus,
+        changed_by: i64,
         transaction: &mut Transaction<'_, Postgres>,
     ) -> Result<(), ChaosError> {
-        sqlx::query!(
-            "
+        let old_status = sqlx::query!(
+            r#"
+                WITH old AS (SELECT status FROM applications WHERE id = $1 FOR UPDATE)
                 UPDATE applications
                 SET status = $2
-                WHERE id = $1 RETURNING id
-            ",
+                FROM old
+                WHERE applications.id = $1
+                RETURNING old.status AS "old_status: ApplicationStatus"
+            "#,
             id,
-            new_status as ApplicationStatus
+            new_status.clone() as ApplicationStatus
         )
         .fetch_one(transaction.deref_mut())
+        .await?
+        .old_status;
+
+        ApplicationEvent::record(
+            id,
+            ApplicationEventType::StatusChanged,
+            Some(changed_by),
+            None,
+            json!({ "from": old_status, "to": new_status }),
+            transaction,
+        )
         .await?;
 
         Ok(())
@@ -849,7 +982,8 @@ impl Application {
     ///
     /// * `id` - ID of the application to update
     /// * `new_status` - New status to set
-    /// * `pool` - Database connection pool
+    /// * `changed_by` - ID of the user making the change
+    /// * `transaction` - Database transaction to use
     ///
     /// # Returns
     ///
@@ -857,18 +991,33 @@ impl Application {
     pub async fn set_private_status(
         id: i64,
         new_status: ApplicationStatus,
+        changed_by: i64,
         transaction: &mut Transaction<'_, Postgres>,
     ) -> Result<(), ChaosError> {
-        sqlx::query!(
-            "
+        let old_status = sqlx::query!(
+            r#"
+                WITH old AS (SELECT private_status FROM applications WHERE id = $1 FOR UPDATE)
                 UPDATE applications
                 SET private_status = $2
-                WHERE id = $1 RETURNING id
-            ",
+                FROM old
+                WHERE applications.id = $1
+                RETURNING old.private_status AS "old_status: ApplicationStatus"
+            "#,
             id,
-            new_status as ApplicationStatus
+            new_status.clone() as ApplicationStatus
         )
         .fetch_one(transaction.deref_mut())
+        .await?
+        .old_status;
+
+        ApplicationEvent::record(
+            id,
+            ApplicationEventType::PrivateStatusChanged,
+            Some(changed_by),
+            None,
+            json!({ "from": old_status, "to": new_status }),
+            transaction,
+        )
         .await?;
 
         Ok(())
@@ -988,13 +1137,24 @@ impl Application {
             ));
         }
 
-        sqlx::query!(
+        let user_id = sqlx::query!(
             "
-                UPDATE applications SET submitted = true WHERE id = $1 RETURNING id
+                UPDATE applications SET submitted = true WHERE id = $1 RETURNING user_id
             ",
             id
         )
         .fetch_one(transaction.deref_mut())
+        .await?
+        .user_id;
+
+        ApplicationEvent::record(
+            id,
+            ApplicationEventType::Submitted,
+            Some(user_id),
+            None,
+            json!({}),
+            transaction,
+        )
         .await?;
 
         Ok(())
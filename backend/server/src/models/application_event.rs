@@ -0,0 +1,206 @@
+//! Application event log for Chaos.
+//!
+//! Every change to an application, such as a status change, a new rating or comment,
+//! or an offer being made or replied to, is appended to the `application_events`
+//! table along with who made it and when. Events are never updated or deleted, so
+//! the log gives reviewers a full timeline of the application. Applicants see a
+//! redacted timeline with only the events that concern them directly.
+
+use crate::models::blind_review::BlindReview;
+use crate::models::error::ChaosError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{Postgres, Transaction};
+use std::ops::DerefMut;
+
+/// Kinds of application events.
+#[derive(Deserialize, Serialize, sqlx::Type, Clone, Debug)]
+#[sqlx(type_name = "application_event_type", rename_all = "PascalCase")]
+pub enum ApplicationEventType {
+    Submitted,
+    StatusChanged,
+    PrivateStatusChanged,
+    RoleStatusChanged,
+    StageChanged,
+    RatingCreated,
+    RatingUpdated,
+    CommentCreated,
+    OfferCreated,
+    OfferSent,
+    OfferAccepted,
+    OfferDeclined,
+    OfferExpired,
+    OfferWithdrawn,
+}
+
+/// An event in an application's timeline, as seen by reviewers.
+#[derive(Serialize)]
+pub struct ApplicationEvent {
+    pub id: i64,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub application_id: i64,
+    pub event_type: ApplicationEventType,
+    /// The user who caused the event, or `None` for background jobs
+    #[serde(serialize_with = "crate::models::serde_string::serialize_option")]
+    pub actor_id: Option<i64>,
+    pub actor_name: Option<String>,
+    /// The role the event concerns, if any
+    #[serde(serialize_with = "crate::models::serde_string::serialize_option")]
+    pub campaign_role_id: Option<i64>,
+    pub role_name: Option<String>,
+    /// Event-specific details, such as the previous and new status
+    pub data: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An event in an application's timeline, as seen by the applicant.
+#[derive(Serialize)]
+pub struct ApplicantEvent {
+    pub event_type: ApplicationEventType,
+    pub role_name: Option<String>,
+    pub data: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApplicationEvent {
+    /// Appends an event to an application's log.
+    ///
+    /// # Arguments
+    /// * `application_id` - The ID of the application
+    /// * `event_type` - What happened
+    /// * `actor_id` - The user who caused the event, or `None` for background jobs
+    /// * `campaign_role_id` - The role the event concerns, if any
+    /// * `data` - Event-specific details
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the event was recorded
+    /// * `Err(ChaosError)` - An error if recording fails
+    pub async fn record(
+        application_id: i64,
+        event_type: ApplicationEventType,
+        actor_id: Option<i64>,
+        campaign_role_id: Option<i64>,
+        data: Value,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        sqlx::query!(
+            "
+                INSERT INTO application_events
+                    (application_id, event_type, actor_id, campaign_role_id, data)
+                VALUES ($1, $2, $3, $4, $5)
+            ",
+            application_id,
+            event_type as ApplicationEventType,
+            actor_id,
+            campaign_role_id,
+            data
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Retrieves an application's full timeline.
+    ///
+    /// Under blind review, events caused by the applicant, such as submitting or
+    /// accepting an offer, name the applicant by their pseudonym.
+    ///
+    /// # Arguments
+    /// * `application_id` - The ID of the application
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<ApplicationEvent>)` - Every event for the application, oldest first
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get_timeline(
+        application_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<ApplicationEvent>, ChaosError> {
+        let mut events = sqlx::query_as!(
+            ApplicationEvent,
+            r#"
+                SELECT e.id, e.application_id, e.event_type AS "event_type: ApplicationEventType",
+                    e.actor_id, u.name AS "actor_name?", e.campaign_role_id,
+                    r.name AS "role_name?", e.data, e.created_at
+                FROM application_events e
+                LEFT JOIN users u ON u.id = e.actor_id
+                LEFT JOIN campaign_roles r ON r.id = e.campaign_role_id
+                WHERE e.application_id = $1
+                ORDER BY e.created_at ASC, e.id ASC
+            "#,
+            application_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let blinded = BlindReview::blinded_applications(&[application_id], transaction).await?;
+        if let Some(pseudonym) = blinded.get(&application_id) {
+            let applicant_id = sqlx::query!(
+                "SELECT user_id FROM applications WHERE id = $1",
+                application_id
+            )
+            .fetch_one(transaction.deref_mut())
+            .await?
+            .user_id;
+
+            for event in events
+                .iter_mut()
+                .filter(|event| event.actor_id == Some(applicant_id))
+            {
+                event.actor_name = Some(pseudonym.clone());
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Retrieves the part of an application's timeline its applicant can see.
+    ///
+    /// Only submission, public status changes and offers are included, without
+    /// saying who made them. Offers are hidden while the campaign's outcome release
+    /// is still scheduled.
+    ///
+    /// # Arguments
+    /// * `application_id` - The ID of the application
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<ApplicantEvent>)` - The visible events, oldest first
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get_applicant_timeline(
+        application_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<ApplicantEvent>, ChaosError> {
+        let events = sqlx::query_as!(
+            ApplicantEvent,
+            r#"
+                SELECT e.event_type AS "event_type: ApplicationEventType",
+                    r.name AS "role_name?", e.data, e.created_at
+                FROM application_events e
+                JOIN applications a ON a.id = e.application_id
+                JOIN campaigns c ON c.id = a.campaign_id
+                LEFT JOIN campaign_roles r ON r.id = e.campaign_role_id
+                WHERE e.application_id = $1
+                AND (
+                    e.event_type IN ('Submitted', 'StatusChanged')
+                    OR (
+                        e.event_type IN ('OfferSent', 'OfferAccepted', 'OfferDeclined', 'OfferExpired', 'OfferWithdrawn')
+                        AND NOT COALESCE(c.outcomes_released_at > NOW(), false)
+                    )
+                )
+                ORDER BY e.created_at ASC, e.id ASC
+            "#,
+            application_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        Ok(events)
+    }
+}
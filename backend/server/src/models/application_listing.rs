@@ -0,0 +1,476 @@
+//! Filtering, sorting and pagination for application listings.
+//!
+//! Campaign and role application listings, and the campaign ratings summary, can be
+//! filtered, sorted and paged with query parameters. All of it happens in a single
+//! SQL query that returns the IDs of one page of applications in order, which the
+//! listing then loads in full. Pages are keyed by cursor rather than offset, so
+//! applications submitted or rated while an admin is paging through a campaign do
+//! not shift later pages. The cursor for the next page is returned in the
+//! `X-Next-Cursor` response header, and a listing without a `limit` is returned in
+//! one page as before.
+//!
+//! Scores are the weighted mean of each category's average rating, scaled from the
+//! category's rating range to between 0 and 1, leaving out ratings by reviewers
+//! with a conflict of interest. Under blind review, sorting by
+//! name uses the applicant's pseudonym, so the order does not leak real names.
+
+use crate::models::application::ApplicationStatus;
+use crate::models::error::ChaosError;
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+use serde::Deserialize;
+use sqlx::{Postgres, Transaction};
+use std::ops::DerefMut;
+
+/// Response header carrying the cursor for the next page of a listing.
+pub const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+
+/// Largest page size a listing can be asked for.
+const MAX_LIMIT: i64 = 500;
+
+/// Keys an application listing can be sorted by.
+#[derive(Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplicationSort {
+    /// When the application was created
+    #[default]
+    CreatedAt,
+    /// Weighted mean rating, with unrated applications always last
+    Score,
+    /// Applicant name, or pseudonym under blind review
+    Name,
+}
+
+impl ApplicationSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ApplicationSort::CreatedAt => "created_at",
+            ApplicationSort::Score => "score",
+            ApplicationSort::Name => "name",
+        }
+    }
+}
+
+/// Direction of an application listing's sort.
+#[derive(Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Query parameters for filtering, sorting and paging an application listing.
+#[derive(Deserialize)]
+pub struct ApplicationListQuery {
+    /// Only applications for this role
+    #[serde(
+        default,
+        deserialize_with = "crate::models::serde_string::deserialize_option"
+    )]
+    pub role_id: Option<i64>,
+    /// Only applications with this public status
+    pub status: Option<ApplicationStatus>,
+    /// Only applications with this private status
+    pub private_status: Option<ApplicationStatus>,
+    /// Whether to list submitted applications or unsubmitted drafts
+    #[serde(default = "default_submitted")]
+    pub submitted: bool,
+    /// Only applications the current user has (or has not) rated
+    pub rated_by_me: Option<bool>,
+    /// Only applications with no ratings (or with at least one)
+    pub unrated: Option<bool>,
+    /// Only applications scoring at least this much, between 0 and 1
+    pub min_score: Option<f64>,
+    /// Only applications scoring at most this much, between 0 and 1
+    pub max_score: Option<f64>,
+    #[serde(default)]
+    pub sort: ApplicationSort,
+    #[serde(default)]
+    pub direction: SortDirection,
+    /// Page size, or every matching application if not given
+    pub limit: Option<i64>,
+    /// Cursor from the `X-Next-Cursor` header of the previous page
+    pub cursor: Option<String>,
+}
+
+fn default_submitted() -> bool {
+    true
+}
+
+/// One page of an application listing.
+pub struct ApplicationPage {
+    /// IDs of the applications on the page, in listing order
+    pub application_ids: Vec<i64>,
+    /// Cursor for the next page, or `None` if this is the last page
+    pub next_cursor: Option<String>,
+}
+
+impl ApplicationPage {
+    /// Response headers carrying the cursor for the next page, if there is one.
+    pub fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(cursor) = &self.next_cursor {
+            // Cursors are hex encoded, so are always valid header values.
+            if let Ok(value) = HeaderValue::from_str(cursor) {
+                headers.insert(HeaderName::from_static(NEXT_CURSOR_HEADER), value);
+            }
+        }
+
+        headers
+    }
+}
+
+/// Position of the last application on a page, in the listing's sort order.
+struct Cursor {
+    id: i64,
+    sort_number: f64,
+    sort_text: String,
+}
+
+impl ApplicationListQuery {
+    /// Finds one page of applications matching the query.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - Only list applications in this campaign, if given
+    /// * `scope_role_id` - Only list applications for this role, if given
+    /// * `current_user` - The ID of the user listing applications, for `rated_by_me`
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(ApplicationPage)` - The page of application IDs and the next cursor
+    /// * `Err(ChaosError)` - An error if the limit or cursor is invalid
+    pub async fn page(
+        &self,
+        campaign_id: Option<i64>,
+        scope_role_id: Option<i64>,
+        current_user: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<ApplicationPage, ChaosError> {
+        if let Some(limit) = self.limit {
+            if !(1..=MAX_LIMIT).contains(&limit) {
+                return Err(ChaosError::BadRequestWithMessage(format!(
+                    "Limit must be between 1 and {MAX_LIMIT}"
+                )));
+            }
+        }
+
+        let cursor = self
+            .cursor
+            .as_deref()
+            .map(|cursor| self.decode_cursor(cursor))
+            .transpose()?;
+        let descending = self.direction == SortDirection::Desc;
+
+        // The sort is reduced to a text key and a number key, always compared in
+        // that order. Sorting by name leaves the number key at zero, and the other
+        // sorts leave the text key empty. Number keys are negated for descending
+        // sorts, so they are always ascending and unrated applications, keyed by
+        // infinity, always come last.
+        let mut rows = sqlx::query!(
+            r#"
+                WITH matched AS (
+                    SELECT a.id, a.created_at,
+                        CASE WHEN c.blind_review AND NOT EXISTS(
+                            SELECT 1 FROM campaign_stages app_stage
+                            JOIN campaign_stages unblind ON unblind.id = c.unblind_stage_id
+                            WHERE app_stage.id = a.stage_id
+                            AND app_stage.position >= unblind.position
+                        )
+                            -- Mirrors `BlindReview::pseudonym`.
+                            THEN 'Applicant ' || a.pseudonym_number
+                            ELSE u.name
+                        END AS display_name,
+                        score.weighted_total
+                    FROM applications a
+                    JOIN users u ON u.id = a.user_id
+                    JOIN campaigns c ON c.id = a.campaign_id
+                    LEFT JOIN LATERAL (
+                        SELECT SUM(
+                            crc.weight * (category_avg.rating - crc.min_rating)
+                                / (crc.max_rating - crc.min_rating)
+                        ) / NULLIF(SUM(crc.weight), 0) AS weighted_total
+                        FROM (
+                            SELECT arc.campaign_rating_category_id,
+                                AVG(arc.rating)::DOUBLE PRECISION AS rating
+                            FROM application_rating_category_ratings arc
+                            JOIN application_ratings rated
+                                ON rated.id = arc.application_rating_id
+                            WHERE rated.application_id = a.id
+                            AND NOT EXISTS (
+                                SELECT 1 FROM review_conflicts rc
+                                WHERE rc.application_id = a.id
+                                AND rc.reviewer_id = rated.rater_id
+                            )
+                            GROUP BY arc.campaign_rating_category_id
+                        ) category_avg
+                        JOIN campaign_rating_categories crc
+                            ON crc.id = category_avg.campaign_rating_category_id
+                    ) score ON true
+                    WHERE ($1::BIGINT IS NULL OR a.campaign_id = $1)
+                    AND ($2::BIGINT IS NULL OR EXISTS(
+                        SELECT 1 FROM application_roles ar
+                        WHERE ar.application_id = a.id AND ar.campaign_role_id = $2
+                    ))
+                    AND ($3::BIGINT IS NULL OR EXISTS(
+                        SELECT 1 FROM application_roles ar
+                        WHERE ar.application_id = a.id AND ar.campaign_role_id = $3
+                    ))
+                    AND ($4::application_status IS NULL OR a.status = $4)
+                    AND ($5::application_status IS NULL OR a.private_status = $5)
+                    AND a.submitted = $6
+                    AND ($7::BOOLEAN IS NULL OR $7 = EXISTS(
+                        SELECT 1 FROM application_ratings mine
+                        WHERE mine.application_id = a.id AND mine.rater_id = $8
+                    ))
+                    AND ($9::BOOLEAN IS NULL OR $9 = NOT EXISTS(
+                        SELECT 1 FROM application_ratings rated
+                        WHERE rated.application_id = a.id
+                        AND NOT EXISTS (
+                            SELECT 1 FROM review_conflicts rc
+                            WHERE rc.application_id = a.id AND rc.reviewer_id = rated.rater_id
+                        )
+                    ))
+                    AND ($10::DOUBLE PRECISION IS NULL OR score.weighted_total >= $10)
+                    AND ($11::DOUBLE PRECISION IS NULL OR score.weighted_total <= $11)
+                ),
+                keyed AS (
+                    SELECT id,
+                        CASE WHEN $12::TEXT = 'name' THEN LOWER(display_name) ELSE '' END
+                            AS sort_text,
+                        CASE $12
+                            WHEN 'created_at' THEN EXTRACT(EPOCH FROM created_at)::DOUBLE PRECISION
+                                * CASE WHEN $13::BOOLEAN THEN -1 ELSE 1 END
+                            WHEN 'score' THEN COALESCE(
+                                weighted_total * CASE WHEN $13 THEN -1 ELSE 1 END,
+                                'Infinity'::DOUBLE PRECISION
+                            )
+                            ELSE 0
+                        END AS sort_number
+                    FROM matched
+                )
+                SELECT id AS "id!", sort_text AS "sort_text!", sort_number AS "sort_number!"
+                FROM keyed
+                WHERE $14::BIGINT IS NULL
+                OR CASE WHEN $13 THEN sort_text < $15::TEXT ELSE sort_text > $15 END
+                OR (sort_text = $15 AND (
+                    sort_number > $16::DOUBLE PRECISION
+                    OR (sort_number = $16 AND id > $14)
+                ))
+                ORDER BY
+                    CASE WHEN $13 THEN sort_text END DESC,
+                    CASE WHEN NOT $13 THEN sort_text END ASC,
+                    sort_number ASC,
+                    id ASC
+                LIMIT $17
+            "#,
+            campaign_id,
+            scope_role_id,
+            self.role_id,
+            self.status.clone() as Option<ApplicationStatus>,
+            self.private_status.clone() as Option<ApplicationStatus>,
+            self.submitted,
+            self.rated_by_me,
+            current_user,
+            self.unrated,
+            self.min_score,
+            self.max_score,
+            self.sort.as_str(),
+            descending,
+            cursor.as_ref().map(|cursor| cursor.id),
+            cursor.as_ref().map(|cursor| cursor.sort_text.clone()),
+            cursor.as_ref().map(|cursor| cursor.sort_number),
+            // Fetch one extra row to tell whether there is a next page.
+            self.limit.map(|limit| limit + 1)
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let mut next_cursor = None;
+        if let Some(limit) = self.limit {
+            if rows.len() as i64 > limit {
+                rows.truncate(limit as usize);
+                next_cursor = rows.last().map(|last| {
+                    self.encode_cursor(&Cursor {
+                        id: last.id,
+                        sort_number: last.sort_number,
+                        sort_text: last.sort_text.clone(),
+                    })
+                });
+            }
+        }
+
+        Ok(ApplicationPage {
+            application_ids: rows.into_iter().map(|row| row.id).collect(),
+            next_cursor,
+        })
+    }
+
+    /// Encodes a cursor, tagged with the sort it belongs to, as hex.
+    fn encode_cursor(&self, cursor: &Cursor) -> String {
+        format!(
+            "{}:{}:{}:{}:{}",
+            self.sort.as_str(),
+            self.direction == SortDirection::Desc,
+            cursor.id,
+            cursor.sort_number,
+            cursor.sort_text
+        )
+        .bytes()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+    }
+
+    /// Decodes a cursor, checking it was issued for the same sort.
+    fn decode_cursor(&self, encoded: &str) -> Result<Cursor, ChaosError> {
+        let invalid = || ChaosError::BadRequestWithMessage("Invalid cursor".to_string());
+
+        if !encoded.len().is_multiple_of(2) {
+            return Err(invalid());
+        }
+        let bytes = (0..encoded.len())
+            .step_by(2)
+            .map(|i| {
+                encoded
+                    .get(i..i + 2)
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+            })
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(invalid)?;
+        let decoded = String::from_utf8(bytes).map_err(|_| invalid())?;
+
+        let mut parts = decoded.splitn(5, ':');
+        let (Some(sort), Some(descending), Some(id), Some(sort_number), Some(sort_text)) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            return Err(invalid());
+        };
+
+        if sort != self.sort.as_str()
+            || descending != (self.direction == SortDirection::Desc).to_string()
+        {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Cursor was issued for a different sort".to_string(),
+            ));
+        }
+
+        Ok(Cursor {
+            id: id.parse().map_err(|_| invalid())?,
+            sort_number: sort_number.parse().map_err(|_| invalid())?,
+            sort_text: sort_text.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(sort: ApplicationSort, direction: SortDirection) -> ApplicationListQuery {
+        ApplicationListQuery {
+            role_id: None,
+            status: None,
+            private_status: None,
+            submitted: true,
+            rated_by_me: None,
+            unrated: None,
+            min_score: None,
+            max_score: None,
+            sort,
+            direction,
+            limit: Some(20),
+            cursor: None,
+        }
+    }
+
+    fn round_trip(query: &ApplicationListQuery, cursor: Cursor) -> Cursor {
+        let encoded = query.encode_cursor(&cursor);
+        assert!(encoded.bytes().all(|byte| byte.is_ascii_hexdigit()));
+        query
+            .decode_cursor(&encoded)
+            .expect("an encoded cursor should decode")
+    }
+
+    #[test]
+    fn cursor_round_trips_an_unrated_position() {
+        let query = query(ApplicationSort::Score, SortDirection::Asc);
+
+        let decoded = round_trip(
+            &query,
+            Cursor {
+                id: 42,
+                sort_number: f64::INFINITY,
+                sort_text: String::new(),
+            },
+        );
+
+        assert_eq!(decoded.id, 42);
+        assert_eq!(decoded.sort_number, f64::INFINITY);
+        assert_eq!(decoded.sort_text, "");
+    }
+
+    #[test]
+    fn cursor_round_trips_descending_sorts() {
+        let by_score = query(ApplicationSort::Score, SortDirection::Desc);
+        let decoded = round_trip(
+            &by_score,
+            Cursor {
+                id: 7,
+                sort_number: -0.625,
+                sort_text: String::new(),
+            },
+        );
+        assert_eq!(decoded.id, 7);
+        assert_eq!(decoded.sort_number, -0.625);
+
+        let unrated = round_trip(
+            &by_score,
+            Cursor {
+                id: 8,
+                sort_number: f64::INFINITY,
+                sort_text: String::new(),
+            },
+        );
+        assert_eq!(unrated.sort_number, f64::INFINITY);
+
+        let by_name = query(ApplicationSort::Name, SortDirection::Desc);
+        let decoded = round_trip(
+            &by_name,
+            Cursor {
+                id: 9,
+                sort_number: 0.0,
+                sort_text: "Zoë: Applicant 3".to_string(),
+            },
+        );
+        assert_eq!(decoded.id, 9);
+        assert_eq!(decoded.sort_text, "Zoë: Applicant 3");
+    }
+
+    #[test]
+    fn cursor_is_rejected_for_a_different_sort() {
+        let descending = query(ApplicationSort::Score, SortDirection::Desc);
+        let encoded = descending.encode_cursor(&Cursor {
+            id: 1,
+            sort_number: 0.5,
+            sort_text: String::new(),
+        });
+
+        let ascending = query(ApplicationSort::Score, SortDirection::Asc);
+        assert!(ascending.decode_cursor(&encoded).is_err());
+        let by_name = query(ApplicationSort::Name, SortDirection::Desc);
+        assert!(by_name.decode_cursor(&encoded).is_err());
+    }
+
+    #[test]
+    fn malformed_cursors_are_rejected() {
+        let query = query(ApplicationSort::CreatedAt, SortDirection::Asc);
+
+        assert!(query.decode_cursor("abc").is_err());
+        assert!(query.decode_cursor("zz").is_err());
+        assert!(query.decode_cursor("").is_err());
+    }
+}
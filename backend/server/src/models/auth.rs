@@ -9,9 +9,12 @@ use crate::models::error::ChaosError;
 use crate::service::answer::user_is_answer_owner;
 use crate::service::application::{user_is_application_admin, user_is_application_owner};
 use crate::service::auth::{assert_is_super_user, extract_user_id_from_request};
-use crate::service::campaign::{user_is_campaign_admin, user_is_campaign_org_member};
+use crate::service::campaign::{
+    user_is_campaign_admin, user_is_campaign_applicant, user_is_campaign_org_member,
+};
 use crate::service::comment::user_is_comment_author;
 use crate::service::email_template::user_is_email_template_admin;
+use crate::service::interview::user_is_interview_timeslot_admin;
 use crate::service::offer::{assert_user_is_offer_admin, assert_user_is_offer_recipient};
 use crate::service::organisation::{
     assert_user_is_organisation_admin, assert_user_is_organisation_admin_or_super_user,
@@ -21,7 +24,10 @@ use crate::service::rating::{
     assert_user_is_application_reviewer_given_rating_id, assert_user_is_organisation_member,
     assert_user_is_rating_creator_and_organisation_member,
 };
-use crate::service::role::user_is_role_admin;
+use crate::service::review_conflict::{
+    assert_user_is_not_conflicted, assert_user_is_not_conflicted_given_rating_id,
+};
+use crate::service::role::{user_is_role_admin, user_is_role_applicant_or_admin};
 use axum::extract::{FromRef, FromRequestParts, Path};
 use axum::http::request::Parts;
 use axum::response::{IntoResponse, Redirect, Response};
@@ -285,6 +291,45 @@ where
     }
 }
 
+/// Campaign applicant information.
+///
+/// Contains the user ID of a user who has submitted an application to a specific campaign.
+pub struct CampaignApplicant {
+    /// ID of the campaign applicant
+    pub user_id: i64,
+}
+
+/// Extractor for campaign applicants.
+///
+/// This extractor is used in route handlers to ensure that the request
+/// comes from a user who has submitted an application to the campaign.
+#[async_trait]
+impl<S> FromRequestParts<S> for CampaignApplicant
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = ChaosError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let app_state = AppState::from_ref(state);
+        let user_id = extract_user_id_from_request(parts, &app_state).await?;
+
+        let campaign_id = *parts
+            .extract::<Path<HashMap<String, i64>>>()
+            .await
+            .map_err(|_| ChaosError::BadRequest)?
+            .get("campaign_id")
+            .ok_or(ChaosError::BadRequest)?;
+
+        let mut tx = app_state.db.begin().await?;
+        user_is_campaign_applicant(user_id, campaign_id, &mut tx).await?;
+        tx.commit().await?;
+
+        Ok(CampaignApplicant { user_id })
+    }
+}
+
 /// Role administrator information.
 ///
 /// Contains the user ID of a user with role administrator privileges.
@@ -322,6 +367,44 @@ where
     }
 }
 
+/// Role applicant or administrator information.
+///
+/// Contains the user ID of a user who applied to a specific role, or who has
+/// administrator privileges for it.
+pub struct RoleApplicantOrAdmin {
+    /// ID of the role applicant or administrator
+    pub user_id: i64,
+}
+
+/// Extractor for role applicants and administrators.
+///
+/// This extractor is used in route handlers to ensure that the request
+/// comes from a user who applied to the role or who administers it.
+#[async_trait]
+impl<S> FromRequestParts<S> for RoleApplicantOrAdmin
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = ChaosError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let app_state = AppState::from_ref(state);
+        let user_id = extract_user_id_from_request(parts, &app_state).await?;
+
+        let Path(role_id) = parts
+            .extract::<Path<i64>>()
+            .await
+            .map_err(|_| ChaosError::BadRequest)?;
+
+        let mut tx = app_state.db.begin().await?;
+        user_is_role_applicant_or_admin(user_id, role_id, &mut tx).await?;
+        tx.commit().await?;
+
+        Ok(RoleApplicantOrAdmin { user_id })
+    }
+}
+
 /// Application administrator information.
 ///
 /// Contains the user ID of a user with application administrator privileges.
@@ -392,6 +475,7 @@ where
 
         let mut tx = app_state.db.begin().await?;
         assert_user_is_organisation_member(user_id, application_id, &mut tx).await?;
+        assert_user_is_not_conflicted(user_id, application_id, &mut tx).await?;
         tx.commit().await?;
 
         Ok(ApplicationReviewerGivenApplicationId { user_id })
@@ -469,6 +553,7 @@ where
 
         let mut tx = app_state.db.begin().await?;
         assert_user_is_application_reviewer_given_rating_id(user_id, rating_id, &mut tx).await?;
+        assert_user_is_not_conflicted_given_rating_id(user_id, rating_id, &mut tx).await?;
         tx.commit().await?;
 
         Ok(ApplicationReviewerGivenRatingId { user_id })
@@ -509,6 +594,7 @@ where
 
         let mut tx = app_state.db.begin().await?;
         assert_user_is_rating_creator_and_organisation_member(user_id, rating_id, &mut tx).await?;
+        assert_user_is_not_conflicted_given_rating_id(user_id, rating_id, &mut tx).await?;
         tx.commit().await?;
 
         Ok(RatingCreator { user_id })
@@ -661,6 +747,7 @@ where
             .await
             .map_err(|_| ChaosError::BadRequest)?;
 
+        let application_id = *ids.get("application_id").ok_or(ChaosError::BadRequest)?;
         let comment_id = *ids.get("comment_id").ok_or(ChaosError::BadRequest)?;
 
         let mut tx = app_state.db.begin().await?;
@@ -669,6 +756,7 @@ where
         if !is_owner {
             return Err(ChaosError::Unauthorized);
         }
+        assert_user_is_not_conflicted(user_id, application_id, &mut tx).await?;
 
         tx.commit().await?;
 
@@ -831,3 +919,44 @@ where
         Ok(OfferRecipient { user_id })
     }
 }
+
+/// Interview timeslot administrator information.
+///
+/// Contains the user ID of a user who has administrative privileges for a specific
+/// interview timeslot.
+pub struct InterviewTimeslotAdmin {
+    /// ID of the interview timeslot administrator
+    pub user_id: i64,
+}
+
+/// Extractor for interview timeslot administrators.
+///
+/// This extractor is used in route handlers to ensure that the request
+/// comes from a user with administrative privileges for the campaign
+/// the interview timeslot belongs to.
+#[async_trait]
+impl<S> FromRequestParts<S> for InterviewTimeslotAdmin
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = ChaosError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let app_state = AppState::from_ref(state);
+        let user_id = extract_user_id_from_request(parts, &app_state).await?;
+
+        let timeslot_id = *parts
+            .extract::<Path<HashMap<String, i64>>>()
+            .await
+            .map_err(|_| ChaosError::BadRequest)?
+            .get("timeslot_id")
+            .ok_or(ChaosError::BadRequest)?;
+
+        let mut tx = app_state.db.begin().await?;
+        user_is_interview_timeslot_admin(user_id, timeslot_id, &mut tx).await?;
+        tx.commit().await?;
+
+        Ok(InterviewTimeslotAdmin { user_id })
+    }
+}
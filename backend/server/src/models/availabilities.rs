@@ -1,15 +1,19 @@
-use std::{hash::Hash, ops::DerefMut};
+use std::{collections::HashSet, hash::Hash, ops::DerefMut};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use snowflake::SnowflakeIdGenerator;
 use sqlx::{Postgres, Transaction};
 
+use crate::models::campaign::Campaign;
 use crate::models::error::ChaosError;
 
-#[derive(Serialize, Deserialize, sqlx::FromRow, PartialEq)]
+/// Length of a single availability slot, in minutes.
+pub const AVAILABILITY_SLOT_MINUTES: i64 = 15;
+
+#[derive(Serialize, Deserialize, sqlx::FromRow, PartialEq, Clone, Debug)]
 pub struct Availability {
-    start_time: DateTime<Utc>,
+    pub start_time: DateTime<Utc>,
 }
 
 impl Eq for Availability {}
@@ -25,6 +29,25 @@ pub struct UserCampaignId {
     pub id: i64,
 }
 
+/// Aggregated availability for a single slot across a campaign.
+///
+/// Lists which interviewers (members of the campaign's organisation) and
+/// applicants are available for the slot starting at `start_time`.
+#[derive(Serialize, sqlx::FromRow)]
+pub struct CampaignAvailabilitySlot {
+    pub start_time: DateTime<Utc>,
+    #[serde(serialize_with = "crate::models::serde_string::serialize_vec")]
+    pub interviewers: Vec<i64>,
+    #[serde(serialize_with = "crate::models::serde_string::serialize_vec")]
+    pub applicants: Vec<i64>,
+}
+
+/// Request body for replacing a user's availability grid, or removing some of its slots.
+#[derive(Deserialize)]
+pub struct AvailabilityUpdate {
+    pub availabilities: Vec<Availability>,
+}
+
 pub struct Availabilities;
 
 impl Availabilities {
@@ -41,20 +64,19 @@ impl Availabilities {
 
     // ------------------------ Operations -----------------------
 
-    /// Creates an ID for a user-campaign availability pair
+    /// Creates an ID for a user-campaign availability pair, or returns the existing
+    /// one if the user has already submitted availability for the campaign
     ///
     /// # Arguments
-    /// * `user_id` - ID of the interviewer
-    /// * `campaign_id` - ID of the campaign in which the user will be interviewing
+    /// * `user_id` - ID of the applicant or interviewer
+    /// * `campaign_id` - ID of the campaign the availability is for
     /// * `snowflake_generator` - A generator for creating unique IDs
     /// * `transaction` - A mutable reference to the database transaction
     ///
     /// # Returns
     /// Returns a `Result` containing either:
-    /// * `Ok(id)` - If the UC pair was created successfully, where id is its ID
+    /// * `Ok(id)` - The ID of the UC pair
     /// * `Err(ChaosError)` - If creation fails
-    ///
-
     pub async fn create_user_campaign_availability(
         user_id: i64,
         campaign_id: i64,
@@ -63,19 +85,23 @@ impl Availabilities {
     ) -> Result<i64, ChaosError> {
         let id = snowflake_generator.real_time_generate();
 
-        sqlx::query!(
+        let availability_id = sqlx::query!(
             "
                 INSERT INTO user_campaign_availabilities (id, user_id, campaign_id)
                     VALUES ($1, $2, $3)
+                ON CONFLICT (user_id, campaign_id)
+                    DO UPDATE SET user_id = EXCLUDED.user_id
+                RETURNING id
             ",
             id,
             user_id,
             campaign_id
         )
-        .execute(transaction.deref_mut())
-        .await?;
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .id;
 
-        Ok(id)
+        Ok(availability_id)
     }
 
     /// Creates availability slots in bulk for a given user-campaign.
@@ -90,7 +116,6 @@ impl Availabilities {
     /// Returns a `Result` containing either:
     /// * `Ok(())` - If ALL the timeslots were successfully created
     /// * `Err(ChaosError)` - If creation of ANY fails
-
     pub async fn create_availability_slots(
         availability_id: i64,
         availabilities: Vec<Availability>,
@@ -113,17 +138,92 @@ impl Availabilities {
         Ok(())
     }
 
-    /// Gets all available slots for a given UC pair
+    /// Replaces a user's entire availability grid for a campaign.
+    ///
+    /// The UC pair is created if it does not exist yet, and all of its existing
+    /// slots are swapped for the given ones. Callers should run this inside a
+    /// single transaction so the grid is never observed half-replaced.
     ///
     /// # Arguments
-    /// * `user_id` - ID of the interviewer
-    /// * `campaign_id` - ID of the campaign in which the user will be interviewing
+    /// * `user_id` - ID of the applicant or interviewer
+    /// * `campaign_id` - ID of the campaign the availability is for
+    /// * `availabilities`- Vec of start times, must align to 15min boundaries
+    /// * `snowflake_generator` - A generator for creating unique IDs
+    /// * `transaction` - A mutable reference to the database transaction
     ///
     /// # Returns
     /// Returns a `Result` containing either
-    /// * `Ok(Vec<Availabilities>)` - a Vec of all the availabilities
-    /// * `Err(ChaosError)`- If no such user campaign pair found
+    /// * `Ok(())` - if the grid was replaced successfully
+    /// * `Err(ChaosError)` - if any slot is invalid or the update fails
+    pub async fn replace_availabilities(
+        user_id: i64,
+        campaign_id: i64,
+        availabilities: Vec<Availability>,
+        snowflake_generator: &mut SnowflakeIdGenerator,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let campaign = Campaign::get(campaign_id, transaction).await?;
+        let slot_length = Duration::minutes(AVAILABILITY_SLOT_MINUTES);
+
+        for availability in availabilities.iter() {
+            let start_time = availability.start_time;
+            if start_time.timestamp() % slot_length.num_seconds() != 0
+                || start_time.timestamp_subsec_nanos() != 0
+            {
+                return Err(ChaosError::BadRequestWithMessage(
+                    "Availability slots must align to 15 minute boundaries".to_string(),
+                ));
+            }
+
+            let starts_too_early = campaign
+                .interview_period_starts_at
+                .is_some_and(|starts_at| start_time < starts_at);
+            let ends_too_late = campaign
+                .interview_period_ends_at
+                .is_some_and(|ends_at| start_time + slot_length > ends_at);
+            if starts_too_early || ends_too_late {
+                return Err(ChaosError::BadRequestWithMessage(
+                    "Availability slots must be within the campaign's interview period".to_string(),
+                ));
+            }
+        }
+
+        let availabilities: Vec<Availability> = availabilities
+            .into_iter()
+            .collect::<HashSet<Availability>>()
+            .into_iter()
+            .collect();
+
+        let availability_id = Self::create_user_campaign_availability(
+            user_id,
+            campaign_id,
+            snowflake_generator,
+            transaction,
+        )
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM availability_slots WHERE availability_id = $1",
+            availability_id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Self::create_availability_slots(availability_id, availabilities, transaction).await?;
 
+        Ok(())
+    }
+
+    /// Gets all available slots for a given UC pair
+    ///
+    /// # Arguments
+    /// * `availability_id` - ID of the UC pair
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either
+    /// * `Ok(Vec<Availability>)` - a Vec of all the availabilities, earliest first
+    /// * `Err(ChaosError)` - If retrieval fails
     pub async fn get_availability_slots(
         availability_id: i64,
         transaction: &mut Transaction<'_, Postgres>,
@@ -134,7 +234,8 @@ impl Availabilities {
                 SELECT start_time
                 FROM availability_slots
                 WHERE availability_id = $1
-                ",
+                ORDER BY start_time
+            ",
             availability_id
         )
         .fetch_all(transaction.deref_mut())
@@ -146,19 +247,20 @@ impl Availabilities {
     /// Gets the UC id for a given user_id and campaign id
     ///
     /// # Arguments
-    /// * `user_id` - ID of the interviewer
-    /// * `campaign_id` - ID of the campaign in which the user will be interviewing
+    /// * `user_id` - ID of the applicant or interviewer
+    /// * `campaign_id` - ID of the campaign
+    /// * `transaction` - A mutable reference to the database transaction
     ///
     /// # Returns
     /// Returns a `Result` containing either
-    /// * `Ok(i64)` - the UC id
-    /// * `Err(ChaosError)` - If no such user campaign pair is found
-
+    /// * `Ok(Some(UserCampaignId))` - the UC id
+    /// * `Ok(None)` - if the user has not submitted availability for the campaign
+    /// * `Err(ChaosError)` - If retrieval fails
     pub async fn get_user_campaign_id(
         user_id: i64,
         campaign_id: i64,
         transaction: &mut Transaction<'_, Postgres>,
-    ) -> Result<UserCampaignId, ChaosError> {
+    ) -> Result<Option<UserCampaignId>, ChaosError> {
         let user_campaign_id = sqlx::query_as!(
             UserCampaignId,
             "
@@ -170,24 +272,50 @@ impl Availabilities {
             user_id,
             campaign_id
         )
-        .fetch_one(transaction.deref_mut())
+        .fetch_optional(transaction.deref_mut())
         .await?;
 
         Ok(user_campaign_id)
     }
 
+    /// Gets all available slots for a given user in a campaign
+    ///
+    /// # Arguments
+    /// * `user_id` - ID of the applicant or interviewer
+    /// * `campaign_id` - ID of the campaign
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either
+    /// * `Ok(Vec<Availability>)` - a Vec of all the availabilities, empty if none were submitted
+    /// * `Err(ChaosError)` - If retrieval fails
+    pub async fn get_user_availabilities(
+        user_id: i64,
+        campaign_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<Availability>, ChaosError> {
+        match Self::get_user_campaign_id(user_id, campaign_id, transaction).await? {
+            Some(user_campaign_id) => {
+                Self::get_availability_slots(user_campaign_id.id, transaction).await
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
     /// Deletes the given availability slots for a given user
     ///
+    /// Slots the user had not submitted are ignored.
+    ///
     /// # Arguments
-    /// * `user_id` - ID of the interviewer
-    /// * `campaign_id` - ID of the campaign in which the user will be interviewing
-    /// * `availabilities`- Vec of start times, must align to 15min boundaries
+    /// * `user_id` - ID of the applicant or interviewer
+    /// * `campaign_id` - ID of the campaign
+    /// * `availabilities`- Vec of start times to remove
+    /// * `transaction` - A mutable reference to the database transaction
     ///
     /// # Returns
     /// Returns a `Result` containing either
     /// * `Ok(())` - if update was successful
     /// * `Err(ChaosError)` - if update was unsuccessful
-
     pub async fn delete_availabilities(
         user_id: i64,
         campaign_id: i64,
@@ -214,4 +342,51 @@ impl Availabilities {
 
         Ok(())
     }
+
+    /// Gets the aggregated availability of every user in a campaign
+    ///
+    /// Users who are members of the campaign's organisation are counted as
+    /// interviewers, everyone else as applicants.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - ID of the campaign
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either
+    /// * `Ok(Vec<CampaignAvailabilitySlot>)` - each slot with at least one available user
+    /// * `Err(ChaosError)` - If retrieval fails
+    pub async fn get_campaign_availabilities(
+        campaign_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<CampaignAvailabilitySlot>, ChaosError> {
+        let slots = sqlx::query_as!(
+            CampaignAvailabilitySlot,
+            r#"
+                SELECT
+                    s.start_time,
+                    COALESCE(
+                        array_agg(uc.user_id) FILTER (WHERE m.user_id IS NOT NULL),
+                        '{}'
+                    ) AS "interviewers!",
+                    COALESCE(
+                        array_agg(uc.user_id) FILTER (WHERE m.user_id IS NULL),
+                        '{}'
+                    ) AS "applicants!"
+                FROM availability_slots s
+                JOIN user_campaign_availabilities uc ON uc.id = s.availability_id
+                JOIN campaigns c ON c.id = uc.campaign_id
+                LEFT JOIN organisation_members m
+                    ON m.organisation_id = c.organisation_id AND m.user_id = uc.user_id
+                WHERE uc.campaign_id = $1
+                GROUP BY s.start_time
+                ORDER BY s.start_time
+            "#,
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        Ok(slots)
+    }
 }
@@ -0,0 +1,169 @@
+//! Blind review for Chaos campaigns.
+//!
+//! When a campaign has blind review turned on, reviewer-facing endpoints replace the
+//! applicant's name, email, zID, gender and pronouns with a stable pseudonym. Each
+//! application is numbered in order within its campaign when it is created, so
+//! pseudonyms never collide within a campaign. Admins can choose a pipeline stage at
+//! which applications are unblinded, or turn blind review off to unblind the whole
+//! campaign.
+
+use crate::models::error::ChaosError;
+use crate::models::user::UserDetails;
+use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, Transaction};
+use std::collections::HashMap;
+use std::ops::DerefMut;
+
+/// A campaign's blind review settings.
+#[derive(Deserialize, Serialize)]
+pub struct BlindReviewSettings {
+    /// Whether applicant identity is hidden from reviewers
+    pub blind_review: bool,
+    /// Stage from which applications are shown with identity, if any
+    #[serde(
+        default,
+        serialize_with = "crate::models::serde_string::serialize_option",
+        deserialize_with = "crate::models::serde_string::deserialize_option"
+    )]
+    pub unblind_stage_id: Option<i64>,
+}
+
+pub struct BlindReview;
+
+impl BlindReview {
+    /// Retrieves a campaign's blind review settings.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(BlindReviewSettings)` - The campaign's settings
+    /// * `Err(ChaosError)` - An error if the campaign does not exist or retrieval fails
+    pub async fn get_settings(
+        campaign_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<BlindReviewSettings, ChaosError> {
+        let settings = sqlx::query_as!(
+            BlindReviewSettings,
+            "SELECT blind_review, unblind_stage_id FROM campaigns WHERE id = $1",
+            campaign_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        Ok(settings)
+    }
+
+    /// Updates a campaign's blind review settings.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `settings` - The new settings
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the settings were updated
+    /// * `Err(ChaosError)` - An error if the unblind stage is not in the campaign's pipeline
+    pub async fn update_settings(
+        campaign_id: i64,
+        settings: BlindReviewSettings,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        if let Some(stage_id) = settings.unblind_stage_id {
+            let exists = sqlx::query!(
+                "
+                    SELECT EXISTS(
+                        SELECT 1 FROM campaign_stages WHERE id = $1 AND campaign_id = $2
+                    )
+                ",
+                stage_id,
+                campaign_id
+            )
+            .fetch_one(transaction.deref_mut())
+            .await?
+            .exists
+            .expect("`exists` should always exist in this query result");
+
+            if !exists {
+                return Err(ChaosError::BadRequestWithMessage(
+                    "Unblind stage is not part of this campaign's pipeline".to_string(),
+                ));
+            }
+        }
+
+        sqlx::query!(
+            "
+                UPDATE campaigns SET blind_review = $2, unblind_stage_id = $3
+                WHERE id = $1
+                RETURNING id
+            ",
+            campaign_id,
+            settings.blind_review,
+            settings.unblind_stage_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Finds which of the given applications must be shown without applicant identity.
+    ///
+    /// # Arguments
+    /// * `application_ids` - The IDs of the applications being shown
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(HashMap<i64, String>)` - The pseudonyms of the blinded applications, by ID
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn blinded_applications(
+        application_ids: &[i64],
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<HashMap<i64, String>, ChaosError> {
+        let blinded = sqlx::query!(
+            "
+                SELECT a.id, a.pseudonym_number
+                FROM applications a
+                JOIN campaigns c ON c.id = a.campaign_id
+                WHERE a.id = ANY($1) AND c.blind_review = true
+                AND NOT EXISTS(
+                    SELECT 1 FROM campaign_stages app_stage
+                    JOIN campaign_stages unblind ON unblind.id = c.unblind_stage_id
+                    WHERE app_stage.id = a.stage_id AND app_stage.position >= unblind.position
+                )
+            ",
+            application_ids
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?
+        .into_iter()
+        .map(|application| {
+            (
+                application.id,
+                Self::pseudonym(application.pseudonym_number),
+            )
+        })
+        .collect();
+
+        Ok(blinded)
+    }
+
+    /// Returns the pseudonym shown in place of an applicant's name, from the
+    /// application's number within its campaign.
+    pub fn pseudonym(pseudonym_number: i32) -> String {
+        format!("Applicant {pseudonym_number}")
+    }
+
+    /// Replaces an applicant's identifying details with their pseudonym.
+    pub fn redact_user(user: &mut UserDetails, pseudonym: &str) {
+        user.name = pseudonym.to_string();
+        user.email = String::new();
+        user.zid = None;
+        user.gender = None;
+        user.pronouns = None;
+    }
+}
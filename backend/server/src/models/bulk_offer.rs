@@ -0,0 +1,374 @@
+//! Bulk outcome processing for Chaos.
+//!
+//! Creates offers and queues outcome emails for many applicants at once. Every
+//! email is rendered on the server from the campaign's email templates, and the
+//! whole batch is checked before anything is written, so a batch either goes out
+//! in full or not at all. A dry run renders the same report without creating
+//! offers or queueing emails.
+
+use crate::models::email::{EmailContext, EmailQueue, EmailType};
+use crate::models::email_template::EmailTemplate;
+use crate::models::error::ChaosError;
+use crate::models::offer::Offer;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use snowflake::SnowflakeIdGenerator;
+use sqlx::{Postgres, Transaction};
+use std::collections::{HashMap, HashSet};
+use std::ops::DerefMut;
+
+/// One applicant in a bulk outcome request.
+#[derive(Deserialize)]
+pub struct BulkOfferItem {
+    #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
+    pub application_id: i64,
+    /// The role being offered, or the role the applicant is being rejected from
+    #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
+    pub role_id: i64,
+    /// Either `Accept` or `Reject`
+    pub outcome: EmailType,
+}
+
+/// A batch of outcomes to process.
+#[derive(Deserialize)]
+pub struct BulkOfferRequest {
+    pub items: Vec<BulkOfferItem>,
+    /// Template used for offers
+    #[serde(
+        default,
+        deserialize_with = "crate::models::serde_string::deserialize_option"
+    )]
+    pub accept_template_id: Option<i64>,
+    /// Template used for rejections
+    #[serde(
+        default,
+        deserialize_with = "crate::models::serde_string::deserialize_option"
+    )]
+    pub reject_template_id: Option<i64>,
+    /// When the offers expire
+    pub expiry: DateTime<Utc>,
+    /// Whether to only report what would be sent
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// The email one applicant will receive.
+#[derive(Serialize)]
+pub struct BulkOfferRecipient {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub application_id: i64,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub role_id: i64,
+    pub role_name: String,
+    pub outcome: EmailType,
+    pub recipient_name: String,
+    pub recipient_email_address: String,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub email_template_id: i64,
+    pub subject: String,
+    pub body: String,
+    /// The created offer, or `None` for rejections and dry runs
+    #[serde(serialize_with = "crate::models::serde_string::serialize_option")]
+    pub offer_id: Option<i64>,
+}
+
+/// A single outcome email to render and queue.
+pub struct OutcomeEmail {
+    pub application_id: i64,
+    pub role_id: i64,
+    /// Either `Accept` or `Reject`
+    pub outcome: EmailType,
+    pub email_template_id: i64,
+    /// When the offer expires, also available to rejection templates
+    pub expiry: DateTime<Utc>,
+}
+
+/// The result of processing a batch of outcomes.
+#[derive(Serialize)]
+pub struct BulkOfferReport {
+    pub dry_run: bool,
+    pub recipients: Vec<BulkOfferRecipient>,
+}
+
+pub struct BulkOffer;
+
+impl BulkOffer {
+    /// Creates offers and queues outcome emails for a batch of applicants, using one
+    /// template per outcome type.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `request` - The outcomes to process
+    /// * `snowflake_generator` - Generator for the new offer IDs
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(BulkOfferReport)` - Who was, or for a dry run would be, emailed and with what
+    /// * `Err(ChaosError)` - An error if any item is invalid or processing fails
+    pub async fn process(
+        campaign_id: i64,
+        request: BulkOfferRequest,
+        snowflake_generator: &mut SnowflakeIdGenerator,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<BulkOfferReport, ChaosError> {
+        let mut outcomes = Vec::with_capacity(request.items.len());
+        for item in request.items {
+            let email_template_id = match item.outcome {
+                EmailType::Accept => request.accept_template_id.ok_or_else(|| {
+                    ChaosError::BadRequestWithMessage(
+                        "An accept template is required to make offers".to_string(),
+                    )
+                })?,
+                EmailType::Reject => request.reject_template_id.ok_or_else(|| {
+                    ChaosError::BadRequestWithMessage(
+                        "A reject template is required to send rejections".to_string(),
+                    )
+                })?,
+                EmailType::Interview => {
+                    return Err(ChaosError::BadRequestWithMessage(
+                        "Outcome must be either Accept or Reject".to_string(),
+                    ))
+                }
+            };
+
+            outcomes.push(OutcomeEmail {
+                application_id: item.application_id,
+                role_id: item.role_id,
+                outcome: item.outcome,
+                email_template_id,
+                expiry: request.expiry,
+            });
+        }
+
+        Self::queue_outcomes(
+            campaign_id,
+            outcomes,
+            request.dry_run,
+            snowflake_generator,
+            transaction,
+        )
+        .await
+    }
+
+    /// Renders and queues outcome emails, creating an offer for each acceptance.
+    ///
+    /// Every email is rendered from an email template of the campaign's organisation
+    /// and sent to the applicant's own address. Every applicant must have submitted
+    /// an application to the campaign, applied for the given role, and not already
+    /// hold an offer that is still open or accepted. Offers are sent straight away,
+    /// and like every outcome email are held until the campaign's outcome release.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `outcomes` - The outcomes to process
+    /// * `dry_run` - Whether to only render the emails
+    /// * `snowflake_generator` - Generator for the new offer IDs
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(BulkOfferReport)` - Who was, or for a dry run would be, emailed and with what
+    /// * `Err(ChaosError)` - An error if any outcome is invalid or processing fails
+    pub async fn queue_outcomes(
+        campaign_id: i64,
+        outcomes: Vec<OutcomeEmail>,
+        dry_run: bool,
+        snowflake_generator: &mut SnowflakeIdGenerator,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<BulkOfferReport, ChaosError> {
+        if outcomes.is_empty() {
+            return Err(ChaosError::BadRequestWithMessage(
+                "No outcomes to process".to_string(),
+            ));
+        }
+
+        let mut seen = HashSet::new();
+        for outcome in &outcomes {
+            if !seen.insert(outcome.application_id) {
+                return Err(ChaosError::BadRequestWithMessage(
+                    "An application can only appear in a batch once".to_string(),
+                ));
+            }
+
+            match outcome.outcome {
+                EmailType::Accept if outcome.expiry <= Utc::now() => {
+                    return Err(ChaosError::BadRequestWithMessage(
+                        "Offer expiry must be in the future".to_string(),
+                    ))
+                }
+                EmailType::Interview => {
+                    return Err(ChaosError::BadRequestWithMessage(
+                        "Outcome must be either Accept or Reject".to_string(),
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        let template_ids: Vec<i64> = outcomes
+            .iter()
+            .map(|outcome| outcome.email_template_id)
+            .collect::<HashSet<i64>>()
+            .into_iter()
+            .collect();
+        let templates_found = sqlx::query!(
+            r#"
+                SELECT COUNT(*) AS "count!"
+                FROM email_templates t
+                JOIN campaigns c ON c.organisation_id = t.organisation_id
+                WHERE c.id = $1 AND t.id = ANY($2)
+            "#,
+            campaign_id,
+            &template_ids
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .count;
+
+        if templates_found != template_ids.len() as i64 {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Email templates must belong to this campaign's organisation".to_string(),
+            ));
+        }
+
+        let campaign = sqlx::query!(
+            "
+                SELECT c.name AS campaign_name, o.name AS organisation_name
+                FROM campaigns c
+                JOIN organisations o ON o.id = c.organisation_id
+                WHERE c.id = $1
+            ",
+            campaign_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        let application_ids: Vec<i64> = outcomes
+            .iter()
+            .map(|outcome| outcome.application_id)
+            .collect();
+        let role_ids: Vec<i64> = outcomes.iter().map(|outcome| outcome.role_id).collect();
+
+        let applicants = sqlx::query!(
+            "
+                SELECT item.application_id, u.name AS user_name, u.email AS user_email,
+                    r.name AS role_name
+                FROM UNNEST($2::bigint[], $3::bigint[]) AS item(application_id, role_id)
+                JOIN applications a ON a.id = item.application_id
+                JOIN application_roles ar
+                    ON ar.application_id = a.id AND ar.campaign_role_id = item.role_id
+                JOIN campaign_roles r ON r.id = item.role_id
+                JOIN users u ON u.id = a.user_id
+                WHERE a.campaign_id = $1 AND r.campaign_id = $1 AND a.submitted = true
+            ",
+            campaign_id,
+            &application_ids,
+            &role_ids
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let mut applicants: HashMap<i64, _> = applicants
+            .into_iter()
+            .filter_map(|applicant| applicant.application_id.map(|id| (id, applicant)))
+            .collect();
+        if applicants.len() != outcomes.len() {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Every application must be submitted to this campaign and have applied for its role"
+                    .to_string(),
+            ));
+        }
+
+        let has_offer = sqlx::query!(
+            "
+                SELECT EXISTS(
+                    SELECT 1 FROM offers
+                    WHERE application_id = ANY($1)
+                    AND status NOT IN ('Declined', 'Expired', 'Withdrawn')
+                )
+            ",
+            &application_ids
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .exists
+        .expect("`exists` should always exist in this query result");
+
+        if has_offer {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Some applicants already have an open or accepted offer".to_string(),
+            ));
+        }
+
+        let mut recipients = Vec::with_capacity(outcomes.len());
+        for item in outcomes {
+            let applicant = applicants
+                .remove(&item.application_id)
+                .expect("every item was matched to an applicant above");
+            let email_template_id = item.email_template_id;
+
+            let email_parts = EmailTemplate::generate_email(
+                applicant.user_name.clone(),
+                applicant.role_name.clone(),
+                campaign.organisation_name.clone(),
+                campaign.campaign_name.clone(),
+                item.expiry,
+                email_template_id,
+                transaction,
+            )
+            .await?;
+
+            let mut offer_id = None;
+            if !dry_run {
+                if matches!(item.outcome, EmailType::Accept) {
+                    let id = Offer::create(
+                        campaign_id,
+                        item.application_id,
+                        email_template_id,
+                        item.role_id,
+                        item.expiry,
+                        transaction,
+                        snowflake_generator,
+                    )
+                    .await?;
+                    Offer::send_offer(id, transaction).await?;
+                    offer_id = Some(id);
+                } else {
+                    EmailQueue::add_to_queue(
+                        Some(applicant.user_name.clone()),
+                        applicant.user_email.clone(),
+                        email_parts.subject.clone(),
+                        email_parts.body.clone(),
+                        EmailContext {
+                            campaign_id: Some(campaign_id),
+                            application_id: Some(item.application_id),
+                            offer_id: None,
+                            hold_for_release: true,
+                        },
+                        transaction,
+                    )
+                    .await?;
+                }
+            }
+
+            recipients.push(BulkOfferRecipient {
+                application_id: item.application_id,
+                role_id: item.role_id,
+                role_name: applicant.role_name,
+                outcome: item.outcome,
+                recipient_name: applicant.user_name,
+                recipient_email_address: applicant.user_email,
+                email_template_id,
+                subject: email_parts.subject,
+                body: email_parts.body,
+                offer_id,
+            });
+        }
+
+        Ok(BulkOfferReport {
+            dry_run,
+            recipients,
+        })
+    }
+}
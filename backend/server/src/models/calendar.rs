@@ -0,0 +1,317 @@
+//! iCalendar support for Chaos.
+//!
+//! This module builds RFC 5545 calendar events, which are attached to emails
+//! as invitations, updates and cancellations. Clients match an update or
+//! cancellation to an earlier invitation by its UID, and apply them in order
+//! of their sequence number.
+//!
+//! It also manages the tokens for each user's read-only calendar feed, which
+//! calendar apps can subscribe to without the user's login cookie.
+
+use crate::models::error::ChaosError;
+use chrono::{DateTime, Utc};
+use nanoid::nanoid;
+use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, Transaction};
+use std::ops::DerefMut;
+
+/// Address that calendar events are organised by, matching the reply-to of sent emails.
+pub const CALENDAR_ORGANISER_EMAIL: &str = "chaos@devsoc.app";
+
+/// Length of generated calendar feed tokens.
+const FEED_TOKEN_LENGTH: usize = 32;
+
+/// Maximum length of a content line in octets, excluding the line break.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// iTIP method of a calendar event.
+///
+/// `Request` is used for both new invitations and updates, `Cancel` for cancellations.
+#[derive(Deserialize, Serialize, sqlx::Type, Clone, Copy, Debug, PartialEq)]
+#[sqlx(type_name = "calendar_method", rename_all = "PascalCase")]
+pub enum CalendarMethod {
+    Request,
+    Cancel,
+}
+
+impl CalendarMethod {
+    /// The method as written in the `METHOD` property and `text/calendar` content type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CalendarMethod::Request => "REQUEST",
+            CalendarMethod::Cancel => "CANCEL",
+        }
+    }
+}
+
+/// A user's calendar feed token.
+#[derive(Serialize)]
+pub struct CalendarFeedToken {
+    /// Secret token that grants read-only access to the user's calendar feed
+    pub token: String,
+}
+
+/// A single calendar event with one attendee.
+pub struct CalendarEvent {
+    /// Globally unique identifier, kept the same across updates and cancellations
+    pub uid: String,
+    /// Revision of the event, must increase with every update
+    pub sequence: i32,
+    /// When the event starts
+    pub start_time: DateTime<Utc>,
+    /// When the event ends
+    pub end_time: DateTime<Utc>,
+    /// Title of the event
+    pub summary: String,
+    /// Optional longer description of the event
+    pub description: Option<String>,
+    /// Optional location of the event
+    pub location: Option<String>,
+    /// Name of the attendee
+    pub attendee_name: Option<String>,
+    /// Email address of the attendee
+    pub attendee_email: String,
+}
+
+impl CalendarEvent {
+    /// Renders the event as an iCalendar object.
+    ///
+    /// # Arguments
+    /// * `method` - The iTIP method to send the event with
+    ///
+    /// # Returns
+    /// The `.ics` contents, with CRLF line endings and long lines folded.
+    pub fn to_ics(&self, method: CalendarMethod) -> String {
+        let status = match method {
+            CalendarMethod::Request => "CONFIRMED",
+            CalendarMethod::Cancel => "CANCELLED",
+        };
+
+        let mut lines = vec![format!("METHOD:{}", method.as_str())];
+        lines.extend(self.vevent_lines(status));
+
+        render_calendar(lines)
+    }
+
+    /// Content lines of the `VEVENT` component for this event.
+    fn vevent_lines(&self, status: &str) -> Vec<String> {
+        let mut lines = vec![
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}", self.uid),
+            format!("SEQUENCE:{}", self.sequence),
+            format!("DTSTAMP:{}", format_time(Utc::now())),
+            format!("DTSTART:{}", format_time(self.start_time)),
+            format!("DTEND:{}", format_time(self.end_time)),
+            format!("SUMMARY:{}", escape_text(&self.summary)),
+        ];
+
+        if let Some(description) = &self.description {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        if let Some(location) = &self.location {
+            lines.push(format!("LOCATION:{}", escape_text(location)));
+        }
+
+        lines.push(format!(
+            "ORGANIZER;CN=\"Chaos Subcommittee Recruitment\":mailto:{}",
+            CALENDAR_ORGANISER_EMAIL
+        ));
+
+        let attendee_name = match &self.attendee_name {
+            Some(name) => format!(";CN=\"{}\"", name.replace('"', "'")),
+            None => String::new(),
+        };
+        lines.push(format!(
+            "ATTENDEE{};ROLE=REQ-PARTICIPANT;PARTSTAT=NEEDS-ACTION:mailto:{}",
+            attendee_name, self.attendee_email
+        ));
+
+        lines.push(format!("STATUS:{}", status));
+        lines.push("END:VEVENT".to_string());
+
+        lines
+    }
+}
+
+/// Renders a read-only calendar of events, suitable for subscribing to as a feed.
+///
+/// # Arguments
+/// * `name` - The display name of the calendar
+/// * `events` - The events in the calendar
+///
+/// # Returns
+/// The `.ics` contents, with CRLF line endings and long lines folded.
+pub fn render_feed(name: &str, events: &[CalendarEvent]) -> String {
+    let mut lines = vec![
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(name)),
+    ];
+    for event in events {
+        lines.extend(event.vevent_lines("CONFIRMED"));
+    }
+
+    render_calendar(lines)
+}
+
+/// Wraps content lines in a `VCALENDAR` component and serialises them.
+fn render_calendar(body: Vec<String>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//DevSoc//Chaos//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    lines.extend(body);
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut ics = String::new();
+    for line in lines {
+        ics.push_str(&fold_line(&line));
+        ics.push_str("\r\n");
+    }
+
+    ics
+}
+
+/// Formats a time as an iCalendar UTC date-time.
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapes a value for use in an iCalendar TEXT property.
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Folds a content line so that no line is longer than 75 octets, without splitting characters.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_octets = 0;
+
+    for c in line.chars() {
+        if line_octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // The leading space of a continuation line counts towards its length.
+            line_octets = 1;
+        }
+        folded.push(c);
+        line_octets += c.len_utf8();
+    }
+
+    folded
+}
+
+pub struct CalendarFeed;
+
+impl CalendarFeed {
+    /// Creates a new calendar feed token for a user, revoking any previous one.
+    ///
+    /// # Arguments
+    /// * `user_id` - The ID of the user
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(CalendarFeedToken)` - The new token
+    /// * `Err(ChaosError)` - An error if creation fails
+    pub async fn create_token(
+        user_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<CalendarFeedToken, ChaosError> {
+        let token = nanoid!(FEED_TOKEN_LENGTH);
+
+        sqlx::query!(
+            "
+                INSERT INTO calendar_feed_tokens (user_id, token)
+                VALUES ($1, $2)
+                ON CONFLICT (user_id)
+                    DO UPDATE SET token = EXCLUDED.token, created_at = CURRENT_TIMESTAMP
+            ",
+            user_id,
+            token
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(CalendarFeedToken { token })
+    }
+
+    /// Retrieves a user's current calendar feed token.
+    ///
+    /// # Arguments
+    /// * `user_id` - The ID of the user
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(CalendarFeedToken)` - The user's token
+    /// * `Err(ChaosError)` - An error if the user has no token
+    pub async fn get_token(
+        user_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<CalendarFeedToken, ChaosError> {
+        let token = sqlx::query_as!(
+            CalendarFeedToken,
+            "SELECT token FROM calendar_feed_tokens WHERE user_id = $1",
+            user_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        Ok(token)
+    }
+
+    /// Revokes a user's calendar feed token, so the feed can no longer be read.
+    ///
+    /// # Arguments
+    /// * `user_id` - The ID of the user
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the token was revoked
+    /// * `Err(ChaosError)` - An error if the user has no token
+    pub async fn revoke_token(
+        user_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        sqlx::query!(
+            "DELETE FROM calendar_feed_tokens WHERE user_id = $1 RETURNING user_id",
+            user_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Resolves a calendar feed token to the user it belongs to.
+    ///
+    /// # Arguments
+    /// * `token` - The feed token
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(i64)` - The ID of the user the token belongs to
+    /// * `Err(ChaosError)` - An error if the token does not exist
+    pub async fn get_user_id(
+        token: &str,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<i64, ChaosError> {
+        let user_id = sqlx::query!(
+            "SELECT user_id FROM calendar_feed_tokens WHERE token = $1",
+            token
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .user_id;
+
+        Ok(user_id)
+    }
+}
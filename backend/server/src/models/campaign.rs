@@ -319,7 +319,12 @@ impl Campaign {
         let campaigns = sqlx::query_as!(
             Campaign,
             "
-                SELECT c.*, o.name as organisation_name, o.slug as organisation_slug
+                SELECT c.id, c.slug, c.name, c.organisation_id, c.cover_image, c.description,
+                    c.starts_at, c.ends_at, c.created_at, c.updated_at,
+                    c.interview_period_starts_at, c.interview_period_ends_at,
+                    c.interview_format, c.outcomes_released_at, c.application_requirements,
+                    c.published, c.max_roles_per_application,
+                    o.name as organisation_name, o.slug as organisation_slug
                 FROM campaigns c
                 JOIN organisations o on c.organisation_id = o.id
             "
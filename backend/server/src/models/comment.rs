@@ -2,9 +2,11 @@
 //!
 //! This module provides database access for CRUD operations on application comments.
 
+use crate::models::application_event::{ApplicationEvent, ApplicationEventType};
 use crate::models::error::ChaosError;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use snowflake::SnowflakeIdGenerator;
 use sqlx::{FromRow, Postgres, Transaction};
 use std::ops::DerefMut;
@@ -92,6 +94,16 @@ impl Comment {
         .execute(transaction.deref_mut())
         .await?;
 
+        ApplicationEvent::record(
+            application_id,
+            ApplicationEventType::CommentCreated,
+            Some(author_id),
+            None,
+            json!({ "comment_id": id.to_string() }),
+            transaction,
+        )
+        .await?;
+
         Ok(id)
     }
 
@@ -1,18 +1,32 @@
 //! Email functionality for Chaos.
 //!
-//! This module provides functionality for sending emails using SMTP.
-//! It handles email credentials management and message sending through
-//! the Lettre email library.
+//! This module provides functionality for building emails and queueing them
+//! for delivery. It handles SMTP credentials management and message building
+//! through the Lettre email library; delivery itself goes through the
+//! transports in `email_transport`.
 
+use crate::models::calendar::CalendarMethod;
 use crate::models::error::ChaosError;
+use chrono::{DateTime, Duration, Utc};
 use lettre::message::header::ContentType;
+use lettre::message::{Attachment, MultiPart, SinglePart};
 use lettre::transport::smtp::authentication::Credentials;
-use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use lettre::transport::smtp::PoolConfig;
+use lettre::{AsyncSmtpTransport, Message, Tokio1Executor};
 use serde::{Deserialize, Serialize};
 use sqlx::{Postgres, Transaction};
 use std::env;
 use std::ops::DerefMut;
 
+/// Number of failed sends after which a queued email is moved to the dead-letter state.
+pub const MAX_EMAIL_ATTEMPTS: i32 = 5;
+
+/// Delay before the first retry of a failed email, doubled on each further failure.
+const EMAIL_RETRY_BASE_SECONDS: i64 = 30;
+
+/// Longest delay between retries of a failed email.
+const EMAIL_RETRY_MAX_SECONDS: i64 = 60 * 60;
+
 /// Main email service for Chaos.
 ///
 /// This struct provides methods for setting up email credentials and
@@ -58,6 +72,18 @@ pub enum EmailType {
     Reject,
 }
 
+/// Calendar event attached to an email.
+///
+/// Sent as both a `text/calendar` alternative, which most clients render as an
+/// invitation, and an `.ics` attachment for clients that do not.
+#[derive(Clone, Debug)]
+pub struct CalendarInvite {
+    /// The iTIP method the event is sent with
+    pub method: CalendarMethod,
+    /// The `.ics` contents of the event
+    pub ics: String,
+}
+
 impl ChaosEmail {
     /// Sets up email credentials from environment variables.
     ///
@@ -65,83 +91,87 @@ impl ChaosEmail {
     /// * `SMTP_USERNAME` - The SMTP username
     /// * `SMTP_PASSWORD` - The SMTP password
     /// * `SMTP_HOST` - The SMTP server host
+    /// * `SMTP_PORT` - The SMTP server port
     ///
     /// # Returns
-    /// Returns an `EmailCredentials` instance with the configured credentials.
-    ///
-    /// # Panics
-    /// Panics if any of the required environment variables are not set.
-    pub fn setup_credentials() -> EmailCredentials {
-        let smtp_username = env::var("SMTP_USERNAME")
-            .expect("Error getting SMTP USERNAME")
-            .to_string();
-
-        let smtp_password = env::var("SMTP_PASSWORD")
-            .expect("Error getting SMTP PASSWORD")
-            .to_string();
-
-        let email_from = env::var("SMTP_USERNAME")
-            .expect("Error getting EMAIL_FROM")
-            .to_string();
-
-        let email_host = env::var("SMTP_HOST")
-            .expect("Error getting SMTP HOST")
-            .to_string();
-
-        let email_host_port = env::var("SMTP_PORT")
-            .expect("Error getting SMTP PORT")
-            .to_string()
-            .parse::<u16>()
-            .unwrap();
-
-        EmailCredentials {
+    /// Returns a `Result` containing either:
+    /// * `Ok(EmailCredentials)` - The configured credentials
+    /// * `Err(ChaosError)` - An error if a variable is missing or the port is invalid
+    pub fn setup_credentials() -> Result<EmailCredentials, ChaosError> {
+        let smtp_var = |name: &str| {
+            env::var(name)
+                .map_err(|_| ChaosError::EmailTransportError(format!("{name} must be set")))
+        };
+
+        let smtp_username = smtp_var("SMTP_USERNAME")?;
+        let smtp_password = smtp_var("SMTP_PASSWORD")?;
+        let email_from = smtp_var("SMTP_USERNAME")?;
+        let email_host = smtp_var("SMTP_HOST")?;
+        let email_host_port = smtp_var("SMTP_PORT")?.parse::<u16>().map_err(|_| {
+            ChaosError::EmailTransportError("SMTP_PORT must be a valid port".to_string())
+        })?;
+
+        Ok(EmailCredentials {
             credentials: Credentials::new(smtp_username, smtp_password),
             email_from,
             email_host,
             email_host_port,
-        }
+        })
     }
 
-    /// Creates a new SMTP connection with the provided credentials.
+    /// Creates a new pooled SMTP transport with the provided credentials.
+    ///
+    /// The transport keeps up to `pool_size` connections open and reuses them
+    /// across messages. Cloning it shares the same pool.
     ///
     /// # Arguments
     /// * `credentials` - The email credentials to use for the connection
+    /// * `pool_size` - The maximum number of open SMTP connections
     ///
     /// # Returns
     /// Returns a `Result` containing either:
     /// * `Ok(AsyncSmtpTransport)` - A configured SMTP transport
     /// * `Err(ChaosError)` - An error if connection setup fails
-    fn new_connection(
+    pub fn new_connection(
         credentials: EmailCredentials,
+        pool_size: u32,
     ) -> Result<AsyncSmtpTransport<Tokio1Executor>, ChaosError> {
         Ok(
             AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&credentials.email_host)?
                 .port(credentials.email_host_port)
                 .credentials(credentials.credentials)
+                .pool_config(PoolConfig::new().max_size(pool_size))
                 .build(),
         )
     }
 
-    /// Sends an email message.
+    /// Builds an email message, optionally carrying a calendar event.
+    ///
+    /// Emails without a calendar event are built as a single text or HTML part.
+    /// Emails with one are built as `multipart/mixed`, containing a
+    /// `multipart/alternative` of the body and the `text/calendar` event,
+    /// followed by the event again as an `invite.ics` attachment.
     ///
     /// # Arguments
     /// * `recipient_name` - The name of the email recipient
     /// * `recipient_email_address` - The email address of the recipient
     /// * `subject` - The email subject
     /// * `body` - The email body content
-    /// * `credentials` - The email credentials to use for sending
+    /// * `calendar` - Optional calendar event to attach
+    /// * `email_from` - The address the email is sent from
     ///
     /// # Returns
     /// Returns a `Result` containing either:
-    /// * `Ok(())` - If the email was sent successfully
-    /// * `Err(ChaosError)` - An error if sending fails
-    pub async fn send_message(
+    /// * `Ok(Message)` - The built message
+    /// * `Err(ChaosError)` - An error if an address is invalid or building fails
+    pub fn build_message(
         recipient_name: Option<String>,
         recipient_email_address: String,
         subject: String,
         body: String,
-        credentials: EmailCredentials,
-    ) -> Result<(), ChaosError> {
+        calendar: Option<CalendarInvite>,
+        email_from: &str,
+    ) -> Result<Message, ChaosError> {
         let to = match recipient_name {
             Some(name) => format!("{name} <{recipient_email_address}>"),
             None => recipient_email_address,
@@ -155,46 +185,232 @@ impl ChaosEmail {
             ContentType::TEXT_PLAIN
         };
 
-        let message = Message::builder()
-            .from(
-                format!(
-                    "Chaos Subcommittee Recruitment <{}>",
-                    credentials.email_from
-                )
-                .parse()?,
-            )
+        let builder = Message::builder()
+            .from(format!("Chaos Subcommittee Recruitment <{}>", email_from).parse()?)
             .reply_to("chaos@devsoc.app".parse()?)
             .to(to.parse()?)
-            .subject(subject)
-            .header(content_type)
-            .body(body)?;
+            .subject(subject);
 
-        let mailer = Self::new_connection(credentials)?;
-        mailer.send(message).await?;
+        let message = match calendar {
+            None => builder.header(content_type).body(body)?,
+            Some(calendar) => {
+                let calendar_type = ContentType::parse(&format!(
+                    "text/calendar; charset=utf-8; method={}",
+                    calendar.method.as_str()
+                ))
+                .expect("calendar content type should always be valid");
+                let attachment_type = ContentType::parse("application/ics")
+                    .expect("ics content type should always be valid");
 
-        Ok(())
+                builder.multipart(
+                    MultiPart::mixed()
+                        .multipart(
+                            MultiPart::alternative()
+                                .singlepart(SinglePart::builder().header(content_type).body(body))
+                                .singlepart(
+                                    SinglePart::builder()
+                                        .header(calendar_type)
+                                        .body(calendar.ics.clone()),
+                                ),
+                        )
+                        .singlepart(
+                            Attachment::new("invite.ics".to_string())
+                                .body(calendar.ics, attachment_type),
+                        ),
+                )?
+            }
+        };
+
+        Ok(message)
     }
 }
 
+/// Delivery state of a queued email.
+#[derive(Deserialize, Serialize, sqlx::Type, Clone, Copy, Debug, PartialEq)]
+#[sqlx(type_name = "email_queue_status", rename_all = "PascalCase")]
+pub enum EmailQueueStatus {
+    /// Waiting to be sent, or to be retried
+    Pending,
+    /// Failed `MAX_EMAIL_ATTEMPTS` times and will not be retried automatically
+    Failed,
+}
+
+/// Delivery state of an email in the sent email log.
+#[derive(Deserialize, Serialize, sqlx::Type, Clone, Copy, Debug, PartialEq)]
+#[sqlx(type_name = "sent_email_status", rename_all = "PascalCase")]
+pub enum SentEmailStatus {
+    /// In the queue, waiting to be sent or retried
+    Queued,
+    /// Handed to the email transport
+    Sent,
+    /// Exhausted its retries without being sent
+    Failed,
+    /// Removed from the queue before it was sent
+    Cancelled,
+}
+
+/// What an email was sent about, recorded in the sent email log.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EmailContext {
+    pub campaign_id: Option<i64>,
+    pub application_id: Option<i64>,
+    pub offer_id: Option<i64>,
+    /// Hold the email until the campaign's outcomes are released
+    pub hold_for_release: bool,
+}
+
+/// An entry in the sent email log.
+#[derive(Serialize)]
+pub struct SentEmail {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub id: i64,
+    #[serde(serialize_with = "crate::models::serde_string::serialize_option")]
+    pub campaign_id: Option<i64>,
+    #[serde(serialize_with = "crate::models::serde_string::serialize_option")]
+    pub application_id: Option<i64>,
+    #[serde(serialize_with = "crate::models::serde_string::serialize_option")]
+    pub offer_id: Option<i64>,
+    pub recipient_name: Option<String>,
+    pub recipient_email_address: String,
+    pub subject: String,
+    pub body: String,
+    pub status: SentEmailStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub queued_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub failed_at: Option<DateTime<Utc>>,
+}
+
+/// An email claimed from the queue by a worker.
+pub struct QueuedEmail {
+    pub id: i32,
+    pub recipient_name: Option<String>,
+    pub recipient_email_address: String,
+    pub subject: String,
+    pub body: String,
+    pub attempts: i32,
+    pub calendar: Option<CalendarInvite>,
+}
+
+/// An email that has exhausted its retries.
+#[derive(Serialize)]
+pub struct FailedEmail {
+    pub id: i32,
+    pub recipient_name: Option<String>,
+    pub recipient_email_address: String,
+    pub subject: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// When the last attempt to send the email was made, if it is known
+    pub last_attempt_at: Option<DateTime<Utc>>,
+}
+
 pub struct EmailQueue;
 
 impl EmailQueue {
+    /// Adds an email to the queue and records it in the sent email log.
+    ///
+    /// # Arguments
+    /// * `recipient_name` - The name of the email recipient
+    /// * `recipient_email_address` - The email address of the recipient
+    /// * `subject` - The email subject
+    /// * `body` - The email body content
+    /// * `context` - The campaign, application and offer the email is about
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(i64)` - The ID of the email in the sent email log
+    /// * `Err(ChaosError)` - An error if queuing fails
     pub async fn add_to_queue(
         recipient_name: Option<String>,
         recipient_email_address: String,
         subject: String,
         body: String,
+        context: EmailContext,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<i64, ChaosError> {
+        let sent_email_id = sqlx::query!(
+            r#"
+                WITH log AS (
+                    INSERT INTO sent_emails
+                        (campaign_id, application_id, offer_id, recipient_name,
+                        recipient_email_address, subject, body)
+                    VALUES ($5, $6, $7, $1, $2, $3, $4)
+                    RETURNING id
+                )
+                INSERT INTO email_queue
+                    (recepient_name, recepient_email_address, subject, body, sent_email_id,
+                    release_campaign_id)
+                SELECT $1, $2, $3, $4, log.id, CASE WHEN $8 THEN $5 END FROM log
+                RETURNING sent_email_id AS "sent_email_id!"
+            "#,
+            recipient_name,
+            recipient_email_address,
+            subject,
+            body,
+            context.campaign_id,
+            context.application_id,
+            context.offer_id,
+            context.hold_for_release,
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .sent_email_id;
+
+        Ok(sent_email_id)
+    }
+
+    /// Adds an email carrying a calendar event to the queue.
+    ///
+    /// # Arguments
+    /// * `recipient_name` - The name of the email recipient
+    /// * `recipient_email_address` - The email address of the recipient
+    /// * `subject` - The email subject
+    /// * `body` - The email body content
+    /// * `calendar` - The calendar event to attach
+    /// * `context` - The campaign, application and offer the email is about
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the email was queued successfully
+    /// * `Err(ChaosError)` - An error if queuing fails
+    pub async fn add_calendar_to_queue(
+        recipient_name: Option<String>,
+        recipient_email_address: String,
+        subject: String,
+        body: String,
+        calendar: CalendarInvite,
+        context: EmailContext,
         transaction: &mut Transaction<'_, Postgres>,
     ) -> Result<(), ChaosError> {
         sqlx::query!(
             r#"
-                INSERT INTO email_queue (recepient_name, recepient_email_address, subject, body)
-                VALUES ($1, $2, $3, $4)
+                WITH log AS (
+                    INSERT INTO sent_emails
+                        (campaign_id, application_id, offer_id, recipient_name,
+                        recipient_email_address, subject, body)
+                    VALUES ($7, $8, $9, $1, $2, $3, $4)
+                    RETURNING id
+                )
+                INSERT INTO email_queue
+                    (recepient_name, recepient_email_address, subject, body, calendar_method,
+                    calendar_ics, sent_email_id, release_campaign_id)
+                SELECT $1, $2, $3, $4, $5, $6, log.id, CASE WHEN $10 THEN $7 END FROM log
             "#,
             recipient_name,
             recipient_email_address,
             subject,
             body,
+            calendar.method as CalendarMethod,
+            calendar.ics,
+            context.campaign_id,
+            context.application_id,
+            context.offer_id,
+            context.hold_for_release,
         )
         .execute(transaction.deref_mut())
         .await?;
@@ -202,39 +418,280 @@ impl EmailQueue {
         Ok(())
     }
 
-    pub async fn send_next(
-        credentials: EmailCredentials,
+    /// Claims a batch of emails that are due to be sent.
+    ///
+    /// Rows are selected with `SKIP LOCKED` and leased for `lease_seconds`, so
+    /// workers on other replicas claim different emails. The transaction should be
+    /// committed straight away; if the worker dies mid-batch, the lease runs out and
+    /// its emails are claimed again.
+    ///
+    /// Emails held for an outcome release are not claimed while their campaign's
+    /// `outcomes_released_at` is still in the future. If no release time is set,
+    /// they are sent straight away.
+    ///
+    /// # Arguments
+    /// * `batch_size` - The maximum number of emails to claim
+    /// * `lease_seconds` - How long the claimed emails are reserved for
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<QueuedEmail>)` - The claimed emails, empty if none are due
+    /// * `Err(ChaosError)` - An error if the queue could not be read
+    pub async fn claim_batch(
+        batch_size: i64,
+        lease_seconds: f64,
         transaction: &mut Transaction<'_, Postgres>,
-    ) -> Result<(), ChaosError> {
-        let email = sqlx::query!(
+    ) -> Result<Vec<QueuedEmail>, ChaosError> {
+        let emails = sqlx::query!(
             r#"
-                WITH del AS (
-                  SELECT id
-                  FROM email_queue
-                  ORDER BY created_at ASC
-                  LIMIT 1
-                  FOR UPDATE
+                UPDATE email_queue
+                SET locked_until = NOW() + make_interval(secs => $2)
+                WHERE id IN (
+                    SELECT id
+                    FROM email_queue
+                    WHERE status = 'Pending' AND next_attempt_at <= NOW()
+                    AND (locked_until IS NULL OR locked_until < NOW())
+                    AND NOT EXISTS (
+                        SELECT 1 FROM campaigns c
+                        WHERE c.id = email_queue.release_campaign_id
+                        AND c.outcomes_released_at > NOW()
+                    )
+                    ORDER BY next_attempt_at ASC, created_at ASC
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, recepient_name, recepient_email_address, subject, body, attempts,
+                    calendar_method AS "calendar_method: CalendarMethod", calendar_ics
+            "#,
+            batch_size,
+            lease_seconds
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let emails = emails
+            .into_iter()
+            .map(|email| QueuedEmail {
+                id: email.id,
+                recipient_name: email.recepient_name,
+                recipient_email_address: email.recepient_email_address,
+                subject: email.subject,
+                body: email.body,
+                attempts: email.attempts,
+                calendar: match (email.calendar_method, email.calendar_ics) {
+                    (Some(method), Some(ics)) => Some(CalendarInvite { method, ics }),
+                    _ => None,
+                },
+            })
+            .collect();
+
+        Ok(emails)
+    }
+
+    /// Removes an email from the queue once it has been sent, and marks it as sent in
+    /// the sent email log.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the sent email
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the email was removed
+    /// * `Err(ChaosError)` - An error if the update fails
+    pub async fn mark_sent(
+        id: i32,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        sqlx::query!(
+            "
+                WITH sent AS (
+                    DELETE FROM email_queue WHERE id = $1 RETURNING sent_email_id, attempts
                 )
-                DELETE FROM email_queue
-                USING del
-                WHERE email_queue.id = del.id
-                RETURNING email_queue.id, recepient_name, recepient_email_address, subject, body
+                UPDATE sent_emails
+                SET status = 'Sent', sent_at = NOW(), attempts = sent.attempts + 1
+                FROM sent
+                WHERE sent_emails.id = sent.sent_email_id
+            ",
+            id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records a failed send, scheduling a retry or moving the email to the dead-letter state.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the email that failed
+    /// * `attempts` - The number of attempts made so far, including this one
+    /// * `error` - A description of the failure
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the failure was recorded
+    /// * `Err(ChaosError)` - An error if the update fails
+    pub async fn record_failure(
+        id: i32,
+        attempts: i32,
+        error: String,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let (status, log_status) = if attempts >= MAX_EMAIL_ATTEMPTS {
+            (EmailQueueStatus::Failed, SentEmailStatus::Failed)
+        } else {
+            (EmailQueueStatus::Pending, SentEmailStatus::Queued)
+        };
+
+        // 30s, 1m, 2m, 4m, ... capped at an hour.
+        let backoff_seconds =
+            (EMAIL_RETRY_BASE_SECONDS << (attempts - 1).clamp(0, 16)).min(EMAIL_RETRY_MAX_SECONDS);
+        let next_attempt_at = Utc::now() + Duration::seconds(backoff_seconds);
+
+        let sent_email_id = sqlx::query!(
+            "
+                UPDATE email_queue
+                SET attempts = $2, last_error = $3, next_attempt_at = $4, status = $5,
+                    last_attempt_at = NOW(), locked_until = NULL
+                WHERE id = $1
+                RETURNING sent_email_id
+            ",
+            id,
+            attempts,
+            error,
+            next_attempt_at,
+            status as EmailQueueStatus
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .sent_email_id;
+
+        if let Some(sent_email_id) = sent_email_id {
+            sqlx::query!(
+                "
+                    UPDATE sent_emails
+                    SET attempts = $2, last_error = $3, status = $4,
+                        failed_at = CASE WHEN $4 = 'Failed'::sent_email_status THEN NOW() END
+                    WHERE id = $1
+                ",
+                sent_email_id,
+                attempts,
+                error,
+                log_status as SentEmailStatus
+            )
+            .execute(transaction.deref_mut())
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Retrieves every email that has exhausted its retries.
+    ///
+    /// # Arguments
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<FailedEmail>)` - The failed emails, most recent first
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get_failed(
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<FailedEmail>, ChaosError> {
+        let emails = sqlx::query_as!(
+            FailedEmail,
+            r#"
+                SELECT id, recepient_name AS recipient_name,
+                    recepient_email_address AS recipient_email_address,
+                    subject, attempts, last_error, created_at, last_attempt_at
+                FROM email_queue
+                WHERE status = 'Failed'
+                ORDER BY last_attempt_at DESC NULLS LAST, created_at DESC
             "#
         )
-        .fetch_optional(transaction.deref_mut())
+        .fetch_all(transaction.deref_mut())
         .await?;
 
-        if let Some(email) = email {
-            ChaosEmail::send_message(
-                email.recepient_name,
-                email.recepient_email_address,
-                email.subject,
-                email.body,
-                credentials,
+        Ok(emails)
+    }
+
+    /// Moves a failed email back into the queue to be sent again.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the failed email
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the email was re-queued
+    /// * `Err(ChaosError)` - An error if the email does not exist or has not failed
+    pub async fn requeue(
+        id: i32,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let sent_email_id = sqlx::query!(
+            "
+                UPDATE email_queue
+                SET status = 'Pending', attempts = 0, next_attempt_at = NOW()
+                WHERE id = $1 AND status = 'Failed'
+                RETURNING sent_email_id
+            ",
+            id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .sent_email_id;
+
+        if let Some(sent_email_id) = sent_email_id {
+            sqlx::query!(
+                "UPDATE sent_emails SET status = 'Queued', failed_at = NULL WHERE id = $1",
+                sent_email_id
             )
+            .execute(transaction.deref_mut())
             .await?;
         }
 
         Ok(())
     }
 }
+
+/// Permanent record of queued emails and what happened to them.
+pub struct SentEmailLog;
+
+impl SentEmailLog {
+    /// Retrieves every email a campaign has sent, or tried to send, about an application.
+    ///
+    /// # Arguments
+    /// * `application_id` - The ID of the application
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<SentEmail>)` - The logged emails, most recent first
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get_all_by_application(
+        application_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<SentEmail>, ChaosError> {
+        let emails = sqlx::query_as!(
+            SentEmail,
+            r#"
+                SELECT id, campaign_id, application_id, offer_id, recipient_name,
+                    recipient_email_address, subject, body,
+                    status AS "status: SentEmailStatus", attempts, last_error,
+                    queued_at, sent_at, failed_at
+                FROM sent_emails
+                WHERE application_id = $1
+                ORDER BY queued_at DESC
+            "#,
+            application_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        Ok(emails)
+    }
+}
@@ -0,0 +1,316 @@
+//! Pluggable delivery of email messages.
+//!
+//! Messages are built by [`ChaosEmail::build_message`] and handed to an
+//! [`EmailTransport`], which is chosen through the `EMAIL_TRANSPORT` environment
+//! variable. This lets development and test runs go through the real queue and
+//! rendering path without an SMTP server:
+//!
+//! * `smtp` - Delivers through the configured SMTP relay
+//! * `file` - Writes each message as an `.eml` file into `EMAIL_FILE_DIRECTORY`
+//! * `memory` - Keeps each message in memory for inspection
+
+use crate::models::email::{CalendarInvite, ChaosEmail, EmailCredentials};
+use crate::models::error::ChaosError;
+use axum::async_trait;
+use chrono::Utc;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use nanoid::nanoid;
+use std::env;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Directory `.eml` files are written to when `EMAIL_FILE_DIRECTORY` is not set.
+const DEFAULT_EMAIL_FILE_DIRECTORY: &str = "emails";
+
+/// Address emails are sent from when the transport is not SMTP and `EMAIL_FROM` is not set.
+const DEFAULT_EMAIL_FROM: &str = "chaos@devsoc.app";
+
+/// A destination that built email messages are delivered to.
+#[async_trait]
+pub trait EmailTransport: Send + Sync {
+    /// Delivers a single message.
+    ///
+    /// # Arguments
+    /// * `message` - The message to deliver
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the message was delivered
+    /// * `Err(ChaosError)` - An error if delivery fails
+    async fn send(&self, message: Message) -> Result<(), ChaosError>;
+}
+
+/// Delivers messages through an SMTP relay over a pool of reused connections.
+pub struct SmtpEmailTransport {
+    mailer: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpEmailTransport {
+    /// Creates an SMTP transport from the given credentials.
+    ///
+    /// # Arguments
+    /// * `credentials` - The SMTP credentials and relay to connect to
+    /// * `pool_size` - The maximum number of open SMTP connections
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(SmtpEmailTransport)` - The configured transport
+    /// * `Err(ChaosError)` - An error if connection setup fails
+    pub fn new(
+        credentials: EmailCredentials,
+        pool_size: u32,
+    ) -> Result<SmtpEmailTransport, ChaosError> {
+        let mailer = ChaosEmail::new_connection(credentials, pool_size)?;
+
+        Ok(SmtpEmailTransport { mailer })
+    }
+}
+
+#[async_trait]
+impl EmailTransport for SmtpEmailTransport {
+    async fn send(&self, message: Message) -> Result<(), ChaosError> {
+        self.mailer.send(message).await?;
+        Ok(())
+    }
+}
+
+/// Writes each message to its own `.eml` file, which any mail client can open.
+pub struct FileEmailTransport {
+    directory: PathBuf,
+}
+
+impl FileEmailTransport {
+    /// Creates a file transport, creating the directory if it does not exist.
+    ///
+    /// # Arguments
+    /// * `directory` - The directory to write messages into
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(FileEmailTransport)` - The configured transport
+    /// * `Err(ChaosError)` - An error if the directory could not be created
+    pub fn new(directory: PathBuf) -> Result<FileEmailTransport, ChaosError> {
+        std::fs::create_dir_all(&directory)
+            .map_err(|e| ChaosError::EmailTransportError(format!("{e}")))?;
+
+        Ok(FileEmailTransport { directory })
+    }
+}
+
+#[async_trait]
+impl EmailTransport for FileEmailTransport {
+    async fn send(&self, message: Message) -> Result<(), ChaosError> {
+        // Timestamped names keep the files in the order they were sent.
+        let file_name = format!(
+            "{}-{}.eml",
+            Utc::now().format("%Y%m%dT%H%M%S%.6f"),
+            nanoid!(8)
+        );
+        let path = self.directory.join(file_name);
+        let contents = message.formatted();
+
+        tokio::task::spawn_blocking(move || std::fs::write(path, contents))
+            .await
+            .map_err(|_| ChaosError::InternalServerError)?
+            .map_err(|e| ChaosError::EmailTransportError(format!("{e}")))?;
+
+        Ok(())
+    }
+}
+
+/// Keeps every message in memory, so tests can inspect what would have been sent.
+///
+/// Clones share the same list of messages.
+#[derive(Clone, Default)]
+pub struct MemoryEmailTransport {
+    messages: Arc<Mutex<Vec<Message>>>,
+}
+
+impl MemoryEmailTransport {
+    /// Creates an empty in-memory transport.
+    pub fn new() -> MemoryEmailTransport {
+        MemoryEmailTransport::default()
+    }
+
+    /// Returns every message delivered so far, oldest first.
+    pub fn messages(&self) -> Vec<Message> {
+        self.messages
+            .lock()
+            .expect("email capture lock should not be poisoned")
+            .clone()
+    }
+}
+
+#[async_trait]
+impl EmailTransport for MemoryEmailTransport {
+    async fn send(&self, message: Message) -> Result<(), ChaosError> {
+        self.messages
+            .lock()
+            .expect("email capture lock should not be poisoned")
+            .push(message);
+        Ok(())
+    }
+}
+
+/// Builds email messages and delivers them through the configured transport.
+///
+/// Cheap to clone; clones share the same transport.
+#[derive(Clone)]
+pub struct Mailer {
+    transport: Arc<dyn EmailTransport>,
+    email_from: String,
+    /// The in-memory transport, when it is the one in use
+    memory: Option<MemoryEmailTransport>,
+}
+
+impl Mailer {
+    /// Creates a mailer around an existing transport.
+    ///
+    /// # Arguments
+    /// * `transport` - The transport to deliver messages through
+    /// * `email_from` - The address emails are sent from
+    pub fn new(transport: Arc<dyn EmailTransport>, email_from: String) -> Mailer {
+        Mailer {
+            transport,
+            email_from,
+            memory: None,
+        }
+    }
+
+    /// Creates a mailer that keeps every message in memory.
+    ///
+    /// # Arguments
+    /// * `email_from` - The address emails are sent from
+    pub fn in_memory(email_from: String) -> Mailer {
+        let memory = MemoryEmailTransport::new();
+        Mailer {
+            transport: Arc::new(memory.clone()),
+            email_from,
+            memory: Some(memory),
+        }
+    }
+
+    /// Returns every message sent so far, if this mailer uses the `memory` transport.
+    ///
+    /// # Returns
+    /// * `Some(Vec<Message>)` - The captured messages, oldest first
+    /// * `None` - If messages are delivered through another transport
+    pub fn captured_messages(&self) -> Option<Vec<Message>> {
+        self.memory.as_ref().map(|memory| memory.messages())
+    }
+
+    /// Creates a mailer with the transport chosen by the environment.
+    ///
+    /// # Environment Variables
+    /// * `EMAIL_TRANSPORT` - One of `smtp`, `file` or `memory`. Defaults to `file`
+    ///   in development and `smtp` otherwise
+    /// * `EMAIL_FILE_DIRECTORY` - Where the `file` transport writes messages
+    /// * `EMAIL_FROM` - The sender address for the `file` and `memory` transports
+    ///
+    /// The `smtp` transport also reads the `SMTP_*` variables described in
+    /// [`ChaosEmail::setup_credentials`].
+    ///
+    /// # Arguments
+    /// * `is_dev_env` - Whether the server is running in development
+    /// * `pool_size` - The maximum number of open SMTP connections
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Mailer)` - The configured mailer
+    /// * `Err(ChaosError)` - An error if the transport is unknown or could not be set up
+    pub fn from_env(is_dev_env: bool, pool_size: u32) -> Result<Mailer, ChaosError> {
+        let default_transport = if is_dev_env { "file" } else { "smtp" };
+        let transport = env::var("EMAIL_TRANSPORT").unwrap_or(default_transport.to_string());
+
+        if transport == "smtp" {
+            let credentials = ChaosEmail::setup_credentials()?;
+            let email_from = credentials.email_from.clone();
+            return Ok(Mailer::new(
+                Arc::new(SmtpEmailTransport::new(credentials, pool_size)?),
+                email_from,
+            ));
+        }
+
+        let email_from = env::var("EMAIL_FROM").unwrap_or(DEFAULT_EMAIL_FROM.to_string());
+        if transport == "memory" {
+            return Ok(Mailer::in_memory(email_from));
+        }
+
+        let transport: Arc<dyn EmailTransport> = match transport.as_str() {
+            "file" => {
+                let directory = env::var("EMAIL_FILE_DIRECTORY")
+                    .unwrap_or(DEFAULT_EMAIL_FILE_DIRECTORY.to_string());
+                Arc::new(FileEmailTransport::new(PathBuf::from(directory))?)
+            }
+            other => {
+                return Err(ChaosError::EmailTransportError(format!(
+                    "Unknown EMAIL_TRANSPORT: {other}"
+                )))
+            }
+        };
+
+        Ok(Mailer::new(transport, email_from))
+    }
+
+    /// Builds and sends an email message.
+    ///
+    /// # Arguments
+    /// * `recipient_name` - The name of the email recipient
+    /// * `recipient_email_address` - The email address of the recipient
+    /// * `subject` - The email subject
+    /// * `body` - The email body content
+    /// * `calendar` - Optional calendar event to attach
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the email was sent successfully
+    /// * `Err(ChaosError)` - An error if building or sending fails
+    pub async fn send(
+        &self,
+        recipient_name: Option<String>,
+        recipient_email_address: String,
+        subject: String,
+        body: String,
+        calendar: Option<CalendarInvite>,
+    ) -> Result<(), ChaosError> {
+        let message = ChaosEmail::build_message(
+            recipient_name,
+            recipient_email_address,
+            subject,
+            body,
+            calendar,
+            &self.email_from,
+        )?;
+
+        self.transport.send(message).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn memory_transport_captures_sent_messages() {
+        let mailer = Mailer::in_memory(DEFAULT_EMAIL_FROM.to_string());
+
+        mailer
+            .send(
+                Some("Applicant".to_string()),
+                "applicant@example.com".to_string(),
+                "Your application".to_string(),
+                "Thanks for applying".to_string(),
+                None,
+            )
+            .await
+            .expect("sending to memory should not fail");
+
+        let messages = mailer
+            .captured_messages()
+            .expect("memory mailer should capture messages");
+        assert_eq!(messages.len(), 1);
+        let formatted = String::from_utf8(messages[0].formatted()).unwrap();
+        assert!(formatted.contains("Subject: Your application"));
+        assert!(formatted.contains("applicant@example.com"));
+    }
+}
@@ -0,0 +1,187 @@
+//! Background delivery of queued emails.
+//!
+//! The worker claims due emails from the queue in batches and sends them
+//! concurrently through the configured [`Mailer`]. With the SMTP transport,
+//! connections are pooled and reused across messages instead of being opened
+//! for every email. Sends are paced by a
+//! configurable rate limit to stay within the SMTP provider's quota.
+
+use crate::models::email::EmailQueue;
+use crate::models::email_transport::Mailer;
+use crate::models::error::ChaosError;
+use sqlx::{Pool, Postgres};
+use std::env;
+use std::str::FromStr;
+use tokio::task::JoinSet;
+use tokio::time::{self, Duration, MissedTickBehavior};
+
+/// How long the worker waits before polling again when no emails are due.
+const EMAIL_IDLE_POLL_MILLIS: u64 = 1000;
+
+/// Extra time added to a batch's lease on top of its expected send time.
+const EMAIL_LEASE_MARGIN_SECONDS: f64 = 60.0;
+
+/// Tuning for the email worker, read from the environment.
+#[derive(Clone, Debug)]
+pub struct EmailWorkerConfig {
+    /// Maximum number of emails claimed from the queue at once.
+    pub batch_size: i64,
+    /// Maximum number of emails being sent at the same time. Also used as the
+    /// size of the SMTP connection pool.
+    pub concurrency: usize,
+    /// Maximum number of emails started per second.
+    pub rate_limit_per_second: u32,
+}
+
+impl EmailWorkerConfig {
+    /// Reads the worker configuration from the environment.
+    ///
+    /// Reads `EMAIL_BATCH_SIZE`, `EMAIL_CONCURRENCY` and
+    /// `EMAIL_RATE_LIMIT_PER_SECOND`, falling back to defaults for any that are
+    /// unset or invalid.
+    ///
+    /// # Returns
+    /// The worker configuration
+    pub fn from_env() -> EmailWorkerConfig {
+        EmailWorkerConfig {
+            batch_size: Self::read_var("EMAIL_BATCH_SIZE", 50),
+            concurrency: Self::read_var("EMAIL_CONCURRENCY", 5),
+            rate_limit_per_second: Self::read_var("EMAIL_RATE_LIMIT_PER_SECOND", 10),
+        }
+    }
+
+    /// Reads a positive number from the environment, or returns `default`.
+    fn read_var<T: FromStr + PartialOrd + Default>(name: &str, default: T) -> T {
+        match env::var(name)
+            .ok()
+            .and_then(|value| value.parse::<T>().ok())
+        {
+            Some(value) if value > T::default() => value,
+            _ => default,
+        }
+    }
+
+    /// Time a claimed batch is reserved for, based on how long it should take to send.
+    fn lease_seconds(&self) -> f64 {
+        self.batch_size as f64 / self.rate_limit_per_second as f64 * 2.0
+            + EMAIL_LEASE_MARGIN_SECONDS
+    }
+}
+
+/// Background worker that delivers queued emails.
+pub struct EmailWorker {
+    db: Pool<Postgres>,
+    mailer: Mailer,
+    config: EmailWorkerConfig,
+}
+
+impl EmailWorker {
+    /// Creates a new email worker.
+    ///
+    /// # Arguments
+    /// * `db` - The database connection pool
+    /// * `mailer` - The mailer to deliver emails through
+    /// * `config` - The worker configuration
+    pub fn new(db: Pool<Postgres>, mailer: Mailer, config: EmailWorkerConfig) -> EmailWorker {
+        EmailWorker { db, mailer, config }
+    }
+
+    /// Runs the worker forever, sending batches as they become due.
+    pub async fn run(self) {
+        let mut limiter =
+            time::interval(Duration::from_secs(1) / self.config.rate_limit_per_second);
+        limiter.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            match self.send_batch(&mut limiter).await {
+                Ok(0) => time::sleep(Duration::from_millis(EMAIL_IDLE_POLL_MILLIS)).await,
+                Ok(_) => {}
+                Err(e) => {
+                    e.print();
+                    time::sleep(Duration::from_millis(EMAIL_IDLE_POLL_MILLIS)).await;
+                }
+            }
+        }
+    }
+
+    /// Claims and sends one batch of emails.
+    ///
+    /// Each outcome is recorded in its own transaction, so one failed update does
+    /// not undo the rest of the batch.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(usize)` - The number of emails claimed
+    /// * `Err(ChaosError)` - An error if the queue could not be read or updated
+    async fn send_batch(&self, limiter: &mut time::Interval) -> Result<usize, ChaosError> {
+        let mut transaction = self.db.begin().await?;
+        let emails = EmailQueue::claim_batch(
+            self.config.batch_size,
+            self.config.lease_seconds(),
+            &mut transaction,
+        )
+        .await?;
+        transaction.commit().await?;
+
+        let claimed = emails.len();
+        let mut in_flight = JoinSet::new();
+
+        for email in emails {
+            while in_flight.len() >= self.config.concurrency {
+                if let Some(outcome) = in_flight.join_next().await {
+                    self.record_outcome(outcome).await?;
+                }
+            }
+
+            limiter.tick().await;
+
+            let mailer = self.mailer.clone();
+            in_flight.spawn(async move {
+                let result = mailer
+                    .send(
+                        email.recipient_name,
+                        email.recipient_email_address,
+                        email.subject,
+                        email.body,
+                        email.calendar,
+                    )
+                    .await;
+                (email.id, email.attempts, result)
+            });
+        }
+
+        while let Some(outcome) = in_flight.join_next().await {
+            self.record_outcome(outcome).await?;
+        }
+
+        Ok(claimed)
+    }
+
+    /// Removes a sent email from the queue, or records the failure for a retry.
+    async fn record_outcome(
+        &self,
+        outcome: Result<(i32, i32, Result<(), ChaosError>), tokio::task::JoinError>,
+    ) -> Result<(), ChaosError> {
+        let (id, attempts, result) = match outcome {
+            Ok(outcome) => outcome,
+            // A panicked send leaves its email leased; it is retried once the lease expires.
+            Err(e) => {
+                ChaosError::EmailTransportError(format!("Email send task failed: {e}")).print();
+                return Ok(());
+            }
+        };
+
+        let mut transaction = self.db.begin().await?;
+        match result {
+            Ok(()) => EmailQueue::mark_sent(id, &mut transaction).await?,
+            Err(e) => {
+                e.print();
+                EmailQueue::record_failure(id, attempts + 1, format!("{e:?}"), &mut transaction)
+                    .await?
+            }
+        }
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
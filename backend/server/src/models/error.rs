@@ -96,6 +96,10 @@ pub enum ChaosError {
     #[error("SMTP transport error")]
     SmtpTransportError(#[from] lettre::transport::smtp::Error),
 
+    /// Email transport is misconfigured or could not deliver a message
+    #[error("Email transport error")]
+    EmailTransportError(String),
+
     // not covered by any other error
     #[error("Internal server error")]
     InternalServerError,
@@ -124,6 +128,7 @@ impl ChaosError {
             ChaosError::LettreError(e) => println!("Lettre error: {}", e),
             ChaosError::AddressError(e) => println!("Address error: {}", e),
             ChaosError::SmtpTransportError(e) => println!("SmtpTransport error: {}", e),
+            ChaosError::EmailTransportError(e) => println!("Email transport error: {}", e),
         };
     }
 }
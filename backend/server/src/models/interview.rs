@@ -0,0 +1,1186 @@
+//! Interview management for Chaos.
+//!
+//! This module provides functionality for managing interview timeslots within a campaign,
+//! including creating and editing timeslots, assigning interviewers and applicants to them,
+//! and letting applicants book or cancel an open timeslot. Booked applicants are emailed a
+//! calendar invitation, along with updates and cancellations as the timeslot changes.
+
+use crate::models::calendar::{CalendarEvent, CalendarMethod};
+use crate::models::campaign::Campaign;
+use crate::models::email::{CalendarInvite, EmailContext, EmailQueue};
+use crate::models::error::ChaosError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use snowflake::SnowflakeIdGenerator;
+use sqlx::types::Json;
+use sqlx::{FromRow, Postgres, Transaction};
+use std::ops::DerefMut;
+
+/// Represents an interview timeslot in the database.
+///
+/// A timeslot is a block of time within a campaign's interview period, which
+/// interviewers are assigned to and a single applicant can book.
+#[derive(Deserialize, Serialize, Clone, FromRow, Debug)]
+pub struct InterviewTimeslot {
+    /// Unique identifier for the timeslot
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub id: i64,
+    /// ID of the campaign this timeslot belongs to
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub campaign_id: i64,
+    /// When the interview starts
+    pub start_time: DateTime<Utc>,
+    /// When the interview ends
+    pub end_time: DateTime<Utc>,
+    /// Where the interview takes place (room, video call link, etc.)
+    pub location: Option<String>,
+    /// Optional notes about the interview
+    pub description: Option<String>,
+    /// Whether an applicant has been booked into this timeslot
+    pub booked: bool,
+}
+
+/// Detailed view of an interview timeslot.
+///
+/// Includes every interviewer and applicant assigned to the timeslot.
+#[derive(Deserialize, Serialize, FromRow)]
+pub struct InterviewTimeslotDetails {
+    /// Unique identifier for the timeslot
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub id: i64,
+    /// ID of the campaign this timeslot belongs to
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub campaign_id: i64,
+    /// When the interview starts
+    pub start_time: DateTime<Utc>,
+    /// When the interview ends
+    pub end_time: DateTime<Utc>,
+    /// Where the interview takes place (room, video call link, etc.)
+    pub location: Option<String>,
+    /// Optional notes about the interview
+    pub description: Option<String>,
+    /// Whether an applicant has been booked into this timeslot
+    pub booked: bool,
+    /// Interviewers and applicants assigned to this timeslot
+    pub participants: Json<Vec<InterviewTimeslotParticipant>>,
+}
+
+/// A user assigned to an interview timeslot.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct InterviewTimeslotParticipant {
+    /// ID of the user
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub user_id: i64,
+    /// Name of the user
+    pub name: String,
+    /// Email of the user
+    pub email: String,
+    /// ID of the campaign role the interview is for
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub role_id: i64,
+    /// Name of the campaign role the interview is for
+    pub role_name: String,
+    /// Whether the user is interviewing (true) or being interviewed (false)
+    pub interviewer: bool,
+}
+
+/// Data structure for creating or updating an interview timeslot.
+#[derive(Deserialize, Serialize)]
+pub struct NewInterviewTimeslot {
+    /// When the interview starts
+    pub start_time: DateTime<Utc>,
+    /// When the interview ends
+    pub end_time: DateTime<Utc>,
+    /// Where the interview takes place (room, video call link, etc.)
+    pub location: Option<String>,
+    /// Optional notes about the interview
+    pub description: Option<String>,
+}
+
+/// Data structure for assigning a user to an interview timeslot.
+#[derive(Deserialize)]
+pub struct InterviewTimeslotAssignment {
+    /// ID of the user to assign
+    #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
+    pub user_id: i64,
+    /// ID of the campaign role the interview is for
+    #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
+    pub role_id: i64,
+    /// Whether the user is interviewing (true) or being interviewed (false)
+    pub interviewer: bool,
+}
+
+/// Data structure for an applicant booking an interview timeslot.
+#[derive(Deserialize)]
+pub struct InterviewBooking {
+    /// ID of the campaign role the applicant is interviewing for
+    #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
+    pub role_id: i64,
+}
+
+/// An organisation member assigned to interview applicants for a campaign role.
+#[derive(Deserialize, Serialize, FromRow, Debug)]
+pub struct RoleInterviewer {
+    /// ID of the interviewer
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub user_id: i64,
+    /// Name of the interviewer
+    pub name: String,
+    /// Email of the interviewer
+    pub email: String,
+}
+
+/// Data structure for adding or removing an interviewer from a campaign role.
+#[derive(Deserialize)]
+pub struct RoleInterviewerUpdate {
+    /// ID of the interviewer
+    #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
+    pub user_id: i64,
+}
+
+/// Kind of email sent to an applicant about their interview.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InterviewNotice {
+    Invitation,
+    Update,
+    Cancellation,
+}
+
+impl InterviewTimeslot {
+    /// Creates a new interview timeslot in a campaign.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign to create the timeslot in
+    /// * `data` - The times, location and description of the timeslot
+    /// * `snowflake_generator` - A generator for creating unique IDs
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(i64)` - The ID of the created timeslot
+    /// * `Err(ChaosError)` - An error if creation fails
+    pub async fn create(
+        campaign_id: i64,
+        data: NewInterviewTimeslot,
+        snowflake_generator: &mut SnowflakeIdGenerator,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<i64, ChaosError> {
+        data.validate(campaign_id, transaction).await?;
+        let id = snowflake_generator.real_time_generate();
+
+        sqlx::query!(
+            "
+                INSERT INTO interview_timeslots (id, campaign_id, start_time, end_time, location, description)
+                VALUES ($1, $2, $3, $4, $5, $6)
+            ",
+            id,
+            campaign_id,
+            data.start_time,
+            data.end_time,
+            data.location,
+            data.description
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Retrieves an interview timeslot and its participants by ID.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the timeslot to retrieve
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(InterviewTimeslotDetails)` - The requested timeslot
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get(
+        id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<InterviewTimeslotDetails, ChaosError> {
+        let timeslot = sqlx::query_as!(
+            InterviewTimeslotDetails,
+            r#"
+                SELECT t.id, t.campaign_id, t.start_time, t.end_time, t.location, t.description, t.booked,
+                    COALESCE(jsonb_agg(jsonb_build_object(
+                        'user_id', u.id,
+                        'name', u.name,
+                        'email', u.email,
+                        'role_id', r.id,
+                        'role_name', r.name,
+                        'interviewer', tu.interviewer
+                    )) FILTER (WHERE tu.user_id IS NOT NULL), '[]'::jsonb)
+                    AS "participants!: Json<Vec<InterviewTimeslotParticipant>>"
+                FROM interview_timeslots t
+                LEFT JOIN interview_timeslot_users tu ON tu.interview_timeslot_id = t.id
+                LEFT JOIN users u ON u.id = tu.user_id
+                LEFT JOIN campaign_roles r ON r.id = tu.role_id
+                WHERE t.id = $1
+                GROUP BY t.id
+            "#,
+            id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        Ok(timeslot)
+    }
+
+    /// Retrieves all interview timeslots in a campaign, with their participants.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign to get timeslots from
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<InterviewTimeslotDetails>)` - List of timeslots in the campaign
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get_all_by_campaign(
+        campaign_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<InterviewTimeslotDetails>, ChaosError> {
+        let timeslots = sqlx::query_as!(
+            InterviewTimeslotDetails,
+            r#"
+                SELECT t.id, t.campaign_id, t.start_time, t.end_time, t.location, t.description, t.booked,
+                    COALESCE(jsonb_agg(jsonb_build_object(
+                        'user_id', u.id,
+                        'name', u.name,
+                        'email', u.email,
+                        'role_id', r.id,
+                        'role_name', r.name,
+                        'interviewer', tu.interviewer
+                    )) FILTER (WHERE tu.user_id IS NOT NULL), '[]'::jsonb)
+                    AS "participants!: Json<Vec<InterviewTimeslotParticipant>>"
+                FROM interview_timeslots t
+                LEFT JOIN interview_timeslot_users tu ON tu.interview_timeslot_id = t.id
+                LEFT JOIN users u ON u.id = tu.user_id
+                LEFT JOIN campaign_roles r ON r.id = tu.role_id
+                WHERE t.campaign_id = $1
+                GROUP BY t.id
+                ORDER BY t.start_time
+            "#,
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        Ok(timeslots)
+    }
+
+    /// Retrieves the timeslots that an applicant can still book for a role.
+    ///
+    /// Only unbooked timeslots that start in the future and have at least one
+    /// interviewer for the given role are returned.
+    ///
+    /// # Arguments
+    /// * `role_id` - The ID of the role the applicant is interviewing for
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<InterviewTimeslot>)` - List of open timeslots
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get_open_by_role(
+        role_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<InterviewTimeslot>, ChaosError> {
+        let timeslots = sqlx::query_as!(
+            InterviewTimeslot,
+            "
+                SELECT t.id, t.campaign_id, t.start_time, t.end_time, t.location, t.description, t.booked
+                FROM interview_timeslots t
+                WHERE t.booked = false AND t.start_time > NOW()
+                AND EXISTS(
+                    SELECT 1 FROM interview_timeslot_users tu
+                    WHERE tu.interview_timeslot_id = t.id AND tu.interviewer = true AND tu.role_id = $1
+                )
+                ORDER BY t.start_time
+            ",
+            role_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        Ok(timeslots)
+    }
+
+    /// Retrieves the timeslots in a campaign that a user has been booked into.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign to get timeslots from
+    /// * `user_id` - The ID of the applicant
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<InterviewTimeslot>)` - List of the applicant's timeslots
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get_booked_by_user(
+        campaign_id: i64,
+        user_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<InterviewTimeslot>, ChaosError> {
+        let timeslots = sqlx::query_as!(
+            InterviewTimeslot,
+            "
+                SELECT t.id, t.campaign_id, t.start_time, t.end_time, t.location, t.description, t.booked
+                FROM interview_timeslots t
+                JOIN interview_timeslot_users tu ON tu.interview_timeslot_id = t.id
+                WHERE t.campaign_id = $1 AND tu.user_id = $2 AND tu.interviewer = false
+                ORDER BY t.start_time
+            ",
+            campaign_id,
+            user_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        Ok(timeslots)
+    }
+
+    /// Updates the times, location and description of an interview timeslot.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the timeslot to update
+    /// * `data` - The new timeslot details
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the timeslot was updated successfully
+    /// * `Err(ChaosError)` - An error if update fails
+    pub async fn update(
+        id: i64,
+        data: NewInterviewTimeslot,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let campaign_id = sqlx::query!(
+            "SELECT campaign_id FROM interview_timeslots WHERE id = $1",
+            id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .campaign_id;
+
+        data.validate(campaign_id, transaction).await?;
+
+        sqlx::query!(
+            "
+                UPDATE interview_timeslots
+                SET (start_time, end_time, location, description) = ($2, $3, $4, $5)
+                WHERE id = $1 RETURNING id
+            ",
+            id,
+            data.start_time,
+            data.end_time,
+            data.location,
+            data.description
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        if let Some(applicant_id) = Self::get_applicant_id(id, transaction).await? {
+            Self::queue_notice(id, applicant_id, InterviewNotice::Update, transaction).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes an interview timeslot, along with all of its assignments.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the timeslot to delete
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the timeslot was deleted successfully
+    /// * `Err(ChaosError)` - An error if deletion fails
+    pub async fn delete(
+        id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        if let Some(applicant_id) = Self::get_applicant_id(id, transaction).await? {
+            Self::queue_notice(id, applicant_id, InterviewNotice::Cancellation, transaction)
+                .await?;
+        }
+
+        sqlx::query!(
+            "DELETE FROM interview_timeslots WHERE id = $1 RETURNING id",
+            id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Assigns an interviewer or applicant to an interview timeslot.
+    ///
+    /// Assigning an applicant marks the timeslot as booked, so only one
+    /// applicant can be assigned to each timeslot.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the timeslot
+    /// * `assignment` - The user, role and whether they are interviewing
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the user was assigned successfully
+    /// * `Err(ChaosError)` - An error if assignment fails
+    pub async fn assign_user(
+        id: i64,
+        assignment: InterviewTimeslotAssignment,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let timeslot = sqlx::query!(
+            "SELECT campaign_id, booked FROM interview_timeslots WHERE id = $1 FOR UPDATE",
+            id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        let role_in_campaign = sqlx::query!(
+            "SELECT EXISTS(SELECT 1 FROM campaign_roles WHERE id = $1 AND campaign_id = $2)",
+            assignment.role_id,
+            timeslot.campaign_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .exists
+        .expect("`exists` should always exist in this query result");
+
+        if !role_in_campaign {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Role does not belong to this campaign".to_string(),
+            ));
+        }
+
+        if assignment.interviewer {
+            assert_user_is_campaign_member(assignment.user_id, timeslot.campaign_id, transaction)
+                .await?;
+        } else {
+            if timeslot.booked {
+                return Err(ChaosError::BadRequestWithMessage(
+                    "Timeslot is already booked".to_string(),
+                ));
+            }
+
+            assert_user_applied_for_role(
+                assignment.user_id,
+                timeslot.campaign_id,
+                assignment.role_id,
+                transaction,
+            )
+            .await?;
+
+            sqlx::query!(
+                "UPDATE interview_timeslots SET booked = true WHERE id = $1",
+                id
+            )
+            .execute(transaction.deref_mut())
+            .await?;
+        }
+
+        sqlx::query!(
+            "
+                INSERT INTO interview_timeslot_users (interview_timeslot_id, user_id, role_id, interviewer)
+                VALUES ($1, $2, $3, $4)
+            ",
+            id,
+            assignment.user_id,
+            assignment.role_id,
+            assignment.interviewer
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        if !assignment.interviewer {
+            Self::queue_notice(
+                id,
+                assignment.user_id,
+                InterviewNotice::Invitation,
+                transaction,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes a user from an interview timeslot.
+    ///
+    /// Removing the applicant frees the timeslot up to be booked again.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the timeslot
+    /// * `user_id` - The ID of the user to remove
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the user was removed successfully
+    /// * `Err(ChaosError)` - An error if removal fails
+    pub async fn remove_user(
+        id: i64,
+        user_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let interviewer = sqlx::query!(
+            "
+                SELECT interviewer FROM interview_timeslot_users
+                WHERE interview_timeslot_id = $1 AND user_id = $2
+            ",
+            id,
+            user_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .interviewer;
+
+        if !interviewer {
+            Self::queue_notice(id, user_id, InterviewNotice::Cancellation, transaction).await?;
+        }
+
+        sqlx::query!(
+            "
+                DELETE FROM interview_timeslot_users
+                WHERE interview_timeslot_id = $1 AND user_id = $2
+            ",
+            id,
+            user_id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        if !interviewer {
+            sqlx::query!(
+                "UPDATE interview_timeslots SET booked = false WHERE id = $1",
+                id
+            )
+            .execute(transaction.deref_mut())
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Books an open interview timeslot for an applicant.
+    ///
+    /// The timeslot must not already be booked, must lie within the campaign's
+    /// interview period and must start in the future. An applicant can only hold
+    /// one booking per role in a campaign.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the timeslot to book
+    /// * `user_id` - The ID of the applicant
+    /// * `role_id` - The ID of the role the applicant is interviewing for
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the timeslot was booked successfully
+    /// * `Err(ChaosError)` - An error if booking fails
+    pub async fn book(
+        id: i64,
+        user_id: i64,
+        role_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        // Lock the timeslot so two applicants cannot book it at the same time.
+        let timeslot = sqlx::query_as!(
+            InterviewTimeslot,
+            "
+                SELECT id, campaign_id, start_time, end_time, location, description, booked
+                FROM interview_timeslots
+                WHERE id = $1
+                FOR UPDATE
+            ",
+            id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        if timeslot.booked {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Timeslot is already booked".to_string(),
+            ));
+        }
+
+        if timeslot.start_time <= Utc::now() {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Timeslot has already started".to_string(),
+            ));
+        }
+
+        let campaign = Campaign::get(timeslot.campaign_id, transaction).await?;
+        assert_within_interview_period(
+            timeslot.start_time,
+            timeslot.end_time,
+            campaign.interview_period_starts_at,
+            campaign.interview_period_ends_at,
+        )?;
+
+        assert_user_applied_for_role(user_id, timeslot.campaign_id, role_id, transaction).await?;
+
+        let has_interviewer = sqlx::query!(
+            "
+                SELECT EXISTS(
+                    SELECT 1 FROM interview_timeslot_users
+                    WHERE interview_timeslot_id = $1 AND interviewer = true AND role_id = $2
+                )
+            ",
+            id,
+            role_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .exists
+        .expect("`exists` should always exist in this query result");
+
+        if !has_interviewer {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Timeslot is not open for this role".to_string(),
+            ));
+        }
+
+        let already_booked = sqlx::query!(
+            "
+                SELECT EXISTS(
+                    SELECT 1 FROM interview_timeslot_users tu
+                    JOIN interview_timeslots t ON t.id = tu.interview_timeslot_id
+                    WHERE t.campaign_id = $1 AND tu.user_id = $2 AND tu.role_id = $3
+                    AND tu.interviewer = false
+                )
+            ",
+            timeslot.campaign_id,
+            user_id,
+            role_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .exists
+        .expect("`exists` should always exist in this query result");
+
+        if already_booked {
+            return Err(ChaosError::BadRequestWithMessage(
+                "You have already booked an interview for this role".to_string(),
+            ));
+        }
+
+        sqlx::query!(
+            "
+                INSERT INTO interview_timeslot_users (interview_timeslot_id, user_id, role_id, interviewer)
+                VALUES ($1, $2, $3, false)
+            ",
+            id,
+            user_id,
+            role_id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        sqlx::query!(
+            "UPDATE interview_timeslots SET booked = true WHERE id = $1",
+            id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Self::queue_notice(id, user_id, InterviewNotice::Invitation, transaction).await?;
+
+        Ok(())
+    }
+
+    /// Cancels an applicant's booking of an interview timeslot.
+    ///
+    /// Bookings can only be cancelled before the interview starts.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the booked timeslot
+    /// * `user_id` - The ID of the applicant
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the booking was cancelled successfully
+    /// * `Err(ChaosError)` - An error if cancellation fails
+    pub async fn cancel_booking(
+        id: i64,
+        user_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let timeslot = sqlx::query!(
+            "SELECT start_time FROM interview_timeslots WHERE id = $1 FOR UPDATE",
+            id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        if timeslot.start_time <= Utc::now() {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Timeslot has already started".to_string(),
+            ));
+        }
+
+        Self::queue_notice(id, user_id, InterviewNotice::Cancellation, transaction).await?;
+
+        sqlx::query!(
+            "
+                DELETE FROM interview_timeslot_users
+                WHERE interview_timeslot_id = $1 AND user_id = $2 AND interviewer = false
+                RETURNING user_id
+            ",
+            id,
+            user_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        sqlx::query!(
+            "UPDATE interview_timeslots SET booked = false WHERE id = $1",
+            id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Builds calendar events for every interview a user is part of, across all campaigns.
+    ///
+    /// Interviewers see the applicant they are interviewing, and applicants see
+    /// the role they are interviewing for. Events share their UID with the
+    /// invitation emails sent for the timeslot.
+    ///
+    /// # Arguments
+    /// * `user_id` - The ID of the interviewer or applicant
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<CalendarEvent>)` - The user's interviews as calendar events
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get_calendar_events_by_user(
+        user_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<CalendarEvent>, ChaosError> {
+        let rows = sqlx::query!(
+            "
+                SELECT t.id, t.start_time, t.end_time, t.location, t.description,
+                    t.calendar_sequence, tu.interviewer,
+                    c.name AS campaign_name, o.name AS organisation_name, r.name AS role_name,
+                    u.name AS user_name, u.email AS user_email,
+                    (
+                        SELECT au.name FROM interview_timeslot_users atu
+                        JOIN users au ON au.id = atu.user_id
+                        WHERE atu.interview_timeslot_id = t.id AND atu.interviewer = false
+                    ) AS applicant_name
+                FROM interview_timeslot_users tu
+                JOIN interview_timeslots t ON t.id = tu.interview_timeslot_id
+                JOIN campaigns c ON c.id = t.campaign_id
+                JOIN organisations o ON o.id = c.organisation_id
+                JOIN campaign_roles r ON r.id = tu.role_id
+                JOIN users u ON u.id = tu.user_id
+                WHERE tu.user_id = $1
+                ORDER BY t.start_time
+            ",
+            user_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let events = rows
+            .into_iter()
+            .map(|row| {
+                let title = format!(
+                    "{} {} interview: {}",
+                    row.organisation_name, row.campaign_name, row.role_name
+                );
+                let summary = match (row.interviewer, &row.applicant_name) {
+                    (true, Some(applicant_name)) => format!("{title} ({applicant_name})"),
+                    _ => title,
+                };
+
+                CalendarEvent {
+                    uid: format!("interview-{}@chaos.devsoc.app", row.id),
+                    sequence: row.calendar_sequence,
+                    start_time: row.start_time,
+                    end_time: row.end_time,
+                    summary,
+                    description: row.description,
+                    location: row.location,
+                    attendee_name: Some(row.user_name),
+                    attendee_email: row.user_email,
+                }
+            })
+            .collect();
+
+        Ok(events)
+    }
+
+    /// Retrieves the ID of the applicant booked into a timeslot, if any.
+    async fn get_applicant_id(
+        id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Option<i64>, ChaosError> {
+        let applicant = sqlx::query!(
+            "
+                SELECT user_id FROM interview_timeslot_users
+                WHERE interview_timeslot_id = $1 AND interviewer = false
+            ",
+            id
+        )
+        .fetch_optional(transaction.deref_mut())
+        .await?;
+
+        Ok(applicant.map(|a| a.user_id))
+    }
+
+    /// Queues an email to a booked applicant about their interview, with a calendar event attached.
+    ///
+    /// Every event for a timeslot shares the same UID, and the timeslot's calendar
+    /// sequence is bumped each time so that calendar clients apply them in order.
+    /// Must be called while the applicant is still assigned to the timeslot.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the timeslot
+    /// * `user_id` - The ID of the booked applicant
+    /// * `notice` - Whether this is an invitation, update or cancellation
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the email was queued successfully
+    /// * `Err(ChaosError)` - An error if the applicant is not booked or queuing fails
+    pub async fn queue_notice(
+        id: i64,
+        user_id: i64,
+        notice: InterviewNotice,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let details = sqlx::query!(
+            "
+                SELECT t.start_time, t.end_time, t.location, t.description, t.campaign_id,
+                    (
+                        SELECT a.id FROM applications a
+                        WHERE a.campaign_id = t.campaign_id AND a.user_id = tu.user_id
+                    ) AS application_id,
+                    c.name AS campaign_name, o.name AS organisation_name, r.name AS role_name,
+                    u.name AS user_name, u.email AS user_email
+                FROM interview_timeslots t
+                JOIN campaigns c ON c.id = t.campaign_id
+                JOIN organisations o ON o.id = c.organisation_id
+                JOIN interview_timeslot_users tu ON tu.interview_timeslot_id = t.id
+                JOIN campaign_roles r ON r.id = tu.role_id
+                JOIN users u ON u.id = tu.user_id
+                WHERE t.id = $1 AND tu.user_id = $2 AND tu.interviewer = false
+            ",
+            id,
+            user_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        let sequence = sqlx::query!(
+            "
+                UPDATE interview_timeslots SET calendar_sequence = calendar_sequence + 1
+                WHERE id = $1 RETURNING calendar_sequence
+            ",
+            id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .calendar_sequence;
+
+        let title = format!(
+            "{} {} interview: {}",
+            details.organisation_name, details.campaign_name, details.role_name
+        );
+        let (subject, summary_line, method) = match notice {
+            InterviewNotice::Invitation => (
+                format!("Interview invitation - {title}"),
+                "has been scheduled",
+                CalendarMethod::Request,
+            ),
+            InterviewNotice::Update => (
+                format!("Interview updated - {title}"),
+                "has been changed",
+                CalendarMethod::Request,
+            ),
+            InterviewNotice::Cancellation => (
+                format!("Interview cancelled - {title}"),
+                "has been cancelled",
+                CalendarMethod::Cancel,
+            ),
+        };
+
+        let location = details
+            .location
+            .clone()
+            .unwrap_or_else(|| "To be confirmed".to_string());
+        let mut body = format!(
+            "Hi {},\n\nYour interview for the {} role in {} {} {}.\n\nWhen: {} - {} UTC\nWhere: {}\n",
+            details.user_name,
+            details.role_name,
+            details.organisation_name,
+            details.campaign_name,
+            summary_line,
+            details.start_time.format("%A %-d %B %Y, %H:%M"),
+            details.end_time.format("%H:%M"),
+            location
+        );
+        if let Some(description) = &details.description {
+            body.push_str(&format!("\n{description}\n"));
+        }
+        body.push_str("\nThe attached calendar event will keep your calendar up to date.\n");
+
+        let event = CalendarEvent {
+            uid: format!("interview-{id}@chaos.devsoc.app"),
+            sequence,
+            start_time: details.start_time,
+            end_time: details.end_time,
+            summary: title,
+            description: details.description,
+            location: details.location,
+            attendee_name: Some(details.user_name.clone()),
+            attendee_email: details.user_email.clone(),
+        };
+
+        EmailQueue::add_calendar_to_queue(
+            Some(details.user_name),
+            details.user_email,
+            subject,
+            body,
+            CalendarInvite {
+                method,
+                ics: event.to_ics(method),
+            },
+            EmailContext {
+                campaign_id: Some(details.campaign_id),
+                application_id: details.application_id,
+                offer_id: None,
+                hold_for_release: false,
+            },
+            transaction,
+        )
+        .await
+    }
+}
+
+impl RoleInterviewer {
+    /// Assigns an organisation member as an interviewer for a campaign role.
+    ///
+    /// # Arguments
+    /// * `role_id` - The ID of the campaign role
+    /// * `user_id` - The ID of the interviewer
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the interviewer was assigned successfully
+    /// * `Err(ChaosError)` - An error if assignment fails
+    pub async fn add(
+        role_id: i64,
+        user_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let campaign_id = sqlx::query!(
+            "SELECT campaign_id FROM campaign_roles WHERE id = $1",
+            role_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .campaign_id;
+
+        assert_user_is_campaign_member(user_id, campaign_id, transaction).await?;
+
+        sqlx::query!(
+            "
+                INSERT INTO campaign_role_interviewers (campaign_role_id, user_id)
+                VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+            ",
+            role_id,
+            user_id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes an interviewer from a campaign role.
+    ///
+    /// # Arguments
+    /// * `role_id` - The ID of the campaign role
+    /// * `user_id` - The ID of the interviewer
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the interviewer was removed successfully
+    /// * `Err(ChaosError)` - An error if removal fails
+    pub async fn remove(
+        role_id: i64,
+        user_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        sqlx::query!(
+            "
+                DELETE FROM campaign_role_interviewers
+                WHERE campaign_role_id = $1 AND user_id = $2
+                RETURNING user_id
+            ",
+            role_id,
+            user_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Retrieves all interviewers assigned to a campaign role.
+    ///
+    /// # Arguments
+    /// * `role_id` - The ID of the campaign role
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<RoleInterviewer>)` - List of interviewers for the role
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get_all_by_role(
+        role_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<RoleInterviewer>, ChaosError> {
+        let interviewers = sqlx::query_as!(
+            RoleInterviewer,
+            "
+                SELECT u.id AS user_id, u.name, u.email
+                FROM campaign_role_interviewers cri
+                JOIN users u ON u.id = cri.user_id
+                WHERE cri.campaign_role_id = $1
+                ORDER BY u.name
+            ",
+            role_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        Ok(interviewers)
+    }
+}
+
+impl NewInterviewTimeslot {
+    /// Checks that the timeslot is well formed and lies within the campaign's interview period.
+    pub async fn validate(
+        &self,
+        campaign_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        if self.end_time <= self.start_time {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Timeslot must end after it starts".to_string(),
+            ));
+        }
+
+        let campaign = Campaign::get(campaign_id, transaction).await?;
+        assert_within_interview_period(
+            self.start_time,
+            self.end_time,
+            campaign.interview_period_starts_at,
+            campaign.interview_period_ends_at,
+        )
+    }
+}
+
+/// Ensures a timeslot falls inside the campaign's interview period, where one is set.
+fn assert_within_interview_period(
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    period_starts_at: Option<DateTime<Utc>>,
+    period_ends_at: Option<DateTime<Utc>>,
+) -> Result<(), ChaosError> {
+    let starts_too_early = period_starts_at.is_some_and(|starts_at| start_time < starts_at);
+    let ends_too_late = period_ends_at.is_some_and(|ends_at| end_time > ends_at);
+
+    if starts_too_early || ends_too_late {
+        return Err(ChaosError::BadRequestWithMessage(
+            "Timeslot is outside the campaign's interview period".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Ensures a user has a submitted application to a campaign that includes the given role.
+async fn assert_user_applied_for_role(
+    user_id: i64,
+    campaign_id: i64,
+    role_id: i64,
+    transaction: &mut Transaction<'_, Postgres>,
+) -> Result<(), ChaosError> {
+    let applied = sqlx::query!(
+        "
+            SELECT EXISTS(
+                SELECT 1 FROM applications a
+                JOIN application_roles ar ON ar.application_id = a.id
+                WHERE a.campaign_id = $1 AND a.user_id = $2 AND ar.campaign_role_id = $3
+                AND a.submitted = true
+            )
+        ",
+        campaign_id,
+        user_id,
+        role_id
+    )
+    .fetch_one(transaction.deref_mut())
+    .await?
+    .exists
+    .expect("`exists` should always exist in this query result");
+
+    if !applied {
+        return Err(ChaosError::BadRequestWithMessage(
+            "User has not applied for this role".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Ensures a user is a member of the organisation running a campaign.
+async fn assert_user_is_campaign_member(
+    user_id: i64,
+    campaign_id: i64,
+    transaction: &mut Transaction<'_, Postgres>,
+) -> Result<(), ChaosError> {
+    let is_member = sqlx::query!(
+        "
+            SELECT EXISTS(
+                SELECT 1 FROM campaigns c
+                JOIN organisation_members m ON m.organisation_id = c.organisation_id
+                WHERE c.id = $1 AND m.user_id = $2
+            )
+        ",
+        campaign_id,
+        user_id
+    )
+    .fetch_one(transaction.deref_mut())
+    .await?
+    .exists
+    .expect("`exists` should always exist in this query result");
+
+    if !is_member {
+        return Err(ChaosError::BadRequestWithMessage(
+            "Interviewers must be members of the organisation".to_string(),
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,928 @@
+//! Automatic interview scheduling for Chaos.
+//!
+//! This module builds a proposed set of interview timeslots for a campaign from the
+//! availability grids submitted by applicants and interviewers, and commits a reviewed
+//! proposal as real timeslots.
+//!
+//! Interviews start on a grid of the slot length, anchored to the start of the
+//! interview period, so interviews in different windows never overlap. In each window
+//! the free interviewers are grouped into panels for the roles that need them most.
+//! Applicants are then matched to panels with a maximum bipartite matching, which
+//! schedules as many interviews as the panels allow. An applicant interviewing for
+//! several roles is never given two panels in the same window.
+
+use crate::models::availabilities::AVAILABILITY_SLOT_MINUTES;
+use crate::models::campaign::Campaign;
+use crate::models::error::ChaosError;
+use crate::models::interview::{
+    InterviewTimeslot, InterviewTimeslotAssignment, NewInterviewTimeslot,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use snowflake::SnowflakeIdGenerator;
+use sqlx::{Postgres, Transaction};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::DerefMut;
+
+/// Length of a single availability slot, in seconds.
+const SLOT_SECONDS: i64 = AVAILABILITY_SLOT_MINUTES * 60;
+
+/// Options for generating an interview schedule.
+#[derive(Deserialize)]
+pub struct ScheduleOptions {
+    /// Length of each interview in minutes, must be a multiple of 15
+    pub slot_length_minutes: i64,
+    /// Number of interviewers on each interview panel
+    pub panel_size: usize,
+}
+
+/// A single interview in a proposed schedule.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ProposedInterview {
+    /// When the interview starts
+    pub start_time: DateTime<Utc>,
+    /// When the interview ends
+    pub end_time: DateTime<Utc>,
+    /// ID of the campaign role the interview is for
+    #[serde(
+        serialize_with = "crate::models::serde_string::serialize",
+        deserialize_with = "crate::models::serde_string::deserialize"
+    )]
+    pub role_id: i64,
+    /// ID of the applicant being interviewed
+    #[serde(
+        serialize_with = "crate::models::serde_string::serialize",
+        deserialize_with = "crate::models::serde_string::deserialize"
+    )]
+    pub applicant_id: i64,
+    /// IDs of the interviewers on the panel
+    #[serde(
+        serialize_with = "crate::models::serde_string::serialize_vec",
+        deserialize_with = "crate::models::serde_string::deserialize_vec"
+    )]
+    pub interviewer_ids: Vec<i64>,
+}
+
+/// An applicant and role that could not be fit into the schedule.
+#[derive(Serialize)]
+pub struct UnscheduledApplicant {
+    /// ID of the applicant
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub applicant_id: i64,
+    /// ID of the campaign role the applicant needs an interview for
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub role_id: i64,
+}
+
+/// A proposed interview schedule for admins to review.
+#[derive(Serialize)]
+pub struct ScheduleProposal {
+    /// Interviews that could be scheduled
+    pub interviews: Vec<ProposedInterview>,
+    /// Applicants that could not be scheduled
+    pub unscheduled: Vec<UnscheduledApplicant>,
+}
+
+/// A reviewed schedule to be turned into interview timeslots.
+#[derive(Deserialize)]
+pub struct ScheduleCommit {
+    /// Location applied to every created timeslot
+    pub location: Option<String>,
+    /// Description applied to every created timeslot
+    pub description: Option<String>,
+    /// Interviews to create
+    pub interviews: Vec<ProposedInterview>,
+}
+
+/// Applicant and role awaiting an interview, along with every start block that could host it.
+struct Candidate {
+    applicant_id: i64,
+    role_id: i64,
+    starts: Vec<i64>,
+}
+
+/// Interviewers grouped to interview for one role in one window.
+struct Panel {
+    start: i64,
+    role_id: i64,
+    interviewer_ids: Vec<i64>,
+}
+
+/// A matching between candidates and panels, grown one augmenting path at a time.
+struct Matching<'a> {
+    candidates: &'a [Candidate],
+    panels: &'a [Panel],
+    /// Panels each candidate could be interviewed by
+    edges: Vec<Vec<usize>>,
+    panel_of: Vec<Option<usize>>,
+    candidate_of: Vec<Option<usize>>,
+}
+
+pub struct InterviewScheduler;
+
+impl InterviewScheduler {
+    /// Proposes an interview schedule for a campaign.
+    ///
+    /// Every applicant whose status for a role is `Interview` and who has not already
+    /// been booked for that role is considered. An interview can be placed at a time
+    /// when the applicant and at least `panel_size` of the role's interviewers are all
+    /// available for the full slot length and not already in another interview.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign to schedule interviews for
+    /// * `options` - The slot length and panel size to use
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(ScheduleProposal)` - The proposed interviews and anyone left unscheduled
+    /// * `Err(ChaosError)` - An error if the options are invalid or retrieval fails
+    pub async fn propose(
+        campaign_id: i64,
+        options: ScheduleOptions,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<ScheduleProposal, ChaosError> {
+        if options.panel_size == 0 {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Panel size must be at least 1".to_string(),
+            ));
+        }
+
+        if options.slot_length_minutes <= 0
+            || options.slot_length_minutes % AVAILABILITY_SLOT_MINUTES != 0
+        {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Slot length must be a positive multiple of 15 minutes".to_string(),
+            ));
+        }
+
+        let length = options.slot_length_minutes / AVAILABILITY_SLOT_MINUTES;
+        let campaign = Campaign::get(campaign_id, transaction).await?;
+        let period_start = campaign.interview_period_starts_at.map(to_block_ceil);
+        let period_end = campaign.interview_period_ends_at.map(to_block);
+        let earliest_start = to_block_ceil(Utc::now());
+
+        let applicants = sqlx::query!(
+            "
+                SELECT a.user_id, ar.campaign_role_id
+                FROM applications a
+                JOIN application_roles ar ON ar.application_id = a.id
+                WHERE a.campaign_id = $1 AND a.submitted = true AND ar.role_status = 'Interview'
+                AND NOT EXISTS(
+                    SELECT 1 FROM interview_timeslot_users tu
+                    JOIN interview_timeslots t ON t.id = tu.interview_timeslot_id
+                    WHERE t.campaign_id = $1 AND tu.user_id = a.user_id
+                    AND tu.role_id = ar.campaign_role_id AND tu.interviewer = false
+                )
+                ORDER BY a.user_id, ar.campaign_role_id
+            ",
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let role_interviewers = sqlx::query!(
+            "
+                SELECT cri.campaign_role_id, cri.user_id
+                FROM campaign_role_interviewers cri
+                JOIN campaign_roles r ON r.id = cri.campaign_role_id
+                WHERE r.campaign_id = $1
+            ",
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let availability_slots = sqlx::query!(
+            "
+                SELECT uc.user_id, s.start_time
+                FROM availability_slots s
+                JOIN user_campaign_availabilities uc ON uc.id = s.availability_id
+                WHERE uc.campaign_id = $1
+            ",
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let existing_interviews = sqlx::query!(
+            "
+                SELECT tu.user_id, t.start_time, t.end_time
+                FROM interview_timeslot_users tu
+                JOIN interview_timeslots t ON t.id = tu.interview_timeslot_id
+                WHERE t.campaign_id = $1
+            ",
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let mut interviewers_by_role: HashMap<i64, Vec<i64>> = HashMap::new();
+        for row in role_interviewers {
+            interviewers_by_role
+                .entry(row.campaign_role_id)
+                .or_default()
+                .push(row.user_id);
+        }
+
+        let mut available: HashMap<i64, HashSet<i64>> = HashMap::new();
+        for row in availability_slots {
+            available
+                .entry(row.user_id)
+                .or_default()
+                .insert(to_block(row.start_time));
+        }
+
+        let mut busy: HashMap<i64, HashSet<i64>> = HashMap::new();
+        for row in existing_interviews {
+            let blocks = busy.entry(row.user_id).or_default();
+            for block in to_block(row.start_time)..to_block_ceil(row.end_time) {
+                blocks.insert(block);
+            }
+        }
+
+        let applicants = applicants
+            .into_iter()
+            .map(|row| (row.user_id, row.campaign_role_id))
+            .collect();
+
+        Ok(build_schedule(ScheduleInput {
+            applicants,
+            interviewers_by_role,
+            available,
+            busy,
+            length,
+            panel_size: options.panel_size,
+            earliest_start,
+            period_start,
+            period_end,
+        }))
+    }
+
+    /// Creates interview timeslots for every interview in a reviewed schedule.
+    ///
+    /// Each interview is re-checked against the current state of the campaign, so
+    /// a schedule that has gone stale since it was proposed is rejected as a whole.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign to create timeslots in
+    /// * `schedule` - The reviewed schedule
+    /// * `snowflake_generator` - A generator for creating unique IDs
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<i64>)` - The IDs of the created timeslots
+    /// * `Err(ChaosError)` - An error if any interview is invalid or creation fails
+    pub async fn commit(
+        campaign_id: i64,
+        schedule: ScheduleCommit,
+        snowflake_generator: &mut SnowflakeIdGenerator,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<i64>, ChaosError> {
+        let mut timeslot_ids = Vec::with_capacity(schedule.interviews.len());
+
+        for interview in schedule.interviews {
+            let interviewer_ids: Vec<i64> = interview
+                .interviewer_ids
+                .into_iter()
+                .collect::<HashSet<i64>>()
+                .into_iter()
+                .collect();
+
+            if interviewer_ids.is_empty() {
+                return Err(ChaosError::BadRequestWithMessage(
+                    "Every interview needs at least one interviewer".to_string(),
+                ));
+            }
+
+            let assigned = sqlx::query!(
+                r#"
+                    SELECT COUNT(*) AS "count!"
+                    FROM campaign_role_interviewers cri
+                    JOIN campaign_roles r ON r.id = cri.campaign_role_id
+                    WHERE r.id = $1 AND r.campaign_id = $2 AND cri.user_id = ANY($3)
+                "#,
+                interview.role_id,
+                campaign_id,
+                &interviewer_ids
+            )
+            .fetch_one(transaction.deref_mut())
+            .await?
+            .count;
+
+            if assigned != interviewer_ids.len() as i64 {
+                return Err(ChaosError::BadRequestWithMessage(
+                    "Interviewers must be assigned to the role they are interviewing for"
+                        .to_string(),
+                ));
+            }
+
+            for user_id in interviewer_ids
+                .iter()
+                .chain(std::iter::once(&interview.applicant_id))
+            {
+                assert_user_is_free(
+                    *user_id,
+                    campaign_id,
+                    interview.start_time,
+                    interview.end_time,
+                    transaction,
+                )
+                .await?;
+            }
+
+            let timeslot_id = InterviewTimeslot::create(
+                campaign_id,
+                NewInterviewTimeslot {
+                    start_time: interview.start_time,
+                    end_time: interview.end_time,
+                    location: schedule.location.clone(),
+                    description: schedule.description.clone(),
+                },
+                snowflake_generator,
+                transaction,
+            )
+            .await?;
+
+            for user_id in interviewer_ids {
+                InterviewTimeslot::assign_user(
+                    timeslot_id,
+                    InterviewTimeslotAssignment {
+                        user_id,
+                        role_id: interview.role_id,
+                        interviewer: true,
+                    },
+                    transaction,
+                )
+                .await?;
+            }
+
+            InterviewTimeslot::assign_user(
+                timeslot_id,
+                InterviewTimeslotAssignment {
+                    user_id: interview.applicant_id,
+                    role_id: interview.role_id,
+                    interviewer: false,
+                },
+                transaction,
+            )
+            .await?;
+
+            timeslot_ids.push(timeslot_id);
+        }
+
+        Ok(timeslot_ids)
+    }
+}
+
+/// Everything a schedule is built from, with times as availability slot indexes.
+struct ScheduleInput {
+    /// Applicant and role pairs awaiting an interview
+    applicants: Vec<(i64, i64)>,
+    interviewers_by_role: HashMap<i64, Vec<i64>>,
+    /// Slots each user is available in
+    available: HashMap<i64, HashSet<i64>>,
+    /// Slots each user is already interviewing in
+    busy: HashMap<i64, HashSet<i64>>,
+    /// Number of slots in each interview
+    length: i64,
+    panel_size: usize,
+    earliest_start: i64,
+    period_start: Option<i64>,
+    period_end: Option<i64>,
+}
+
+/// Builds a schedule from the campaign's applicants, interviewers and availability.
+fn build_schedule(input: ScheduleInput) -> ScheduleProposal {
+    let ScheduleInput {
+        applicants,
+        interviewers_by_role,
+        available,
+        busy,
+        length,
+        panel_size,
+        earliest_start,
+        period_start,
+        period_end,
+    } = input;
+
+    // Interviews start on a grid of the slot length, so windows never overlap.
+    let anchor = period_start.unwrap_or(0);
+
+    let mut candidates = Vec::new();
+    let mut unscheduled = Vec::new();
+    for (applicant_id, role_id) in applicants {
+        let interviewers = interviewers_by_role
+            .get(&role_id)
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+
+        let mut starts: Vec<i64> = available
+            .get(&applicant_id)
+            .map(|blocks| blocks.iter().copied().collect())
+            .unwrap_or_default();
+        starts.retain(|&start| {
+            (start - anchor).rem_euclid(length) == 0
+                && start >= earliest_start
+                && period_start.is_none_or(|period_start| start >= period_start)
+                && period_end.is_none_or(|period_end| start + length <= period_end)
+                && is_free(applicant_id, start, length, &available, &busy)
+                && free_interviewers(interviewers, applicant_id, start, length, &available, &busy)
+                    .len()
+                    >= panel_size
+        });
+        starts.sort_unstable();
+
+        if starts.is_empty() {
+            unscheduled.push(UnscheduledApplicant {
+                applicant_id,
+                role_id,
+            });
+            continue;
+        }
+
+        candidates.push(Candidate {
+            applicant_id,
+            role_id,
+            starts,
+        });
+    }
+
+    let panels = form_panels(
+        &candidates,
+        &interviewers_by_role,
+        panel_size,
+        length,
+        &available,
+        &busy,
+    );
+
+    // Candidates with the fewest options are matched first.
+    candidates.sort_by_key(|c| (c.starts.len(), c.applicant_id, c.role_id));
+    let mut matching = Matching::new(&candidates, &panels);
+    matching.maximise();
+
+    let mut interviews = Vec::new();
+    for (candidate, panel) in candidates.iter().zip(&matching.panel_of) {
+        let Some(panel) = panel.map(|index| &panels[index]) else {
+            unscheduled.push(UnscheduledApplicant {
+                applicant_id: candidate.applicant_id,
+                role_id: candidate.role_id,
+            });
+            continue;
+        };
+
+        interviews.push(ProposedInterview {
+            start_time: from_block(panel.start),
+            end_time: from_block(panel.start + length),
+            role_id: candidate.role_id,
+            applicant_id: candidate.applicant_id,
+            interviewer_ids: panel.interviewer_ids.clone(),
+        });
+    }
+
+    interviews.sort_by_key(|i| (i.start_time, i.role_id, i.applicant_id));
+
+    ScheduleProposal {
+        interviews,
+        unscheduled,
+    }
+}
+
+/// Groups the interviewers free in each window into panels.
+///
+/// Each window is filled in rounds, giving one panel per round to every role that
+/// still has more candidates who could use the window than panels. Interviewers who
+/// have sat on the fewest panels so far are picked first, to spread the load.
+fn form_panels(
+    candidates: &[Candidate],
+    interviewers_by_role: &HashMap<i64, Vec<i64>>,
+    panel_size: usize,
+    length: i64,
+    available: &HashMap<i64, HashSet<i64>>,
+    busy: &HashMap<i64, HashSet<i64>>,
+) -> Vec<Panel> {
+    let mut demand: BTreeMap<i64, HashMap<i64, usize>> = BTreeMap::new();
+    for candidate in candidates {
+        for start in &candidate.starts {
+            *demand
+                .entry(*start)
+                .or_default()
+                .entry(candidate.role_id)
+                .or_default() += 1;
+        }
+    }
+
+    let mut panels = Vec::new();
+    let mut load: HashMap<i64, usize> = HashMap::new();
+    for (start, mut demand) in demand {
+        let mut seated: HashSet<i64> = HashSet::new();
+        loop {
+            let mut roles: Vec<(i64, usize)> = demand
+                .iter()
+                .filter(|(_, count)| **count > 0)
+                .map(|(role_id, count)| (*role_id, *count))
+                .collect();
+            roles.sort_by_key(|(role_id, count)| (std::cmp::Reverse(*count), *role_id));
+
+            let mut formed = false;
+            for (role_id, _) in roles {
+                let mut free: Vec<i64> = interviewers_by_role
+                    .get(&role_id)
+                    .map(Vec::as_slice)
+                    .unwrap_or(&[])
+                    .iter()
+                    .copied()
+                    .filter(|id| {
+                        !seated.contains(id) && is_free(*id, start, length, available, busy)
+                    })
+                    .collect();
+                if free.len() < panel_size {
+                    continue;
+                }
+
+                free.sort_by_key(|id| (load.get(id).copied().unwrap_or(0), *id));
+                free.truncate(panel_size);
+                for id in &free {
+                    seated.insert(*id);
+                    *load.entry(*id).or_default() += 1;
+                }
+                if let Some(count) = demand.get_mut(&role_id) {
+                    *count -= 1;
+                }
+
+                panels.push(Panel {
+                    start,
+                    role_id,
+                    interviewer_ids: free,
+                });
+                formed = true;
+            }
+
+            if !formed {
+                break;
+            }
+        }
+    }
+
+    panels
+}
+
+impl<'a> Matching<'a> {
+    /// Creates an empty matching, with an edge from each candidate to every panel for
+    /// its role in a window it can make.
+    fn new(candidates: &'a [Candidate], panels: &'a [Panel]) -> Matching<'a> {
+        let edges = candidates
+            .iter()
+            .map(|candidate| {
+                (0..panels.len())
+                    .filter(|&index| {
+                        let panel = &panels[index];
+                        panel.role_id == candidate.role_id
+                            && candidate.starts.binary_search(&panel.start).is_ok()
+                            && !panel.interviewer_ids.contains(&candidate.applicant_id)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Matching {
+            candidates,
+            panels,
+            edges,
+            panel_of: vec![None; candidates.len()],
+            candidate_of: vec![None; panels.len()],
+        }
+    }
+
+    /// Grows the matching until no augmenting path is left.
+    ///
+    /// An augmenting path can move an applicant's other interview into the window it
+    /// just left, so any clash is undone and retried until the matching settles.
+    fn maximise(&mut self) {
+        let mut pending: Vec<usize> = (0..self.candidates.len()).collect();
+        while !pending.is_empty() {
+            for candidate in pending {
+                if self.panel_of[candidate].is_none() {
+                    let mut visited = vec![false; self.panels.len()];
+                    self.augment(candidate, &mut visited);
+                }
+            }
+
+            pending = self.unmatch_clashes();
+        }
+    }
+
+    /// Looks for an augmenting path from an unmatched candidate, applying it if found.
+    fn augment(&mut self, candidate: usize, visited: &mut [bool]) -> bool {
+        for edge in 0..self.edges[candidate].len() {
+            let panel = self.edges[candidate][edge];
+            if visited[panel] || self.clashes(candidate, panel) {
+                continue;
+            }
+            visited[panel] = true;
+
+            let free = match self.candidate_of[panel] {
+                None => true,
+                Some(holder) => self.augment(holder, visited),
+            };
+            if free {
+                self.panel_of[candidate] = Some(panel);
+                self.candidate_of[panel] = Some(candidate);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Whether the candidate's applicant already has another interview in the panel's window.
+    fn clashes(&self, candidate: usize, panel: usize) -> bool {
+        let applicant_id = self.candidates[candidate].applicant_id;
+        let start = self.panels[panel].start;
+
+        self.candidates.iter().zip(&self.panel_of).enumerate().any(
+            |(other, (other_candidate, other_panel))| {
+                other != candidate
+                    && other_candidate.applicant_id == applicant_id
+                    && other_panel
+                        .is_some_and(|other_panel| self.panels[other_panel].start == start)
+            },
+        )
+    }
+
+    /// Unmatches every candidate that shares a window with another interview of the
+    /// same applicant, returning them so they can be matched again.
+    fn unmatch_clashes(&mut self) -> Vec<usize> {
+        let mut windows: HashSet<(i64, i64)> = HashSet::new();
+        let mut unmatched = Vec::new();
+        for candidate in 0..self.candidates.len() {
+            let Some(panel) = self.panel_of[candidate] else {
+                continue;
+            };
+
+            let window = (
+                self.candidates[candidate].applicant_id,
+                self.panels[panel].start,
+            );
+            if !windows.insert(window) {
+                self.panel_of[candidate] = None;
+                self.candidate_of[panel] = None;
+                // The clash came from a path that moved another interview here, so
+                // give up on this panel rather than looping on it.
+                self.edges[candidate].retain(|&edge| edge != panel);
+                unmatched.push(candidate);
+            }
+        }
+
+        unmatched
+    }
+}
+
+/// Converts a time to the index of the availability slot containing it.
+fn to_block(time: DateTime<Utc>) -> i64 {
+    time.timestamp().div_euclid(SLOT_SECONDS)
+}
+
+/// Converts a time to the index of the first availability slot starting at or after it.
+fn to_block_ceil(time: DateTime<Utc>) -> i64 {
+    (time.timestamp() + SLOT_SECONDS - 1).div_euclid(SLOT_SECONDS)
+}
+
+/// Converts an availability slot index back to its start time.
+fn from_block(block: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(block * SLOT_SECONDS, 0)
+        .expect("availability slot index should always be a valid timestamp")
+}
+
+/// Whether a user is available and not already interviewing for `length` slots from `start`.
+fn is_free(
+    user_id: i64,
+    start: i64,
+    length: i64,
+    available: &HashMap<i64, HashSet<i64>>,
+    busy: &HashMap<i64, HashSet<i64>>,
+) -> bool {
+    let Some(available) = available.get(&user_id) else {
+        return false;
+    };
+    let busy = busy.get(&user_id);
+
+    (start..start + length)
+        .all(|block| available.contains(&block) && !busy.is_some_and(|b| b.contains(&block)))
+}
+
+/// The interviewers that could sit on a panel for `length` slots from `start`.
+fn free_interviewers(
+    interviewers: &[i64],
+    applicant_id: i64,
+    start: i64,
+    length: i64,
+    available: &HashMap<i64, HashSet<i64>>,
+    busy: &HashMap<i64, HashSet<i64>>,
+) -> Vec<i64> {
+    interviewers
+        .iter()
+        .copied()
+        .filter(|&id| id != applicant_id && is_free(id, start, length, available, busy))
+        .collect()
+}
+
+/// Ensures a user has no other interview in the campaign overlapping the given time.
+async fn assert_user_is_free(
+    user_id: i64,
+    campaign_id: i64,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    transaction: &mut Transaction<'_, Postgres>,
+) -> Result<(), ChaosError> {
+    let overlaps = sqlx::query!(
+        "
+            SELECT EXISTS(
+                SELECT 1 FROM interview_timeslot_users tu
+                JOIN interview_timeslots t ON t.id = tu.interview_timeslot_id
+                WHERE t.campaign_id = $1 AND tu.user_id = $2
+                AND t.start_time < $4 AND t.end_time > $3
+            )
+        ",
+        campaign_id,
+        user_id,
+        start_time,
+        end_time
+    )
+    .fetch_one(transaction.deref_mut())
+    .await?
+    .exists
+    .expect("`exists` should always exist in this query result");
+
+    if overlaps {
+        return Err(ChaosError::BadRequestWithMessage(
+            "A participant already has an interview at this time".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An arbitrary slot index, far enough from zero to be a realistic time.
+    const BASE: i64 = 2_000_000;
+
+    fn input(applicants: &[(i64, i64)], interviewers: &[(i64, &[i64])]) -> ScheduleInput {
+        ScheduleInput {
+            applicants: applicants.to_vec(),
+            interviewers_by_role: interviewers
+                .iter()
+                .map(|(role_id, ids)| (*role_id, ids.to_vec()))
+                .collect(),
+            available: HashMap::new(),
+            busy: HashMap::new(),
+            length: 1,
+            panel_size: 1,
+            earliest_start: 0,
+            period_start: None,
+            period_end: None,
+        }
+    }
+
+    fn make_available(input: &mut ScheduleInput, user_id: i64, blocks: &[i64]) {
+        input
+            .available
+            .entry(user_id)
+            .or_default()
+            .extend(blocks.iter().map(|block| BASE + block));
+    }
+
+    fn starts(proposal: &ScheduleProposal) -> Vec<DateTime<Utc>> {
+        proposal.interviews.iter().map(|i| i.start_time).collect()
+    }
+
+    #[test]
+    fn reports_applicants_without_a_panel_as_unscheduled() {
+        let mut input = input(&[(1, 10), (2, 10)], &[(10, &[100])]);
+        make_available(&mut input, 1, &[0]);
+        make_available(&mut input, 2, &[0]);
+        make_available(&mut input, 100, &[0]);
+
+        let proposal = build_schedule(input);
+
+        assert_eq!(proposal.interviews.len(), 1);
+        assert_eq!(proposal.unscheduled.len(), 1);
+        let scheduled = proposal.interviews[0].applicant_id;
+        let unscheduled = &proposal.unscheduled[0];
+        assert_ne!(scheduled, unscheduled.applicant_id);
+        assert_eq!(unscheduled.role_id, 10);
+    }
+
+    #[test]
+    fn reports_applicants_with_no_shared_availability_as_unscheduled() {
+        let mut input = input(&[(1, 10)], &[(10, &[100])]);
+        make_available(&mut input, 1, &[0]);
+        make_available(&mut input, 100, &[1]);
+
+        let proposal = build_schedule(input);
+
+        assert!(proposal.interviews.is_empty());
+        assert_eq!(proposal.unscheduled.len(), 1);
+        assert_eq!(proposal.unscheduled[0].applicant_id, 1);
+    }
+
+    #[test]
+    fn moves_matched_applicants_to_schedule_everyone() {
+        // Matching applicant 1 into the first window would leave applicant 2 out.
+        let candidates = [
+            Candidate {
+                applicant_id: 1,
+                role_id: 10,
+                starts: vec![BASE, BASE + 1],
+            },
+            Candidate {
+                applicant_id: 2,
+                role_id: 10,
+                starts: vec![BASE],
+            },
+        ];
+        let panels = [
+            Panel {
+                start: BASE,
+                role_id: 10,
+                interviewer_ids: vec![100],
+            },
+            Panel {
+                start: BASE + 1,
+                role_id: 10,
+                interviewer_ids: vec![100],
+            },
+        ];
+
+        let mut matching = Matching::new(&candidates, &panels);
+        matching.maximise();
+
+        assert_eq!(matching.panel_of, vec![Some(1), Some(0)]);
+    }
+
+    #[test]
+    fn only_schedules_full_panels_of_free_interviewers() {
+        let mut input = input(&[(1, 10), (2, 10)], &[(10, &[100, 101, 102])]);
+        input.panel_size = 2;
+        make_available(&mut input, 1, &[0]);
+        make_available(&mut input, 2, &[0]);
+        make_available(&mut input, 100, &[0]);
+        make_available(&mut input, 101, &[0]);
+        make_available(&mut input, 102, &[0]);
+        input.busy.entry(102).or_default().insert(BASE);
+
+        let proposal = build_schedule(input);
+
+        // Only two interviewers are free, which is one panel.
+        assert_eq!(proposal.interviews.len(), 1);
+        assert_eq!(proposal.unscheduled.len(), 1);
+        let mut panel = proposal.interviews[0].interviewer_ids.clone();
+        panel.sort_unstable();
+        assert_eq!(panel, vec![100, 101]);
+    }
+
+    #[test]
+    fn never_gives_an_applicant_two_interviews_in_one_window() {
+        let mut input = input(&[(1, 10), (1, 11)], &[(10, &[100]), (11, &[101])]);
+        make_available(&mut input, 1, &[0, 1]);
+        make_available(&mut input, 100, &[0]);
+        make_available(&mut input, 101, &[0, 1]);
+
+        let proposal = build_schedule(input);
+
+        assert!(proposal.unscheduled.is_empty());
+        let mut times = starts(&proposal);
+        times.sort_unstable();
+        assert_eq!(times, vec![from_block(BASE), from_block(BASE + 1)]);
+    }
+
+    #[test]
+    fn keeps_interviews_within_the_interview_period() {
+        let mut input = input(&[(1, 10), (2, 10), (3, 10)], &[(10, &[100])]);
+        input.length = 2;
+        input.period_start = Some(BASE + 2);
+        input.period_end = Some(BASE + 5);
+        // Applicant 1 is only free before the period, applicant 3 only across its end.
+        make_available(&mut input, 1, &[0, 1]);
+        make_available(&mut input, 2, &[2, 3]);
+        make_available(&mut input, 3, &[4, 5]);
+        make_available(&mut input, 100, &[0, 1, 2, 3, 4, 5]);
+
+        let proposal = build_schedule(input);
+
+        assert_eq!(starts(&proposal), vec![from_block(BASE + 2)]);
+        assert_eq!(proposal.interviews[0].end_time, from_block(BASE + 4));
+        let mut unscheduled: Vec<i64> = proposal
+            .unscheduled
+            .iter()
+            .map(|u| u.applicant_id)
+            .collect();
+        unscheduled.sort_unstable();
+        assert_eq!(unscheduled, vec![1, 3]);
+    }
+}
@@ -7,21 +7,40 @@
 //! The models are designed to be used with the application's database layer and API endpoints,
 //! providing a consistent interface for data manipulation and validation.
 
+pub mod allocation;
 pub mod answer;
 pub mod app;
+pub mod applicant_status;
 pub mod application;
+pub mod application_event;
+pub mod application_listing;
 pub mod auth;
+pub mod availabilities;
+pub mod blind_review;
+pub mod bulk_offer;
+pub mod calendar;
 pub mod campaign;
 pub mod comment_last_read;
 pub mod comment;
 pub mod email;
+pub mod email_transport;
+pub mod email_worker;
 pub mod email_template;
 pub mod error;
+pub mod interview;
+pub mod interview_schedule;
 pub mod invite;
 pub mod offer;
+pub mod offer_acceptance;
+pub mod offer_reminder;
 pub mod organisation;
+pub mod outcome_release;
+pub mod pipeline;
 pub mod question;
 pub mod rating;
+pub mod rating_analytics;
+pub mod review_conflict;
+pub mod reviewer_assignment;
 pub mod role;
 pub mod role_status;
 pub mod seeder;
@@ -29,3 +48,4 @@ pub mod serde_string;
 pub mod storage;
 pub mod transaction;
 pub mod user;
+pub mod waitlist;
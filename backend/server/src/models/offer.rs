@@ -3,11 +3,16 @@
 //! This module provides functionality for managing job offers in recruitment campaigns,
 //! including creation, updates, and email notifications.
 
-use crate::models::email::{EmailCredentials, EmailParts, EmailQueue};
+use crate::models::application_event::{ApplicationEvent, ApplicationEventType};
+use crate::models::email::{EmailContext, EmailParts, EmailQueue};
 use crate::models::email_template::EmailTemplate;
 use crate::models::error::ChaosError;
+use crate::models::offer_acceptance::OfferAcceptance;
+use crate::models::offer_reminder::OfferReminder;
+use crate::models::waitlist::{CascadeReason, Waitlist};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use snowflake::SnowflakeIdGenerator;
 use sqlx::{Postgres, Transaction};
 use std::ops::DerefMut;
@@ -98,6 +103,10 @@ pub enum OfferStatus {
     Accepted,
     /// Offer has been declined by the applicant
     Declined,
+    /// Offer was not replied to before its expiry
+    Expired,
+    /// Offer was withdrawn before the applicant could reply
+    Withdrawn,
 }
 
 /// Response to an offer.
@@ -151,6 +160,16 @@ impl Offer {
             .execute(transaction.deref_mut())
             .await?;
 
+        ApplicationEvent::record(
+            application_id,
+            ApplicationEventType::OfferCreated,
+            None,
+            Some(role_id),
+            json!({ "offer_id": id.to_string(), "expiry": expiry }),
+            transaction,
+        )
+        .await?;
+
         Ok(id)
     }
 
@@ -263,9 +282,14 @@ impl Offer {
 
     /// Processes a response to an offer.
     ///
+    /// Accepting an offer finalises the applicant's role statuses and may add them to
+    /// the organisation. Declining an offer passes the role on to the next applicant on
+    /// its waitlist.
+    ///
     /// # Arguments
     /// * `id` - The ID of the offer to respond to
     /// * `accept` - Whether the offer is being accepted
+    /// * `snowflake_generator` - Generator for the ID of any waitlist offer
     /// * `transaction` - A mutable reference to the database transaction
     ///
     /// # Returns
@@ -274,10 +298,12 @@ impl Offer {
     /// * `Err(ChaosError)` - An error if processing fails
     ///
     /// # Note
-    /// This will fail if the offer has expired.
+    /// This will fail unless the offer has been sent and has not expired or been
+    /// replied to.
     pub async fn reply(
         id: i64,
         accept: bool,
+        snowflake_generator: &mut SnowflakeIdGenerator,
         transaction: &mut Transaction<'_, Postgres>,
     ) -> Result<(), ChaosError> {
         let offer = Offer::get(id, transaction).await?;
@@ -286,9 +312,42 @@ impl Offer {
             return Err(ChaosError::BadRequest);
         }
 
+        match offer.status {
+            OfferStatus::Sent => {}
+            OfferStatus::Accepted | OfferStatus::Declined => {
+                return Err(ChaosError::BadRequestWithMessage(
+                    "Offer has already been replied to".to_string(),
+                ))
+            }
+            _ => {
+                return Err(ChaosError::BadRequestWithMessage(
+                    "Offer cannot be replied to".to_string(),
+                ))
+            }
+        }
+
+        let held = sqlx::query!(
+            r#"
+                SELECT COALESCE(outcomes_released_at > NOW(), false) AS "held!"
+                FROM campaigns WHERE id = $1
+            "#,
+            offer.campaign_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .held;
+
+        if held {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Offer has not been released yet".to_string(),
+            ));
+        }
+
         let mut status = OfferStatus::Accepted;
+        let mut event_type = ApplicationEventType::OfferAccepted;
         if !accept {
             status = OfferStatus::Declined;
+            event_type = ApplicationEventType::OfferDeclined;
         }
 
         sqlx::query!(
@@ -299,9 +358,111 @@ impl Offer {
         .execute(transaction.deref_mut())
         .await?;
 
+        ApplicationEvent::record(
+            offer.application_id,
+            event_type,
+            Some(offer.user_id),
+            Some(offer.role_id),
+            json!({ "offer_id": id.to_string() }),
+            transaction,
+        )
+        .await?;
+
+        OfferReminder::cancel_pending(id, transaction).await?;
+
+        if accept {
+            OfferAcceptance::apply(&offer, snowflake_generator, transaction).await?;
+        } else {
+            Waitlist::cascade(
+                id,
+                CascadeReason::Declined,
+                snowflake_generator,
+                transaction,
+            )
+            .await?;
+        }
+
         Ok(())
     }
 
+    /// Retrieves the IDs of every sent offer past its expiry.
+    ///
+    /// # Arguments
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<i64>)` - The IDs of the overdue offers
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get_overdue(
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<i64>, ChaosError> {
+        let overdue =
+            sqlx::query!("SELECT id FROM offers WHERE status = 'Sent' AND expiry < NOW()")
+                .fetch_all(transaction.deref_mut())
+                .await?
+                .into_iter()
+                .map(|offer| offer.id)
+                .collect();
+
+        Ok(overdue)
+    }
+
+    /// Moves a sent offer past its expiry to `Expired`, and passes its role on to the
+    /// next applicant on the role's waitlist.
+    ///
+    /// Each overdue offer should be expired in its own transaction, so one failing
+    /// cascade does not hold back the rest.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the offer to expire
+    /// * `snowflake_generator` - Generator for the ID of any waitlist offer
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(bool)` - Whether the offer expired, `false` if it was replied to first
+    /// * `Err(ChaosError)` - An error if any update fails
+    pub async fn expire(
+        id: i64,
+        snowflake_generator: &mut SnowflakeIdGenerator,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<bool, ChaosError> {
+        let Some(offer) = sqlx::query!(
+            "
+                UPDATE offers SET status = 'Expired'
+                WHERE id = $1 AND status = 'Sent' AND expiry < NOW()
+                RETURNING id, application_id, role_id
+            ",
+            id
+        )
+        .fetch_optional(transaction.deref_mut())
+        .await?
+        else {
+            return Ok(false);
+        };
+
+        ApplicationEvent::record(
+            offer.application_id,
+            ApplicationEventType::OfferExpired,
+            None,
+            Some(offer.role_id),
+            json!({ "offer_id": offer.id.to_string() }),
+            transaction,
+        )
+        .await?;
+        OfferReminder::cancel_pending(offer.id, transaction).await?;
+        Waitlist::cascade(
+            offer.id,
+            CascadeReason::Expired,
+            snowflake_generator,
+            transaction,
+        )
+        .await?;
+
+        Ok(true)
+    }
+
     /// Generates a preview of the offer email.
     ///
     /// # Arguments
@@ -331,12 +492,11 @@ impl Offer {
         Ok(email_parts)
     }
 
-    /// Sends an offer email to the applicant.
+    /// Sends an offer email to the applicant, marking a draft offer as sent.
     ///
     /// # Arguments
     /// * `id` - The ID of the offer to send
     /// * `transaction` - A mutable reference to the database transaction
-    /// * `email_credentials` - The credentials to use for sending email
     ///
     /// # Returns
     /// Returns a `Result` containing either:
@@ -345,9 +505,20 @@ impl Offer {
     pub async fn send_offer(
         id: i64,
         transaction: &mut Transaction<'_, Postgres>,
-        _email_credentials: EmailCredentials,
     ) -> Result<(), ChaosError> {
         let offer = Offer::get(id, transaction).await?;
+        if !matches!(offer.status, OfferStatus::Draft | OfferStatus::Sent) {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Offer can no longer be sent".to_string(),
+            ));
+        }
+
+        let context = EmailContext {
+            campaign_id: Some(offer.campaign_id),
+            application_id: Some(offer.application_id),
+            offer_id: Some(offer.id),
+            hold_for_release: true,
+        };
         let email_parts = EmailTemplate::generate_email(
             offer.user_name.clone(),
             offer.role_name,
@@ -364,9 +535,28 @@ impl Offer {
             offer.user_email,
             email_parts.subject,
             email_parts.body,
+            context,
             transaction,
         )
         .await?;
+
+        sqlx::query!(
+            "UPDATE offers SET status = 'Sent' WHERE id = $1 AND status = 'Draft'",
+            id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        ApplicationEvent::record(
+            offer.application_id,
+            ApplicationEventType::OfferSent,
+            None,
+            Some(offer.role_id),
+            json!({ "offer_id": id.to_string() }),
+            transaction,
+        )
+        .await?;
+
         Ok(())
     }
 }
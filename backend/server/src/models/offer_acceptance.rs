@@ -0,0 +1,313 @@
+//! Side effects of accepting an offer.
+//!
+//! When an applicant accepts an offer, the offered role is marked as successful,
+//! their other roles in the campaign are closed out as rejected, and the application
+//! itself is marked as successful. Any other offers still awaiting the applicant's
+//! reply are declined on their behalf, and their roles passed on to the next
+//! applicant on each waitlist. Draft offers that were never sent are withdrawn,
+//! since their roles have just been rejected. Campaigns can also choose to add
+//! accepted applicants to the organisation as members. The organisation's admins
+//! are notified of every acceptance.
+
+use crate::models::application::ApplicationStatus;
+use crate::models::application_event::{ApplicationEvent, ApplicationEventType};
+use crate::models::email::{EmailContext, EmailQueue};
+use crate::models::error::ChaosError;
+use crate::models::offer::OfferDetails;
+use crate::models::offer_reminder::OfferReminder;
+use crate::models::waitlist::{CascadeReason, Waitlist};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use snowflake::SnowflakeIdGenerator;
+use sqlx::{Postgres, Transaction};
+use std::ops::DerefMut;
+
+/// A campaign's settings for accepted offers.
+#[derive(Deserialize, Serialize)]
+pub struct OfferAcceptanceSettings {
+    /// Whether applicants who accept an offer are added to the organisation
+    pub onboard_accepted_applicants: bool,
+}
+
+pub struct OfferAcceptance;
+
+impl OfferAcceptance {
+    /// Retrieves a campaign's settings for accepted offers.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(OfferAcceptanceSettings)` - The campaign's settings
+    /// * `Err(ChaosError)` - An error if the campaign does not exist or retrieval fails
+    pub async fn get_settings(
+        campaign_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<OfferAcceptanceSettings, ChaosError> {
+        let settings = sqlx::query_as!(
+            OfferAcceptanceSettings,
+            "SELECT onboard_accepted_applicants FROM campaigns WHERE id = $1",
+            campaign_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        Ok(settings)
+    }
+
+    /// Updates a campaign's settings for accepted offers.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `settings` - The new settings
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the settings were updated
+    /// * `Err(ChaosError)` - An error if the campaign does not exist or the update fails
+    pub async fn update_settings(
+        campaign_id: i64,
+        settings: OfferAcceptanceSettings,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        sqlx::query!(
+            "UPDATE campaigns SET onboard_accepted_applicants = $2 WHERE id = $1 RETURNING id",
+            campaign_id,
+            settings.onboard_accepted_applicants
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Applies the side effects of an applicant accepting an offer.
+    ///
+    /// Every status change is recorded in the application's event log, with the
+    /// applicant as the actor.
+    ///
+    /// # Arguments
+    /// * `offer` - The offer that was accepted
+    /// * `snowflake_generator` - Generator for the IDs of any waitlist offers
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If every side effect was applied
+    /// * `Err(ChaosError)` - An error if any update fails
+    pub async fn apply(
+        offer: &OfferDetails,
+        snowflake_generator: &mut SnowflakeIdGenerator,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let role_changes = sqlx::query!(
+            r#"
+                WITH old AS (
+                    SELECT campaign_role_id, role_status FROM application_roles
+                    WHERE application_id = $1
+                    FOR UPDATE
+                ), new AS (
+                    SELECT campaign_role_id, CASE WHEN campaign_role_id = $2
+                        THEN 'Successful'::application_status
+                        ELSE 'Rejected'::application_status
+                    END AS role_status
+                    FROM old
+                )
+                UPDATE application_roles
+                SET role_status = new.role_status
+                FROM old
+                JOIN new ON new.campaign_role_id = old.campaign_role_id
+                WHERE application_roles.application_id = $1
+                AND application_roles.campaign_role_id = old.campaign_role_id
+                AND old.role_status <> new.role_status
+                RETURNING application_roles.campaign_role_id,
+                    old.role_status AS "old_status: ApplicationStatus",
+                    application_roles.role_status AS "new_status: ApplicationStatus"
+            "#,
+            offer.application_id,
+            offer.role_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        for change in role_changes {
+            ApplicationEvent::record(
+                offer.application_id,
+                ApplicationEventType::RoleStatusChanged,
+                Some(offer.user_id),
+                Some(change.campaign_role_id),
+                json!({ "from": change.old_status, "to": change.new_status }),
+                transaction,
+            )
+            .await?;
+        }
+
+        let application = sqlx::query!(
+            r#"
+                WITH old AS (
+                    SELECT status, private_status FROM applications WHERE id = $1 FOR UPDATE
+                )
+                UPDATE applications
+                SET status = 'Successful', private_status = 'Successful', updated_at = NOW()
+                FROM old
+                WHERE applications.id = $1
+                RETURNING old.status AS "old_status: ApplicationStatus",
+                    old.private_status AS "old_private_status: ApplicationStatus"
+            "#,
+            offer.application_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        if !matches!(application.old_status, ApplicationStatus::Successful) {
+            ApplicationEvent::record(
+                offer.application_id,
+                ApplicationEventType::StatusChanged,
+                Some(offer.user_id),
+                None,
+                json!({ "from": application.old_status, "to": ApplicationStatus::Successful }),
+                transaction,
+            )
+            .await?;
+        }
+
+        if !matches!(
+            application.old_private_status,
+            ApplicationStatus::Successful
+        ) {
+            ApplicationEvent::record(
+                offer.application_id,
+                ApplicationEventType::PrivateStatusChanged,
+                Some(offer.user_id),
+                None,
+                json!({
+                    "from": application.old_private_status,
+                    "to": ApplicationStatus::Successful
+                }),
+                transaction,
+            )
+            .await?;
+        }
+
+        let declined = sqlx::query!(
+            "
+                UPDATE offers SET status = 'Declined'
+                WHERE application_id = $1 AND id <> $2 AND status = 'Sent'
+                RETURNING id, role_id
+            ",
+            offer.application_id,
+            offer.id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        for other in declined {
+            ApplicationEvent::record(
+                offer.application_id,
+                ApplicationEventType::OfferDeclined,
+                Some(offer.user_id),
+                Some(other.role_id),
+                json!({ "offer_id": other.id.to_string() }),
+                transaction,
+            )
+            .await?;
+            OfferReminder::cancel_pending(other.id, transaction).await?;
+            Waitlist::cascade(
+                other.id,
+                CascadeReason::Declined,
+                snowflake_generator,
+                transaction,
+            )
+            .await?;
+        }
+
+        let withdrawn = sqlx::query!(
+            "
+                UPDATE offers SET status = 'Withdrawn'
+                WHERE application_id = $1 AND id <> $2 AND status = 'Draft'
+                RETURNING id, role_id
+            ",
+            offer.application_id,
+            offer.id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        for other in withdrawn {
+            ApplicationEvent::record(
+                offer.application_id,
+                ApplicationEventType::OfferWithdrawn,
+                Some(offer.user_id),
+                Some(other.role_id),
+                json!({ "offer_id": other.id.to_string() }),
+                transaction,
+            )
+            .await?;
+        }
+
+        let onboarded = sqlx::query!(
+            "
+                INSERT INTO organisation_members (organisation_id, user_id, role)
+                SELECT c.organisation_id, $2, 'User'
+                FROM campaigns c
+                WHERE c.id = $1 AND c.onboard_accepted_applicants = true
+                ON CONFLICT (organisation_id, user_id) DO NOTHING
+            ",
+            offer.campaign_id,
+            offer.user_id
+        )
+        .execute(transaction.deref_mut())
+        .await?
+        .rows_affected()
+            > 0;
+
+        let mut body = format!(
+            "{} has accepted their offer for the {} role in {}.\n",
+            offer.user_name, offer.role_name, offer.campaign_name
+        );
+        if onboarded {
+            body.push_str(&format!(
+                "\nThey have been added to {} as a member.\n",
+                offer.organisation_name
+            ));
+        }
+
+        let admins = sqlx::query!(
+            "
+                SELECT u.name, u.email
+                FROM organisation_members om
+                JOIN users u ON u.id = om.user_id
+                JOIN campaigns c ON c.organisation_id = om.organisation_id
+                WHERE c.id = $1 AND om.role = 'Admin'
+            ",
+            offer.campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        for admin in admins {
+            EmailQueue::add_to_queue(
+                Some(admin.name),
+                admin.email,
+                format!(
+                    "Offer accepted - {} {}",
+                    offer.campaign_name, offer.role_name
+                ),
+                body.clone(),
+                EmailContext {
+                    campaign_id: Some(offer.campaign_id),
+                    application_id: None,
+                    offer_id: Some(offer.id),
+                    hold_for_release: false,
+                },
+                transaction,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,286 @@
+//! Offer expiry reminders for Chaos.
+//!
+//! Each campaign can configure reminders to be sent a number of hours before its
+//! offers expire. Reminders are queued by a background job for offers that are still
+//! awaiting a reply, and any reminder that has not gone out yet is cancelled as soon
+//! as the offer is accepted, declined or expires.
+
+use crate::models::email::{EmailContext, EmailParts, EmailQueue};
+use crate::models::email_template::EmailTemplate;
+use crate::models::error::ChaosError;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, Transaction};
+use std::collections::HashSet;
+use std::ops::DerefMut;
+
+/// Longest time before expiry a reminder can be sent.
+const MAX_REMINDER_HOURS: i32 = 24 * 30;
+
+/// A campaign's offer reminder schedule.
+#[derive(Deserialize, Serialize)]
+pub struct OfferReminderSettings {
+    /// How many hours before expiry each reminder is sent
+    pub hours_before: Vec<i32>,
+    /// Template used for reminders, or `None` for the built-in reminder
+    #[serde(
+        default,
+        serialize_with = "crate::models::serde_string::serialize_option",
+        deserialize_with = "crate::models::serde_string::deserialize_option"
+    )]
+    pub email_template_id: Option<i64>,
+}
+
+pub struct OfferReminder;
+
+impl OfferReminder {
+    /// Retrieves a campaign's offer reminder schedule.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(OfferReminderSettings)` - The reminder schedule, soonest to expiry last
+    /// * `Err(ChaosError)` - An error if the campaign does not exist or retrieval fails
+    pub async fn get_settings(
+        campaign_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<OfferReminderSettings, ChaosError> {
+        let settings = sqlx::query_as!(
+            OfferReminderSettings,
+            "
+                SELECT offer_reminder_hours AS hours_before,
+                    offer_reminder_template_id AS email_template_id
+                FROM campaigns WHERE id = $1
+            ",
+            campaign_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        Ok(settings)
+    }
+
+    /// Replaces a campaign's offer reminder schedule.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `settings` - The new reminder schedule
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the schedule was updated
+    /// * `Err(ChaosError)` - An error if the schedule or template is invalid
+    pub async fn update_settings(
+        campaign_id: i64,
+        settings: OfferReminderSettings,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        if settings
+            .hours_before
+            .iter()
+            .any(|hours| *hours <= 0 || *hours > MAX_REMINDER_HOURS)
+        {
+            return Err(ChaosError::BadRequestWithMessage(format!(
+                "Reminders must be between 1 and {MAX_REMINDER_HOURS} hours before expiry"
+            )));
+        }
+
+        let mut hours_before: Vec<i32> = settings
+            .hours_before
+            .into_iter()
+            .collect::<HashSet<i32>>()
+            .into_iter()
+            .collect();
+        hours_before.sort_unstable_by(|a, b| b.cmp(a));
+
+        if let Some(email_template_id) = settings.email_template_id {
+            let template_exists = sqlx::query!(
+                "
+                    SELECT EXISTS(
+                        SELECT 1 FROM email_templates t
+                        JOIN campaigns c ON c.organisation_id = t.organisation_id
+                        WHERE t.id = $1 AND c.id = $2
+                    )
+                ",
+                email_template_id,
+                campaign_id
+            )
+            .fetch_one(transaction.deref_mut())
+            .await?
+            .exists
+            .expect("`exists` should always exist in this query result");
+
+            if !template_exists {
+                return Err(ChaosError::BadRequestWithMessage(
+                    "Email template does not belong to this campaign's organisation".to_string(),
+                ));
+            }
+        }
+
+        sqlx::query!(
+            "
+                UPDATE campaigns
+                SET offer_reminder_hours = $2, offer_reminder_template_id = $3
+                WHERE id = $1
+            ",
+            campaign_id,
+            &hours_before,
+            settings.email_template_id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Queues every reminder that has fallen due for offers still awaiting a reply.
+    ///
+    /// If several of an offer's reminders are due at once, for example because the
+    /// offer was sent close to its expiry, only the latest one is sent.
+    ///
+    /// # Arguments
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(usize)` - The number of reminders queued
+    /// * `Err(ChaosError)` - An error if queueing fails
+    pub async fn queue_due(
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<usize, ChaosError> {
+        let due = sqlx::query!(
+            r#"
+                SELECT off.id, off.campaign_id, off.application_id, off.expiry,
+                    c.offer_reminder_template_id,
+                    ARRAY_AGG(h.hours ORDER BY h.hours) AS "hours!: Vec<i32>",
+                    c.name AS campaign_name, o.name AS organisation_name,
+                    r.name AS role_name, u.name AS user_name, u.email AS user_email
+                FROM offers off
+                JOIN campaigns c ON c.id = off.campaign_id
+                JOIN organisations o ON o.id = c.organisation_id
+                JOIN campaign_roles r ON r.id = off.role_id
+                JOIN applications a ON a.id = off.application_id
+                JOIN users u ON u.id = a.user_id
+                CROSS JOIN LATERAL UNNEST(c.offer_reminder_hours) AS h(hours)
+                WHERE off.status = 'Sent' AND off.expiry > NOW()
+                AND NOW() >= off.expiry - make_interval(hours => h.hours)
+                AND NOT EXISTS (
+                    SELECT 1 FROM offer_reminders rem
+                    WHERE rem.offer_id = off.id AND rem.hours_before = h.hours
+                )
+                GROUP BY off.id, c.id, o.name, r.name, u.name, u.email
+            "#
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let count = due.len();
+        for offer in due {
+            let email_parts = match offer.offer_reminder_template_id {
+                Some(email_template_id) => {
+                    EmailTemplate::generate_email(
+                        offer.user_name.clone(),
+                        offer.role_name,
+                        offer.organisation_name,
+                        offer.campaign_name,
+                        offer.expiry,
+                        email_template_id,
+                        transaction,
+                    )
+                    .await?
+                }
+                None => {
+                    let hours_left = (offer.expiry - Utc::now()).num_hours();
+                    let time_left = if hours_left >= 48 {
+                        format!("{} days", hours_left / 24)
+                    } else {
+                        format!("{} hours", hours_left.max(1))
+                    };
+
+                    EmailParts {
+                        subject: format!(
+                            "Reminder: your offer for {} expires in {time_left}",
+                            offer.role_name
+                        ),
+                        body: format!(
+                            "Hi {},\n\nThis is a reminder that your offer for the {} role in {} {} expires on {} UTC.\n\nPlease accept or decline it on Chaos before then.\n",
+                            offer.user_name,
+                            offer.role_name,
+                            offer.organisation_name,
+                            offer.campaign_name,
+                            offer.expiry.format("%A %-d %B %Y, %H:%M")
+                        ),
+                    }
+                }
+            };
+
+            let sent_email_id = EmailQueue::add_to_queue(
+                Some(offer.user_name),
+                offer.user_email,
+                email_parts.subject,
+                email_parts.body,
+                EmailContext {
+                    campaign_id: Some(offer.campaign_id),
+                    application_id: Some(offer.application_id),
+                    offer_id: Some(offer.id),
+                    // Never overtake the offer email itself if it is still held.
+                    hold_for_release: true,
+                },
+                transaction,
+            )
+            .await?;
+
+            // Record every due reminder so the earlier ones are not sent later.
+            sqlx::query!(
+                "
+                    INSERT INTO offer_reminders (offer_id, hours_before, sent_email_id)
+                    SELECT $1, hours, $3 FROM UNNEST($2::int[]) AS hours
+                ",
+                offer.id,
+                &offer.hours,
+                sent_email_id
+            )
+            .execute(transaction.deref_mut())
+            .await?;
+        }
+
+        Ok(count)
+    }
+
+    /// Cancels any reminders for an offer that are still in the queue.
+    ///
+    /// # Arguments
+    /// * `offer_id` - The ID of the offer
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the pending reminders were cancelled
+    /// * `Err(ChaosError)` - An error if the update fails
+    pub async fn cancel_pending(
+        offer_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        sqlx::query!(
+            "
+                WITH cancelled AS (
+                    DELETE FROM email_queue q
+                    USING offer_reminders rem
+                    WHERE rem.offer_id = $1 AND q.sent_email_id = rem.sent_email_id
+                    RETURNING q.sent_email_id
+                )
+                UPDATE sent_emails SET status = 'Cancelled'
+                WHERE id IN (SELECT sent_email_id FROM cancelled)
+            ",
+            offer_id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+}
@@ -5,8 +5,7 @@
 
 use crate::constants::NANOID_ALPHABET;
 use crate::models::campaign::OrganisationCampaign;
-use crate::models::email::ChaosEmail;
-use crate::models::email::EmailCredentials;
+use crate::models::email::{EmailContext, EmailQueue};
 use crate::models::error::ChaosError;
 use crate::models::storage::Storage;
 use crate::models::user::User;
@@ -758,8 +757,6 @@ impl Organisation {
         organisation_id: i64,
         inviting_user_id: i64,
         email: String,
-        email_credentials: EmailCredentials,
-        is_dev_env: bool,
         snowflake_generator: &mut SnowflakeIdGenerator,
         transaction: &mut Transaction<'_, Postgres>,
     ) -> Result<String, ChaosError> {
@@ -847,18 +844,20 @@ impl Organisation {
         .execute(transaction.deref_mut())
         .await?;
 
-        if is_dev_env {
-            println!("Invite code for {email}: {code}")
-        } else {
-            ChaosEmail::send_message(
-                None,
-                email,
-                "You have been invited to join an organisation on Chaos".to_string(),
-                format!("You have been invited to join an organisation on Chaos. Please use the following link to accept the invite: https://chaos.devsoc.app/dashboard/invite/{code}").to_string(),
-                email_credentials
-            )
-            .await?;
-        }
+        EmailQueue::add_to_queue(
+            None,
+            email,
+            "You have been invited to join an organisation on Chaos".to_string(),
+            format!("You have been invited to join an organisation on Chaos. Please use the following link to accept the invite: https://chaos.devsoc.app/dashboard/invite/{code}").to_string(),
+            EmailContext {
+                campaign_id: None,
+                application_id: None,
+                offer_id: None,
+                hold_for_release: false,
+            },
+            transaction,
+        )
+        .await?;
 
         Ok(code)
     }
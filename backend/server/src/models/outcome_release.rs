@@ -0,0 +1,315 @@
+//! Scheduled release of campaign outcomes.
+//!
+//! Outcome and offer emails are queued with their campaign as the release
+//! campaign, and the email worker holds them while the campaign's
+//! `outcomes_released_at` is in the future. Emails for a campaign with no release
+//! time are sent straight away. Until the release, admins can preview the pending
+//! batch, move the release time, or cancel the batch altogether. Once the release
+//! time passes, each application's private status is promoted to its public status.
+
+use crate::models::application::ApplicationStatus;
+use crate::models::application_event::{ApplicationEvent, ApplicationEventType};
+use crate::models::error::ChaosError;
+use crate::models::offer::OfferStatus;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{Postgres, Transaction};
+use std::ops::DerefMut;
+
+/// An email waiting for its campaign's outcome release.
+#[derive(Serialize)]
+pub struct HeldEmail {
+    pub id: i32,
+    #[serde(serialize_with = "crate::models::serde_string::serialize_option")]
+    pub application_id: Option<i64>,
+    #[serde(serialize_with = "crate::models::serde_string::serialize_option")]
+    pub offer_id: Option<i64>,
+    pub recipient_name: Option<String>,
+    pub recipient_email_address: String,
+    pub subject: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The pending outcome release of a campaign.
+#[derive(Serialize)]
+pub struct OutcomeRelease {
+    /// When the held emails will be released, if a time has been set
+    pub outcomes_released_at: Option<DateTime<Utc>>,
+    /// The emails that will be sent on release
+    pub emails: Vec<HeldEmail>,
+}
+
+/// Data structure for rescheduling an outcome release.
+#[derive(Deserialize)]
+pub struct OutcomeReleaseUpdate {
+    pub outcomes_released_at: DateTime<Utc>,
+}
+
+impl OutcomeRelease {
+    /// Retrieves the release time and the emails still held for a campaign.
+    ///
+    /// Emails that have already been sent are no longer in the queue, so are not included.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(OutcomeRelease)` - The pending release
+    /// * `Err(ChaosError)` - An error if the campaign does not exist or retrieval fails
+    pub async fn get(
+        campaign_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<OutcomeRelease, ChaosError> {
+        let outcomes_released_at = sqlx::query!(
+            "SELECT outcomes_released_at FROM campaigns WHERE id = $1",
+            campaign_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .outcomes_released_at;
+
+        let emails = sqlx::query_as!(
+            HeldEmail,
+            r#"
+                SELECT q.id, s.application_id AS "application_id?", s.offer_id AS "offer_id?",
+                    q.recepient_name AS recipient_name,
+                    q.recepient_email_address AS recipient_email_address,
+                    q.subject, q.body, q.created_at
+                FROM email_queue q
+                LEFT JOIN sent_emails s ON s.id = q.sent_email_id
+                WHERE q.release_campaign_id = $1 AND q.status = 'Pending'
+                ORDER BY q.created_at ASC
+            "#,
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        Ok(OutcomeRelease {
+            outcomes_released_at,
+            emails,
+        })
+    }
+
+    /// Moves a campaign's outcome release to a new time.
+    ///
+    /// Only allowed while the current release is still pending. Once it passes,
+    /// emails have been sent and applicants can see their outcomes, so it cannot be
+    /// moved back into the future.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `update` - The new release time
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the release was rescheduled
+    /// * `Err(ChaosError)` - An error if the outcomes have already been released
+    pub async fn reschedule(
+        campaign_id: i64,
+        update: OutcomeReleaseUpdate,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let campaign = sqlx::query!(
+            "SELECT outcomes_released_at FROM campaigns WHERE id = $1 FOR UPDATE",
+            campaign_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        if campaign
+            .outcomes_released_at
+            .is_some_and(|released_at| released_at <= Utc::now())
+        {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Outcomes have already been released".to_string(),
+            ));
+        }
+
+        sqlx::query!(
+            "UPDATE campaigns SET outcomes_released_at = $2 WHERE id = $1",
+            campaign_id,
+            update.outcomes_released_at
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Cancels every email still held for a campaign's outcome release.
+    ///
+    /// The emails are removed from the queue and marked as cancelled in the sent
+    /// email log. Offers whose emails were cancelled never reached the applicant, so
+    /// they are withdrawn whether or not they were marked as sent. Withdrawn offers
+    /// are kept for their history but no longer count as open, so the outcomes can
+    /// be queued again.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(i64)` - The number of emails cancelled
+    /// * `Err(ChaosError)` - An error if the outcomes have already been released
+    pub async fn cancel(
+        campaign_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<i64, ChaosError> {
+        let campaign = sqlx::query!(
+            "SELECT outcomes_released_at FROM campaigns WHERE id = $1 FOR UPDATE",
+            campaign_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        if campaign
+            .outcomes_released_at
+            .is_some_and(|released_at| released_at <= Utc::now())
+        {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Outcomes have already been released".to_string(),
+            ));
+        }
+
+        let cancelled = sqlx::query!(
+            "
+                DELETE FROM email_queue
+                WHERE release_campaign_id = $1 AND status = 'Pending'
+                RETURNING sent_email_id
+            ",
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let sent_email_ids: Vec<i64> = cancelled
+            .iter()
+            .filter_map(|email| email.sent_email_id)
+            .collect();
+
+        let withdrawn = sqlx::query!(
+            r#"
+                WITH old AS (
+                    SELECT id, status FROM offers
+                    WHERE status IN ('Draft', 'Sent') AND id IN (
+                        SELECT offer_id FROM sent_emails WHERE id = ANY($1)
+                    )
+                    FOR UPDATE
+                )
+                UPDATE offers SET status = 'Withdrawn'
+                FROM old
+                WHERE offers.id = old.id
+                RETURNING offers.id, offers.application_id, offers.role_id,
+                    old.status AS "status: OfferStatus"
+            "#,
+            &sent_email_ids
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        for offer in withdrawn
+            .iter()
+            .filter(|offer| matches!(offer.status, OfferStatus::Sent))
+        {
+            ApplicationEvent::record(
+                offer.application_id,
+                ApplicationEventType::OfferWithdrawn,
+                None,
+                Some(offer.role_id),
+                json!({ "offer_id": offer.id.to_string() }),
+                transaction,
+            )
+            .await?;
+        }
+
+        sqlx::query!(
+            "UPDATE sent_emails SET status = 'Cancelled' WHERE id = ANY($1)",
+            &sent_email_ids
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(cancelled.len() as i64)
+    }
+
+    /// Promotes private statuses to public statuses for every campaign whose outcome
+    /// release has passed.
+    ///
+    /// Each campaign is promoted once per release time, so statuses changed by
+    /// admins after the release are left alone unless the release is rescheduled.
+    ///
+    /// # Arguments
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(usize)` - The number of applications whose public status changed
+    /// * `Err(ChaosError)` - An error if the promotion fails
+    pub async fn promote_due(
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<usize, ChaosError> {
+        let campaign_ids: Vec<i64> = sqlx::query!(
+            "
+                UPDATE campaigns SET outcomes_promoted_at = NOW()
+                WHERE id IN (
+                    SELECT id FROM campaigns
+                    WHERE outcomes_released_at <= NOW()
+                    AND (outcomes_promoted_at IS NULL OR outcomes_promoted_at < outcomes_released_at)
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id
+            "
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?
+        .into_iter()
+        .map(|campaign| campaign.id)
+        .collect();
+
+        if campaign_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let promoted = sqlx::query!(
+            r#"
+                WITH old AS (
+                    SELECT id, status FROM applications
+                    WHERE campaign_id = ANY($1) AND submitted = true
+                    AND status <> private_status
+                    FOR UPDATE
+                )
+                UPDATE applications
+                SET status = applications.private_status, updated_at = NOW()
+                FROM old
+                WHERE applications.id = old.id
+                RETURNING applications.id,
+                    old.status AS "old_status: ApplicationStatus",
+                    applications.status AS "new_status: ApplicationStatus"
+            "#,
+            &campaign_ids
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        for application in &promoted {
+            ApplicationEvent::record(
+                application.id,
+                ApplicationEventType::StatusChanged,
+                None,
+                None,
+                json!({ "from": application.old_status, "to": application.new_status }),
+                transaction,
+            )
+            .await?;
+        }
+
+        Ok(promoted.len())
+    }
+}
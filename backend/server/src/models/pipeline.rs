@@ -0,0 +1,486 @@
+//! Recruitment pipelines for Chaos.
+//!
+//! A campaign can define ordered stages, such as Screening, Interview 1, Task,
+//! Interview 2 and Offer, along with the moves allowed between them. Applications,
+//! and each role within an application, are moved through these stages by admins.
+//! Each stage reports a fixed [`ApplicationStatus`], which is applied to the
+//! application's private status or the role's status when it enters the stage, so
+//! existing status-based views keep working. Every move is recorded with who made
+//! it and when.
+
+use crate::models::application::ApplicationStatus;
+use crate::models::application_event::{ApplicationEvent, ApplicationEventType};
+use crate::models::error::ChaosError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use snowflake::SnowflakeIdGenerator;
+use sqlx::{Postgres, Transaction};
+use std::collections::{HashMap, HashSet};
+use std::ops::DerefMut;
+
+/// A stage in a campaign's pipeline.
+#[derive(Serialize)]
+pub struct PipelineStage {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub id: i64,
+    pub name: String,
+    /// Position in the pipeline, starting at 0
+    pub position: i32,
+    /// Status reported by applications and roles in this stage
+    pub status: ApplicationStatus,
+    /// Stages an application in this stage can be moved to
+    #[serde(serialize_with = "crate::models::serde_string::serialize_vec")]
+    pub next_stage_ids: Vec<i64>,
+}
+
+/// A campaign's pipeline.
+#[derive(Serialize)]
+pub struct Pipeline {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub campaign_id: i64,
+    /// Stages in pipeline order
+    pub stages: Vec<PipelineStage>,
+}
+
+/// A stage in a pipeline update.
+#[derive(Deserialize)]
+pub struct PipelineStageUpdate {
+    /// ID of an existing stage to keep, or `None` to create a new stage
+    #[serde(
+        default,
+        deserialize_with = "crate::models::serde_string::deserialize_option"
+    )]
+    pub id: Option<i64>,
+    pub name: String,
+    pub status: ApplicationStatus,
+    /// Names of the stages an application in this stage can be moved to
+    #[serde(default)]
+    pub next_stages: Vec<String>,
+}
+
+/// Data structure for replacing a campaign's pipeline.
+#[derive(Deserialize)]
+pub struct PipelineUpdate {
+    /// Stages in pipeline order. Existing stages left out are removed.
+    pub stages: Vec<PipelineStageUpdate>,
+}
+
+/// Data structure for moving an application, or one of its roles, to a stage.
+#[derive(Deserialize)]
+pub struct StageMove {
+    #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
+    pub stage_id: i64,
+    /// Role to move, or `None` to move the application as a whole
+    #[serde(
+        default,
+        deserialize_with = "crate::models::serde_string::deserialize_option"
+    )]
+    pub campaign_role_id: Option<i64>,
+}
+
+/// A recorded stage move.
+#[derive(Serialize)]
+pub struct StageTransition {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub id: i64,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub application_id: i64,
+    /// The role that was moved, or `None` if the application as a whole was moved
+    #[serde(serialize_with = "crate::models::serde_string::serialize_option")]
+    pub campaign_role_id: Option<i64>,
+    #[serde(serialize_with = "crate::models::serde_string::serialize_option")]
+    pub from_stage_id: Option<i64>,
+    pub from_stage_name: Option<String>,
+    #[serde(serialize_with = "crate::models::serde_string::serialize_option")]
+    pub to_stage_id: Option<i64>,
+    pub to_stage_name: String,
+    /// The user who made the move, or `None` if their account has since been deleted
+    #[serde(serialize_with = "crate::models::serde_string::serialize_option")]
+    pub moved_by: Option<i64>,
+    pub moved_by_name: Option<String>,
+    pub moved_at: DateTime<Utc>,
+}
+
+impl Pipeline {
+    /// Retrieves a campaign's pipeline.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Pipeline)` - The campaign's pipeline, which has no stages if none are defined
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get(
+        campaign_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Pipeline, ChaosError> {
+        let stages = sqlx::query_as!(
+            PipelineStage,
+            r#"
+                SELECT s.id, s.name, s.position, s.status AS "status: ApplicationStatus",
+                    COALESCE(
+                        ARRAY_AGG(t.to_stage_id) FILTER (WHERE t.to_stage_id IS NOT NULL),
+                        '{}'
+                    ) AS "next_stage_ids!"
+                FROM campaign_stages s
+                LEFT JOIN campaign_stage_transitions t ON t.from_stage_id = s.id
+                WHERE s.campaign_id = $1
+                GROUP BY s.id
+                ORDER BY s.position ASC
+            "#,
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        Ok(Pipeline {
+            campaign_id,
+            stages,
+        })
+    }
+
+    /// Replaces a campaign's pipeline.
+    ///
+    /// Stages are matched by ID, so applications stay in stages that are renamed or
+    /// reordered. A stage can only be removed once nothing is in it.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `update` - The new pipeline
+    /// * `snowflake_generator` - Generator for new stage IDs
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the pipeline was replaced
+    /// * `Err(ChaosError)` - An error if the pipeline is invalid
+    pub async fn set(
+        campaign_id: i64,
+        update: PipelineUpdate,
+        snowflake_generator: &mut SnowflakeIdGenerator,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let mut names = HashSet::new();
+        for stage in &update.stages {
+            if stage.name.trim().is_empty() {
+                return Err(ChaosError::BadRequestWithMessage(
+                    "Stage names cannot be empty".to_string(),
+                ));
+            }
+            if !names.insert(stage.name.as_str()) {
+                return Err(ChaosError::BadRequestWithMessage(format!(
+                    "Stage name '{}' is used more than once",
+                    stage.name
+                )));
+            }
+        }
+
+        for stage in &update.stages {
+            if let Some(next) = stage
+                .next_stages
+                .iter()
+                .find(|next| !names.contains(next.as_str()) || **next == stage.name)
+            {
+                return Err(ChaosError::BadRequestWithMessage(format!(
+                    "Stage '{}' cannot move to '{next}'",
+                    stage.name
+                )));
+            }
+        }
+
+        let existing: HashSet<i64> = sqlx::query!(
+            "SELECT id FROM campaign_stages WHERE campaign_id = $1 FOR UPDATE",
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?
+        .into_iter()
+        .map(|stage| stage.id)
+        .collect();
+
+        let kept: HashSet<i64> = update.stages.iter().filter_map(|stage| stage.id).collect();
+        if !kept.is_subset(&existing) {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Stage does not belong to this campaign".to_string(),
+            ));
+        }
+
+        let removed: Vec<i64> = existing.difference(&kept).copied().collect();
+        let in_use = sqlx::query!(
+            "
+                SELECT EXISTS(
+                    SELECT 1 FROM applications WHERE stage_id = ANY($1)
+                    UNION ALL
+                    SELECT 1 FROM application_roles WHERE stage_id = ANY($1)
+                )
+            ",
+            &removed
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .exists
+        .expect("`exists` should always exist in this query result");
+
+        if in_use {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Move every application out of a stage before removing it".to_string(),
+            ));
+        }
+
+        sqlx::query!("DELETE FROM campaign_stages WHERE id = ANY($1)", &removed)
+            .execute(transaction.deref_mut())
+            .await?;
+
+        let mut ids_by_name = HashMap::new();
+        for (position, stage) in update.stages.iter().enumerate() {
+            let id = match stage.id {
+                Some(id) => {
+                    sqlx::query!(
+                        "
+                            UPDATE campaign_stages SET name = $2, position = $3, status = $4
+                            WHERE id = $1
+                        ",
+                        id,
+                        stage.name,
+                        position as i32,
+                        stage.status.clone() as ApplicationStatus
+                    )
+                    .execute(transaction.deref_mut())
+                    .await?;
+                    id
+                }
+                None => {
+                    let id = snowflake_generator.real_time_generate();
+                    sqlx::query!(
+                        "
+                            INSERT INTO campaign_stages (id, campaign_id, name, position, status)
+                            VALUES ($1, $2, $3, $4, $5)
+                        ",
+                        id,
+                        campaign_id,
+                        stage.name,
+                        position as i32,
+                        stage.status.clone() as ApplicationStatus
+                    )
+                    .execute(transaction.deref_mut())
+                    .await?;
+                    id
+                }
+            };
+
+            ids_by_name.insert(stage.name.as_str(), id);
+        }
+
+        sqlx::query!(
+            "
+                DELETE FROM campaign_stage_transitions
+                WHERE from_stage_id IN (SELECT id FROM campaign_stages WHERE campaign_id = $1)
+            ",
+            campaign_id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        let mut from_ids = Vec::new();
+        let mut to_ids = Vec::new();
+        for stage in &update.stages {
+            for next in &stage.next_stages {
+                from_ids.push(ids_by_name[stage.name.as_str()]);
+                to_ids.push(ids_by_name[next.as_str()]);
+            }
+        }
+
+        sqlx::query!(
+            "
+                INSERT INTO campaign_stage_transitions (from_stage_id, to_stage_id)
+                SELECT * FROM UNNEST($1::bigint[], $2::bigint[])
+                ON CONFLICT DO NOTHING
+            ",
+            &from_ids,
+            &to_ids
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Moves an application, or one of its roles, to a stage of its campaign's pipeline.
+    ///
+    /// Applications and roles that are not in a stage yet can be moved into any stage.
+    /// After that, only the moves defined by the pipeline are allowed. The move is
+    /// recorded against the admin who made it.
+    ///
+    /// # Arguments
+    /// * `application_id` - The ID of the application
+    /// * `stage_move` - The stage to move to, and the role to move if not the whole application
+    /// * `user_id` - The ID of the admin making the move
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the application or role was moved
+    /// * `Err(ChaosError)` - An error if the move is not allowed
+    pub async fn move_application(
+        application_id: i64,
+        stage_move: StageMove,
+        user_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let current_stage_id = match stage_move.campaign_role_id {
+            None => {
+                sqlx::query!(
+                    "SELECT stage_id FROM applications WHERE id = $1 FOR UPDATE",
+                    application_id
+                )
+                .fetch_one(transaction.deref_mut())
+                .await?
+                .stage_id
+            }
+            Some(campaign_role_id) => {
+                sqlx::query!(
+                    "
+                        SELECT stage_id FROM application_roles
+                        WHERE application_id = $1 AND campaign_role_id = $2
+                        FOR UPDATE
+                    ",
+                    application_id,
+                    campaign_role_id
+                )
+                .fetch_optional(transaction.deref_mut())
+                .await?
+                .ok_or(ChaosError::BadRequestWithMessage(
+                    "The application did not apply for this role".to_string(),
+                ))?
+                .stage_id
+            }
+        };
+
+        let stage = sqlx::query!(
+            r#"
+                SELECT s.name, s.status AS "status: ApplicationStatus",
+                    (SELECT name FROM campaign_stages WHERE id = $3) AS from_stage_name,
+                    ($3::bigint IS NULL OR EXISTS(
+                        SELECT 1 FROM campaign_stage_transitions
+                        WHERE from_stage_id = $3 AND to_stage_id = s.id
+                    )) AS "allowed!"
+                FROM campaign_stages s
+                JOIN applications a ON a.campaign_id = s.campaign_id
+                WHERE s.id = $1 AND a.id = $2
+            "#,
+            stage_move.stage_id,
+            application_id,
+            current_stage_id
+        )
+        .fetch_optional(transaction.deref_mut())
+        .await?
+        .ok_or(ChaosError::BadRequestWithMessage(
+            "Stage does not belong to this application's campaign".to_string(),
+        ))?;
+
+        if !stage.allowed {
+            return Err(ChaosError::BadRequestWithMessage(format!(
+                "Cannot move from '{}' to '{}'",
+                stage.from_stage_name.unwrap_or_default(),
+                stage.name
+            )));
+        }
+
+        match stage_move.campaign_role_id {
+            None => {
+                sqlx::query!(
+                    "
+                        UPDATE applications
+                        SET stage_id = $2, private_status = $3, updated_at = NOW()
+                        WHERE id = $1
+                    ",
+                    application_id,
+                    stage_move.stage_id,
+                    stage.status as ApplicationStatus
+                )
+                .execute(transaction.deref_mut())
+                .await?;
+            }
+            Some(campaign_role_id) => {
+                sqlx::query!(
+                    "
+                        UPDATE application_roles SET stage_id = $3, role_status = $4
+                        WHERE application_id = $1 AND campaign_role_id = $2
+                    ",
+                    application_id,
+                    campaign_role_id,
+                    stage_move.stage_id,
+                    stage.status as ApplicationStatus
+                )
+                .execute(transaction.deref_mut())
+                .await?;
+            }
+        }
+
+        sqlx::query!(
+            "
+                INSERT INTO application_stage_history
+                    (application_id, campaign_role_id, from_stage_id, from_stage_name,
+                    to_stage_id, to_stage_name, moved_by)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ",
+            application_id,
+            stage_move.campaign_role_id,
+            current_stage_id,
+            stage.from_stage_name,
+            stage_move.stage_id,
+            stage.name,
+            user_id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        ApplicationEvent::record(
+            application_id,
+            ApplicationEventType::StageChanged,
+            Some(user_id),
+            stage_move.campaign_role_id,
+            json!({ "from": stage.from_stage_name, "to": stage.name }),
+            transaction,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Retrieves every stage move made for an application.
+    ///
+    /// # Arguments
+    /// * `application_id` - The ID of the application
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<StageTransition>)` - The application's stage moves, oldest first
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get_history(
+        application_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<StageTransition>, ChaosError> {
+        let history = sqlx::query_as!(
+            StageTransition,
+            r#"
+                SELECT h.id, h.application_id, h.campaign_role_id, h.from_stage_id,
+                    h.from_stage_name, h.to_stage_id, h.to_stage_name, h.moved_by,
+                    u.name AS "moved_by_name?", h.moved_at
+                FROM application_stage_history h
+                LEFT JOIN users u ON u.id = h.moved_by
+                WHERE h.application_id = $1
+                ORDER BY h.moved_at ASC, h.id ASC
+            "#,
+            application_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        Ok(history)
+    }
+}
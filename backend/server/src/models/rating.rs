@@ -3,9 +3,11 @@
 //! This module provides functionality for managing ratings and comments
 //! on applications, including creation, updates, and retrieval of rating information.
 
+use crate::models::application_event::{ApplicationEvent, ApplicationEventType};
 use crate::models::error::ChaosError;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use snowflake::SnowflakeIdGenerator;
 use sqlx::{FromRow, Postgres, Transaction};
 use std::ops::DerefMut;
@@ -13,7 +15,7 @@ use std::ops::DerefMut;
 /// Represents a category rating in the database
 ///
 /// Admin of a campaign can create category rating
-/// includes the name of the category,
+/// includes the name of the category, its weight, its scale and its rubric
 #[derive(Deserialize, Serialize, Clone, FromRow, Debug)]
 pub struct CategoryRating {
     /// Unique identifier for the category
@@ -24,6 +26,23 @@ pub struct CategoryRating {
     pub campaign_id: i64,
     /// Name of the category that it is being created for the campaign
     pub name: String,
+    /// Weight of the category in an application's weighted total
+    pub weight: f64,
+    /// Lowest score allowed in the category
+    pub min_rating: i32,
+    /// Highest score allowed in the category
+    pub max_rating: i32,
+    /// Descriptors explaining what scores in the category mean
+    pub rubric: sqlx::types::Json<Vec<RubricDescriptor>>,
+}
+
+/// Describes what a score means in a rating category.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RubricDescriptor {
+    /// Score on the category's scale
+    pub score: i32,
+    /// What the score means
+    pub description: String,
 }
 
 /// Data structure for creating a new Category Rating
@@ -33,6 +52,47 @@ pub struct CategoryRating {
 pub struct NewCategoryRating {
     /// Name of the category
     pub name: String,
+    /// Weight of the category, defaults to 1
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    /// Lowest score allowed, defaults to 1
+    #[serde(default = "default_min_rating")]
+    pub min_rating: i32,
+    /// Highest score allowed, defaults to 5
+    #[serde(default = "default_max_rating")]
+    pub max_rating: i32,
+    /// Descriptors explaining what scores mean
+    #[serde(default)]
+    pub rubric: Vec<RubricDescriptor>,
+}
+
+/// Data structure for updating a rating category.
+///
+/// Fields left out keep their current value.
+#[derive(Deserialize, Serialize)]
+pub struct RatingCategoryUpdate {
+    /// Name of the category
+    pub name: String,
+    /// Weight of the category
+    pub weight: Option<f64>,
+    /// Lowest score allowed
+    pub min_rating: Option<i32>,
+    /// Highest score allowed
+    pub max_rating: Option<i32>,
+    /// Descriptors explaining what scores mean, replacing the current rubric
+    pub rubric: Option<Vec<RubricDescriptor>>,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+fn default_min_rating() -> i32 {
+    1
+}
+
+fn default_max_rating() -> i32 {
+    5
 }
 
 ///
@@ -176,7 +236,7 @@ impl Rating {
     /// Creates a new category for a campaign for rating
     ///
     /// # Arguments
-    /// * `new_category` - The category name that is created
+    /// * `new_category` - The category that is created
     /// * `campaign_id` - Campaign that is adding the new category for ratings
     /// * `snowflake_generator` - A generator for creating unique IDs
     /// * `transaction` - A mutable reference to the database transaction
@@ -184,28 +244,41 @@ impl Rating {
     /// # Returns
     /// Returns a `Result` containing either:
     /// * `Ok(())` - If the category of a campaign was created successfully
-    /// * `Err(ChaosError)` - An error if creation fails
+    /// * `Err(ChaosError)` - An error if the scale or rubric is invalid, or creation fails
     ///
     pub async fn create_category(
-        name: String,
+        new_category: NewCategoryRating,
         campaign_id: i64,
         snowflake_generator: &mut SnowflakeIdGenerator,
         transaction: &mut Transaction<'_, Postgres>,
     ) -> Result<i64, ChaosError> {
+        Self::validate_scale(
+            new_category.weight,
+            new_category.min_rating,
+            new_category.max_rating,
+            &new_category.rubric,
+        )?;
+
         let id = snowflake_generator.real_time_generate();
 
         sqlx::query!(
             "
-                INSERT INTO campaign_rating_categories (id, name, campaign_id)
-                    VALUES ($1, $2, $3)
+                INSERT INTO campaign_rating_categories
+                    (id, name, campaign_id, weight, min_rating, max_rating)
+                    VALUES ($1, $2, $3, $4, $5, $6)
             ",
             id,
-            name,
-            campaign_id
+            new_category.name,
+            campaign_id,
+            new_category.weight,
+            new_category.min_rating,
+            new_category.max_rating
         )
         .execute(transaction.deref_mut())
         .await?;
 
+        Self::set_rubric(id, new_category.rubric, transaction).await?;
+
         Ok(id)
     }
 
@@ -225,11 +298,23 @@ impl Rating {
     ) -> Result<Vec<CategoryRating>, ChaosError> {
         let categories = sqlx::query_as!(
             CategoryRating,
-            "
-                SELECT id, name, campaign_id
-                FROM campaign_rating_categories
-                WHERE campaign_id = $1
-            ",
+            r#"
+                SELECT crc.id, crc.name, crc.campaign_id, crc.weight, crc.min_rating,
+                    crc.max_rating,
+                    COALESCE(
+                        (
+                            SELECT jsonb_agg(
+                                jsonb_build_object('score', r.score, 'description', r.description)
+                                ORDER BY r.score
+                            )
+                            FROM campaign_rating_category_rubrics r
+                            WHERE r.campaign_rating_category_id = crc.id
+                        ),
+                        '[]'::jsonb
+                    ) AS "rubric!: sqlx::types::Json<Vec<RubricDescriptor>>"
+                FROM campaign_rating_categories crc
+                WHERE crc.campaign_id = $1
+            "#,
             campaign_id
         )
         .fetch_all(transaction.deref_mut())
@@ -238,35 +323,198 @@ impl Rating {
         Ok(categories)
     }
 
-    /// Updates an existing category name by category.
+    /// Updates an existing category by category.
+    ///
+    /// The scale can only be narrowed if every existing rating and rubric descriptor
+    /// in the category still falls within it.
     ///
     /// # Arguments
     /// * `category_id` - The ID of the category to update
-    /// * `updated_category` - The new category (ONLY INCLUDES NAME)
+    /// * `update` - The new category details
     /// * `transaction` - A mutable reference to the database transaction
     ///
     /// # Returns
     /// Returns a `Result` containing either:
     /// * `Ok(())` - If the category was updated successfully
-    /// * `Err(ChaosError)` - An error if update fails
+    /// * `Err(ChaosError)` - An error if the scale or rubric is invalid, or update fails
     pub async fn update_category(
         category_id: i64,
-        name: String,
+        update: RatingCategoryUpdate,
         transaction: &mut Transaction<'_, Postgres>,
     ) -> Result<(), ChaosError> {
+        let current = sqlx::query!(
+            "
+                SELECT weight, min_rating, max_rating
+                FROM campaign_rating_categories
+                WHERE id = $1
+                FOR UPDATE
+            ",
+            category_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        let weight = update.weight.unwrap_or(current.weight);
+        let min_rating = update.min_rating.unwrap_or(current.min_rating);
+        let max_rating = update.max_rating.unwrap_or(current.max_rating);
+        Self::validate_scale(
+            weight,
+            min_rating,
+            max_rating,
+            update.rubric.as_deref().unwrap_or_default(),
+        )?;
+
+        let out_of_scale = sqlx::query!(
+            "
+                SELECT EXISTS(
+                    SELECT 1 FROM application_rating_category_ratings
+                    WHERE campaign_rating_category_id = $1
+                    AND (rating < $2 OR rating > $3)
+                )
+            ",
+            category_id,
+            min_rating,
+            max_rating
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .exists
+        .expect("`exists` should always exist in this query result");
+
+        if out_of_scale {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Existing ratings fall outside the new scale".to_string(),
+            ));
+        }
+
         sqlx::query!(
             "
                 UPDATE campaign_rating_categories
-                SET name = $2
+                SET name = $2, weight = $3, min_rating = $4, max_rating = $5
                 WHERE id = $1
-                RETURNING id
             ",
             category_id,
-            name,
+            update.name,
+            weight,
+            min_rating,
+            max_rating
         )
-        .fetch_one(transaction.deref_mut())
+        .execute(transaction.deref_mut())
         .await?;
 
+        match update.rubric {
+            Some(rubric) => Self::set_rubric(category_id, rubric, transaction).await?,
+            None => {
+                let rubric_out_of_scale = sqlx::query!(
+                    "
+                        SELECT EXISTS(
+                            SELECT 1 FROM campaign_rating_category_rubrics
+                            WHERE campaign_rating_category_id = $1
+                            AND (score < $2 OR score > $3)
+                        )
+                    ",
+                    category_id,
+                    min_rating,
+                    max_rating
+                )
+                .fetch_one(transaction.deref_mut())
+                .await?
+                .exists
+                .expect("`exists` should always exist in this query result");
+
+                if rubric_out_of_scale {
+                    return Err(ChaosError::BadRequestWithMessage(
+                        "Rubric has descriptors outside the new scale".to_string(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces a category's rubric.
+    async fn set_rubric(
+        category_id: i64,
+        rubric: Vec<RubricDescriptor>,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        sqlx::query!(
+            "DELETE FROM campaign_rating_category_rubrics WHERE campaign_rating_category_id = $1",
+            category_id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        let (scores, descriptions): (Vec<i32>, Vec<String>) = rubric
+            .into_iter()
+            .map(|descriptor| (descriptor.score, descriptor.description))
+            .unzip();
+
+        sqlx::query!(
+            "
+                INSERT INTO campaign_rating_category_rubrics
+                    (campaign_rating_category_id, score, description)
+                SELECT $1, * FROM UNNEST($2::INTEGER[], $3::TEXT[])
+            ",
+            category_id,
+            &scores,
+            &descriptions
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Checks that a category's weight, scale and rubric are consistent.
+    fn validate_scale(
+        weight: f64,
+        min_rating: i32,
+        max_rating: i32,
+        rubric: &[RubricDescriptor],
+    ) -> Result<(), ChaosError> {
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Weight must be a non-negative number".to_string(),
+            ));
+        }
+
+        if min_rating >= max_rating {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Minimum rating must be less than maximum rating".to_string(),
+            ));
+        }
+
+        let mut scores = Vec::with_capacity(rubric.len());
+        for descriptor in rubric {
+            if descriptor.score < min_rating || descriptor.score > max_rating {
+                return Err(ChaosError::BadRequestWithMessage(format!(
+                    "Rubric score {} is outside the scale {} to {}",
+                    descriptor.score, min_rating, max_rating
+                )));
+            }
+            if scores.contains(&descriptor.score) {
+                return Err(ChaosError::BadRequestWithMessage(format!(
+                    "Rubric score {} is described more than once",
+                    descriptor.score
+                )));
+            }
+            scores.push(descriptor.score);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a score falls within a category's scale.
+    fn validate_rating(rating: i32, min_rating: i32, max_rating: i32) -> Result<(), ChaosError> {
+        if rating < min_rating || rating > max_rating {
+            return Err(ChaosError::BadRequestWithMessage(format!(
+                "Rating must be between {} and {}",
+                min_rating, max_rating
+            )));
+        }
+
         Ok(())
     }
 
@@ -313,22 +561,38 @@ impl Rating {
     ) -> Result<i64, ChaosError> {
         let rating_id = snowflake_generator.real_time_generate();
 
-        sqlx::query!(
-            "
+        let rating = sqlx::query!(
+            r#"
                 INSERT INTO application_ratings (id, application_id, rater_id, comment)
                     VALUES ($1, $2, $3, $4)
                 ON CONFLICT (application_id, rater_id)
                 DO UPDATE SET comment = $4, updated_at = NOW()
-            ",
+                RETURNING id, (xmax = 0) AS "inserted!"
+            "#,
             rating_id,
             application_id,
             rater_id,
             comment
         )
-        .execute(transaction.deref_mut())
+        .fetch_one(transaction.deref_mut())
         .await?;
 
-        Ok(rating_id)
+        let event_type = if rating.inserted {
+            ApplicationEventType::RatingCreated
+        } else {
+            ApplicationEventType::RatingUpdated
+        };
+        ApplicationEvent::record(
+            application_id,
+            event_type,
+            Some(rater_id),
+            None,
+            json!({ "rating_id": rating.id.to_string() }),
+            transaction,
+        )
+        .await?;
+
+        Ok(rating.id)
     }
 
     /// Updates an existing application_rating ONLY THRU COMMENT.
@@ -349,12 +613,12 @@ impl Rating {
     ) -> Result<(), ChaosError> {
         let current_time = Utc::now();
 
-        sqlx::query!(
+        let rating = sqlx::query!(
             "
                 UPDATE application_ratings
                 SET comment = $2, updated_at = $3
                 WHERE id = $1
-                RETURNING id
+                RETURNING application_id, rater_id
             ",
             rating_id,
             comment,
@@ -363,6 +627,16 @@ impl Rating {
         .fetch_one(transaction.deref_mut())
         .await?;
 
+        ApplicationEvent::record(
+            rating.application_id,
+            ApplicationEventType::RatingUpdated,
+            Some(rating.rater_id),
+            None,
+            json!({ "rating_id": rating_id.to_string() }),
+            transaction,
+        )
+        .await?;
+
         Ok(())
     }
 
@@ -532,13 +806,36 @@ impl Rating {
     /// # Returns
     /// Returns a `Result` containing either:
     /// * `Ok(i64)` - The ID of the created application category rating
-    /// * `Err(ChaosError)` - An error if creation fails
+    /// * `Err(ChaosError)` - An error if the score is outside the category's scale, or creation fails
     pub async fn create_category_rating(
         new_category_rating: NewApplicationCategoryRating,
         application_rating_id: i64,
         snowflake_generator: &mut SnowflakeIdGenerator,
         transaction: &mut Transaction<'_, Postgres>,
     ) -> Result<i64, ChaosError> {
+        let scale = sqlx::query!(
+            "
+                SELECT crc.min_rating, crc.max_rating
+                FROM campaign_rating_categories crc
+                JOIN application_ratings ar ON ar.id = $2
+                JOIN applications a ON a.id = ar.application_id
+                WHERE crc.id = $1 AND crc.campaign_id = a.campaign_id
+            ",
+            new_category_rating.campaign_rating_category_id,
+            application_rating_id
+        )
+        .fetch_optional(transaction.deref_mut())
+        .await?
+        .ok_or(ChaosError::BadRequestWithMessage(
+            "Rating category does not belong to this campaign".to_string(),
+        ))?;
+
+        Self::validate_rating(
+            new_category_rating.rating,
+            scale.min_rating,
+            scale.max_rating,
+        )?;
+
         let category_rating_id = snowflake_generator.real_time_generate();
 
         sqlx::query!(
@@ -568,20 +865,35 @@ impl Rating {
     /// # Returns
     /// Returns a `Result` containing either:
     /// * `Ok(())` - If the rating was updated successfully
-    /// * `Err(ChaosError)` - An error if update fails
+    /// * `Err(ChaosError)` - An error if the score is outside the category's scale, or update fails
     pub async fn update_category_rating(
         category_rating_id: i64,
         updated_rating: i32,
         transaction: &mut Transaction<'_, Postgres>,
     ) -> Result<(), ChaosError> {
+        let scale = sqlx::query!(
+            "
+                SELECT crc.min_rating, crc.max_rating
+                FROM application_rating_category_ratings cr
+                JOIN campaign_rating_categories crc ON crc.id = cr.campaign_rating_category_id
+                WHERE cr.id = $1
+            ",
+            category_rating_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        Self::validate_rating(updated_rating, scale.min_rating, scale.max_rating)?;
+
         let current_time = Utc::now();
 
-        sqlx::query!(
+        let rating = sqlx::query!(
             "
-            UPDATE application_rating_category_ratings
+            UPDATE application_rating_category_ratings cr
             SET rating = $2, updated_at = $3
-            WHERE id = $1
-            RETURNING id
+            FROM application_ratings ar
+            WHERE cr.id = $1 AND ar.id = cr.application_rating_id
+            RETURNING ar.id, ar.application_id, ar.rater_id
         ",
             category_rating_id,
             updated_rating,
@@ -590,6 +902,16 @@ impl Rating {
         .fetch_one(transaction.deref_mut())
         .await?;
 
+        ApplicationEvent::record(
+            rating.application_id,
+            ApplicationEventType::RatingUpdated,
+            Some(rating.rater_id),
+            None,
+            json!({ "rating_id": rating.id.to_string() }),
+            transaction,
+        )
+        .await?;
+
         Ok(())
     }
 
@@ -0,0 +1,382 @@
+//! Rating analytics for Chaos campaigns.
+//!
+//! Reviewers calibrate their scores differently: some rate harshly while others
+//! give almost everyone top marks. To rank applicants fairly across reviewer pools,
+//! each rating is converted to a z-score against the reviewer's own ratings in that
+//! category, and applications are ranked by the weighted total of their normalised
+//! category scores, using each category's weight. Krippendorff's alpha is reported
+//! per category and for the whole campaign, so admins can see how far reviewers
+//! actually agree. Ratings by reviewers who have a conflict of interest on an
+//! application are left out entirely.
+
+use crate::models::blind_review::BlindReview;
+use crate::models::error::ChaosError;
+use serde::Serialize;
+use sqlx::{Postgres, Transaction};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::DerefMut;
+
+/// How one reviewer rates in one category, across the whole campaign.
+#[derive(Serialize)]
+pub struct ReviewerCalibration {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub rater_id: i64,
+    pub rater_name: String,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub campaign_rating_category_id: i64,
+    pub category_name: String,
+    pub ratings_count: usize,
+    pub mean: f64,
+    /// Population standard deviation of the reviewer's ratings in the category
+    pub std_dev: f64,
+}
+
+/// An application's score in one category.
+#[derive(Serialize)]
+pub struct CategoryScore {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub campaign_rating_category_id: i64,
+    pub category_name: String,
+    pub ratings_count: usize,
+    /// Mean of the raw ratings, or `None` if nobody rated this category
+    pub raw_mean: Option<f64>,
+    /// Mean of the reviewers' z-scores, or `None` if nobody rated this category
+    pub normalised_mean: Option<f64>,
+}
+
+/// An application's normalised scores and its rank within the campaign.
+#[derive(Serialize)]
+pub struct ApplicationScore {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub application_id: i64,
+    pub user_name: String,
+    pub user_email: String,
+    /// Number of reviewers who rated the application
+    pub raters_count: usize,
+    pub categories: Vec<CategoryScore>,
+    /// Weighted total of the raw category means, each scaled from its category's
+    /// rating range to between 0 and 1
+    pub raw_total: Option<f64>,
+    /// Weighted total of the normalised category means, used for ranking
+    pub normalised_total: Option<f64>,
+    /// Position in the campaign ranking, or `None` if the application has no ratings
+    pub rank: Option<usize>,
+}
+
+/// Inter-rater agreement for one category.
+#[derive(Serialize)]
+pub struct CategoryAgreement {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub campaign_rating_category_id: i64,
+    pub category_name: String,
+    /// Krippendorff's alpha with the interval metric, or `None` if there are too few
+    /// overlapping ratings to compute it
+    pub krippendorff_alpha: Option<f64>,
+}
+
+/// Rating analytics for a campaign.
+#[derive(Serialize)]
+pub struct RatingAnalytics {
+    pub reviewers: Vec<ReviewerCalibration>,
+    /// Applications ordered by rank, with unrated applications last
+    pub applications: Vec<ApplicationScore>,
+    pub category_agreement: Vec<CategoryAgreement>,
+    /// Krippendorff's alpha across every category of the campaign, with each
+    /// category's ratings scaled to 0..1 so that different scales can be pooled
+    pub krippendorff_alpha: Option<f64>,
+}
+
+/// Running totals for a set of ratings.
+#[derive(Default)]
+struct Moments {
+    count: usize,
+    sum: f64,
+    sum_squares: f64,
+}
+
+impl Moments {
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.sum_squares += value * value;
+    }
+
+    fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+
+    fn std_dev(&self) -> f64 {
+        let mean = self.mean();
+        (self.sum_squares / self.count as f64 - mean * mean)
+            .max(0.0)
+            .sqrt()
+    }
+
+    /// Sum of squared differences over every ordered pair of distinct ratings.
+    fn pairwise_squared_differences(&self) -> f64 {
+        2.0 * (self.count as f64 * self.sum_squares - self.sum * self.sum)
+    }
+}
+
+impl RatingAnalytics {
+    /// Computes normalised scores, rankings and inter-rater agreement for a campaign.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(RatingAnalytics)` - The campaign's rating analytics
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get(
+        campaign_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<RatingAnalytics, ChaosError> {
+        let categories = sqlx::query!(
+            "
+                SELECT id, name, weight, min_rating, max_rating
+                FROM campaign_rating_categories
+                WHERE campaign_id = $1
+                ORDER BY id
+            ",
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let applications = sqlx::query!(
+            "
+                SELECT a.id, u.name, u.email
+                FROM applications a
+                JOIN users u ON u.id = a.user_id
+                WHERE a.campaign_id = $1 AND a.submitted = true
+                ORDER BY a.id
+            ",
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let ratings = sqlx::query!(
+            "
+                SELECT ar.application_id, ar.rater_id, reviewer.name AS rater_name,
+                    arc.campaign_rating_category_id AS category_id, arc.rating
+                FROM application_rating_category_ratings arc
+                JOIN application_ratings ar ON ar.id = arc.application_rating_id
+                JOIN applications a ON a.id = ar.application_id
+                JOIN users reviewer ON reviewer.id = ar.rater_id
+                WHERE a.campaign_id = $1 AND a.submitted = true
+                AND NOT EXISTS (
+                    SELECT 1 FROM review_conflicts rc
+                    WHERE rc.application_id = a.id AND rc.reviewer_id = ar.rater_id
+                )
+            ",
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let category_names: HashMap<i64, &str> = categories
+            .iter()
+            .map(|category| (category.id, category.name.as_str()))
+            .collect();
+        let weights: HashMap<i64, f64> = categories
+            .iter()
+            .map(|category| (category.id, category.weight))
+            .collect();
+        let scales: HashMap<i64, (f64, f64)> = categories
+            .iter()
+            .map(|category| {
+                (
+                    category.id,
+                    (category.min_rating as f64, category.max_rating as f64),
+                )
+            })
+            .collect();
+
+        // Calibrate each reviewer against their own ratings in each category.
+        let mut rater_names: HashMap<i64, &str> = HashMap::new();
+        let mut calibration: BTreeMap<(i64, i64), Moments> = BTreeMap::new();
+        for rating in &ratings {
+            rater_names.insert(rating.rater_id, rating.rater_name.as_str());
+            calibration
+                .entry((rating.rater_id, rating.category_id))
+                .or_default()
+                .add(rating.rating as f64);
+        }
+
+        // Group raw, scaled and normalised ratings by application and category.
+        let mut raw: HashMap<(i64, i64), Moments> = HashMap::new();
+        let mut scaled: HashMap<(i64, i64), Moments> = HashMap::new();
+        let mut normalised: HashMap<(i64, i64), Moments> = HashMap::new();
+        let mut raters: HashMap<i64, Vec<i64>> = HashMap::new();
+        for rating in &ratings {
+            let key = (rating.application_id, rating.category_id);
+            let reviewer = &calibration[&(rating.rater_id, rating.category_id)];
+            let std_dev = reviewer.std_dev();
+            let z_score = if std_dev > 0.0 {
+                (rating.rating as f64 - reviewer.mean()) / std_dev
+            } else {
+                0.0
+            };
+
+            let (min, max) = scales[&rating.category_id];
+            raw.entry(key).or_default().add(rating.rating as f64);
+            scaled
+                .entry(key)
+                .or_default()
+                .add((rating.rating as f64 - min) / (max - min));
+            normalised.entry(key).or_default().add(z_score);
+
+            let application_raters = raters.entry(rating.application_id).or_default();
+            if !application_raters.contains(&rating.rater_id) {
+                application_raters.push(rating.rater_id);
+            }
+        }
+
+        let application_ids: Vec<i64> = applications
+            .iter()
+            .map(|application| application.id)
+            .collect();
+        let blinded = BlindReview::blinded_applications(&application_ids, transaction).await?;
+
+        let mut scores: Vec<ApplicationScore> = applications
+            .into_iter()
+            .map(|application| {
+                let categories: Vec<CategoryScore> = categories
+                    .iter()
+                    .map(|category| {
+                        let key = (application.id, category.id);
+                        CategoryScore {
+                            campaign_rating_category_id: category.id,
+                            category_name: category.name.clone(),
+                            ratings_count: raw.get(&key).map_or(0, |moments| moments.count),
+                            raw_mean: raw.get(&key).map(Moments::mean),
+                            normalised_mean: normalised.get(&key).map(Moments::mean),
+                        }
+                    })
+                    .collect();
+
+                let (user_name, user_email) = if let Some(pseudonym) = blinded.get(&application.id)
+                {
+                    (pseudonym.clone(), String::new())
+                } else {
+                    (application.name, application.email)
+                };
+
+                ApplicationScore {
+                    application_id: application.id,
+                    user_name,
+                    user_email,
+                    raters_count: raters.get(&application.id).map_or(0, Vec::len),
+                    raw_total: Self::weighted_total(&categories, &weights, |score| {
+                        let (min, max) = scales[&score.campaign_rating_category_id];
+                        score.raw_mean.map(|mean| (mean - min) / (max - min))
+                    }),
+                    normalised_total: Self::weighted_total(&categories, &weights, |score| {
+                        score.normalised_mean
+                    }),
+                    categories,
+                    rank: None,
+                }
+            })
+            .collect();
+
+        scores.sort_by(|a, b| match (a.normalised_total, b.normalised_total) {
+            (Some(a), Some(b)) => b.total_cmp(&a),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        for (index, score) in scores.iter_mut().enumerate() {
+            if score.normalised_total.is_some() {
+                score.rank = Some(index + 1);
+            }
+        }
+
+        let reviewers = calibration
+            .iter()
+            .map(|(&(rater_id, category_id), moments)| ReviewerCalibration {
+                rater_id,
+                rater_name: rater_names[&rater_id].to_string(),
+                campaign_rating_category_id: category_id,
+                category_name: category_names
+                    .get(&category_id)
+                    .copied()
+                    .unwrap_or_default()
+                    .to_string(),
+                ratings_count: moments.count,
+                mean: moments.mean(),
+                std_dev: moments.std_dev(),
+            })
+            .collect();
+
+        let category_agreement = categories
+            .iter()
+            .map(|category| CategoryAgreement {
+                campaign_rating_category_id: category.id,
+                category_name: category.name.clone(),
+                krippendorff_alpha: Self::krippendorff_alpha(
+                    raw.iter()
+                        .filter(|((_, category_id), _)| *category_id == category.id)
+                        .map(|(_, moments)| moments),
+                ),
+            })
+            .collect();
+
+        Ok(RatingAnalytics {
+            reviewers,
+            applications: scores,
+            category_agreement,
+            krippendorff_alpha: Self::krippendorff_alpha(scaled.values()),
+        })
+    }
+
+    /// Weighted mean of the category scores that are present.
+    fn weighted_total(
+        categories: &[CategoryScore],
+        weights: &HashMap<i64, f64>,
+        value: impl Fn(&CategoryScore) -> Option<f64>,
+    ) -> Option<f64> {
+        let mut total = 0.0;
+        let mut total_weight = 0.0;
+        for category in categories {
+            if let Some(score) = value(category) {
+                let weight = weights[&category.campaign_rating_category_id];
+                total += weight * score;
+                total_weight += weight;
+            }
+        }
+
+        (total_weight > 0.0).then_some(total / total_weight)
+    }
+
+    /// Krippendorff's alpha with the interval metric.
+    ///
+    /// Each unit is one application's ratings in one category. Units rated by a
+    /// single reviewer cannot be paired, so are left out.
+    fn krippendorff_alpha<'a>(units: impl Iterator<Item = &'a Moments>) -> Option<f64> {
+        let mut pooled = Moments::default();
+        let mut observed = 0.0;
+        for unit in units.filter(|unit| unit.count >= 2) {
+            observed += unit.pairwise_squared_differences() / (unit.count - 1) as f64;
+            pooled.count += unit.count;
+            pooled.sum += unit.sum;
+            pooled.sum_squares += unit.sum_squares;
+        }
+
+        if pooled.count < 2 {
+            return None;
+        }
+
+        let n = pooled.count as f64;
+        let observed_disagreement = observed / n;
+        let expected_disagreement = pooled.pairwise_squared_differences() / (n * (n - 1.0));
+        if expected_disagreement <= 0.0 {
+            return None;
+        }
+
+        Some(1.0 - observed_disagreement / expected_disagreement)
+    }
+}
@@ -0,0 +1,152 @@
+//! Conflicts of interest for Chaos reviewers.
+//!
+//! A reviewer with a conflict of interest on an application, whether declared by
+//! themselves or recorded by an admin, cannot rate it, comment on it or read its
+//! comments. Their existing ratings are left out of the application's aggregated
+//! scores, and they are never assigned to review it. Reviewers can declare their
+//! own conflicts, and admins get a report of every conflict in a campaign.
+
+use crate::models::blind_review::BlindReview;
+use crate::models::error::ChaosError;
+use crate::models::reviewer_assignment::{NewReviewConflict, ReviewerAssignment};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use snowflake::SnowflakeIdGenerator;
+use sqlx::{Postgres, Transaction};
+use std::ops::DerefMut;
+
+/// Data structure for a reviewer declaring their own conflict of interest.
+#[derive(Deserialize)]
+pub struct ConflictDeclaration {
+    pub note: Option<String>,
+}
+
+/// A conflict of interest in a campaign's conflict report.
+#[derive(Serialize)]
+pub struct ConflictReportEntry {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub id: i64,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub application_id: i64,
+    pub user_name: String,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub reviewer_id: i64,
+    pub reviewer_name: String,
+    pub note: Option<String>,
+    #[serde(serialize_with = "crate::models::serde_string::serialize_option")]
+    pub declared_by: Option<i64>,
+    pub declared_by_name: Option<String>,
+    /// Whether the reviewer declared the conflict themselves
+    pub self_declared: bool,
+    /// Whether the reviewer had rated the application, so their rating is now
+    /// left out of aggregated scores
+    pub rating_excluded: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct ReviewConflict;
+
+impl ReviewConflict {
+    /// Declares that a reviewer has a conflict of interest with an application.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign the application belongs to
+    /// * `application_id` - The ID of the application
+    /// * `declaration` - An optional note explaining the conflict
+    /// * `reviewer_id` - The reviewer declaring the conflict
+    /// * `snowflake_generator` - A generator for creating unique IDs
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(i64)` - The ID of the conflict
+    /// * `Err(ChaosError)` - An error if the application is not in the campaign
+    pub async fn declare(
+        campaign_id: i64,
+        application_id: i64,
+        declaration: ConflictDeclaration,
+        reviewer_id: i64,
+        snowflake_generator: &mut SnowflakeIdGenerator,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<i64, ChaosError> {
+        let in_campaign = sqlx::query!(
+            r#"
+                SELECT EXISTS(
+                    SELECT 1 FROM applications WHERE id = $1 AND campaign_id = $2
+                ) AS "exists!"
+            "#,
+            application_id,
+            campaign_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .exists;
+
+        if !in_campaign {
+            return Err(ChaosError::BadRequest);
+        }
+
+        ReviewerAssignment::create_conflict(
+            application_id,
+            NewReviewConflict {
+                reviewer_id,
+                note: declaration.note,
+            },
+            reviewer_id,
+            snowflake_generator,
+            transaction,
+        )
+        .await
+    }
+
+    /// Retrieves every conflict of interest declared in a campaign.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<ConflictReportEntry>)` - The campaign's conflicts, grouped by application
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get_report(
+        campaign_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<ConflictReportEntry>, ChaosError> {
+        let mut report = sqlx::query_as!(
+            ConflictReportEntry,
+            r#"
+                SELECT rc.id, rc.application_id, applicant.name AS user_name,
+                    rc.reviewer_id, reviewer.name AS reviewer_name, rc.note,
+                    rc.declared_by, declarer.name AS "declared_by_name?",
+                    COALESCE(rc.declared_by = rc.reviewer_id, false) AS "self_declared!",
+                    EXISTS(
+                        SELECT 1 FROM application_ratings ar
+                        WHERE ar.application_id = rc.application_id
+                        AND ar.rater_id = rc.reviewer_id
+                    ) AS "rating_excluded!",
+                    rc.created_at
+                FROM review_conflicts rc
+                JOIN applications a ON a.id = rc.application_id
+                JOIN users applicant ON applicant.id = a.user_id
+                JOIN users reviewer ON reviewer.id = rc.reviewer_id
+                LEFT JOIN users declarer ON declarer.id = rc.declared_by
+                WHERE a.campaign_id = $1
+                ORDER BY rc.application_id, rc.created_at
+            "#,
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let application_ids: Vec<i64> = report.iter().map(|entry| entry.application_id).collect();
+        let blinded = BlindReview::blinded_applications(&application_ids, transaction).await?;
+        for entry in report.iter_mut() {
+            if let Some(pseudonym) = blinded.get(&entry.application_id) {
+                entry.user_name = pseudonym.clone();
+            }
+        }
+
+        Ok(report)
+    }
+}
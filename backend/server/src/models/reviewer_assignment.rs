@@ -0,0 +1,742 @@
+//! Reviewer assignment for Chaos.
+//!
+//! Admins can assign reviewers to every application for a role, or to specific
+//! applications. Auto-assign gives each application in a campaign a target number of
+//! reviewers, always picking the least loaded eligible reviewer so work is spread
+//! evenly. Reviewers with a conflict of interest on an application are never
+//! assigned to it, and applicants are never assigned to their own application.
+//! Each reviewer has a queue of the applications assigned to them that they have
+//! not yet rated.
+
+use crate::models::blind_review::BlindReview;
+use crate::models::error::ChaosError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use snowflake::SnowflakeIdGenerator;
+use sqlx::{Postgres, Transaction};
+use std::collections::{HashMap, HashSet};
+use std::ops::DerefMut;
+
+/// A reviewer assigned to every application for a role.
+#[derive(Serialize)]
+pub struct RoleReviewer {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub campaign_role_id: i64,
+    pub role_name: String,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub reviewer_id: i64,
+    pub reviewer_name: String,
+}
+
+/// A reviewer assigned to a specific application.
+#[derive(Serialize)]
+pub struct ApplicationReviewer {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub application_id: i64,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub reviewer_id: i64,
+    pub reviewer_name: String,
+    /// Whether the assignment was made by auto-assign
+    pub auto_assigned: bool,
+    /// Whether the reviewer has rated the application
+    pub rated: bool,
+}
+
+/// A reviewer excluded from an application because of a conflict of interest.
+#[derive(Serialize)]
+pub struct ReviewConflict {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub id: i64,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub application_id: i64,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub reviewer_id: i64,
+    pub reviewer_name: String,
+    pub note: Option<String>,
+    /// The user who recorded the conflict
+    #[serde(serialize_with = "crate::models::serde_string::serialize_option")]
+    pub declared_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Every reviewer assignment and conflict in a campaign.
+#[derive(Serialize)]
+pub struct ReviewerAssignments {
+    pub role_reviewers: Vec<RoleReviewer>,
+    pub application_reviewers: Vec<ApplicationReviewer>,
+    pub conflicts: Vec<ReviewConflict>,
+}
+
+/// Data structure for replacing the reviewers of a role or application.
+#[derive(Deserialize)]
+pub struct ReviewerList {
+    #[serde(deserialize_with = "crate::models::serde_string::deserialize_vec")]
+    pub reviewer_ids: Vec<i64>,
+}
+
+/// Data structure for auto-assigning reviewers across a campaign.
+#[derive(Deserialize)]
+pub struct AutoAssignRequest {
+    /// How many reviewers each application should end up with
+    pub reviewers_per_application: usize,
+    /// Reviewers to assign from, or every organisation member if empty
+    #[serde(
+        default,
+        deserialize_with = "crate::models::serde_string::deserialize_vec"
+    )]
+    pub reviewer_ids: Vec<i64>,
+}
+
+/// How many applications a reviewer is assigned to in a campaign.
+#[derive(Serialize)]
+pub struct ReviewerLoad {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub reviewer_id: i64,
+    pub assigned: usize,
+}
+
+/// The result of auto-assigning reviewers.
+#[derive(Serialize)]
+pub struct AutoAssignReport {
+    pub assignments_created: usize,
+    pub reviewer_loads: Vec<ReviewerLoad>,
+    /// Applications that could not be given enough eligible reviewers
+    #[serde(serialize_with = "crate::models::serde_string::serialize_vec")]
+    pub understaffed_applications: Vec<i64>,
+}
+
+/// Data structure for recording a conflict of interest.
+#[derive(Deserialize)]
+pub struct NewReviewConflict {
+    #[serde(deserialize_with = "crate::models::serde_string::deserialize")]
+    pub reviewer_id: i64,
+    pub note: Option<String>,
+}
+
+/// An application waiting for a reviewer's rating.
+#[derive(Serialize)]
+pub struct ReviewQueueItem {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub application_id: i64,
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub campaign_id: i64,
+    pub campaign_name: String,
+    pub organisation_name: String,
+    pub user_name: String,
+    /// Names of the roles applied for, most preferred first
+    pub roles: Vec<String>,
+    /// When the reviewer was first assigned to the application
+    pub assigned_at: DateTime<Utc>,
+}
+
+pub struct ReviewerAssignment;
+
+impl ReviewerAssignment {
+    /// Retrieves every reviewer assignment and conflict in a campaign.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(ReviewerAssignments)` - The campaign's assignments and conflicts
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get(
+        campaign_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<ReviewerAssignments, ChaosError> {
+        let role_reviewers = sqlx::query_as!(
+            RoleReviewer,
+            "
+                SELECT rra.campaign_role_id, r.name AS role_name, rra.reviewer_id,
+                    u.name AS reviewer_name
+                FROM reviewer_role_assignments rra
+                JOIN campaign_roles r ON r.id = rra.campaign_role_id
+                JOIN users u ON u.id = rra.reviewer_id
+                WHERE r.campaign_id = $1
+                ORDER BY r.id, u.name
+            ",
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let application_reviewers = sqlx::query_as!(
+            ApplicationReviewer,
+            r#"
+                SELECT raa.application_id, raa.reviewer_id, u.name AS reviewer_name,
+                    raa.auto_assigned,
+                    EXISTS(
+                        SELECT 1 FROM application_ratings ar
+                        WHERE ar.application_id = raa.application_id
+                        AND ar.rater_id = raa.reviewer_id
+                    ) AS "rated!"
+                FROM reviewer_application_assignments raa
+                JOIN applications a ON a.id = raa.application_id
+                JOIN users u ON u.id = raa.reviewer_id
+                WHERE a.campaign_id = $1
+                ORDER BY raa.application_id, u.name
+            "#,
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let conflicts = sqlx::query_as!(
+            ReviewConflict,
+            "
+                SELECT rc.id, rc.application_id, rc.reviewer_id, u.name AS reviewer_name,
+                    rc.note, rc.declared_by, rc.created_at
+                FROM review_conflicts rc
+                JOIN applications a ON a.id = rc.application_id
+                JOIN users u ON u.id = rc.reviewer_id
+                WHERE a.campaign_id = $1
+                ORDER BY rc.created_at
+            ",
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        Ok(ReviewerAssignments {
+            role_reviewers,
+            application_reviewers,
+            conflicts,
+        })
+    }
+
+    /// Replaces the reviewers assigned to every application for a role.
+    ///
+    /// # Arguments
+    /// * `role_id` - The ID of the campaign role
+    /// * `reviewer_ids` - The new reviewers
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the reviewers were assigned
+    /// * `Err(ChaosError)` - An error if a reviewer is not an organisation member
+    pub async fn set_role_reviewers(
+        role_id: i64,
+        reviewer_ids: Vec<i64>,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let campaign_id = sqlx::query!(
+            "SELECT campaign_id FROM campaign_roles WHERE id = $1",
+            role_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .campaign_id;
+
+        Self::assert_reviewers_are_members(campaign_id, &reviewer_ids, transaction).await?;
+
+        // Only remove reviewers who are no longer listed, so kept assignments keep
+        // their original assignment time.
+        sqlx::query!(
+            "
+                DELETE FROM reviewer_role_assignments
+                WHERE campaign_role_id = $1 AND reviewer_id <> ALL($2)
+            ",
+            role_id,
+            &reviewer_ids
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        sqlx::query!(
+            "
+                INSERT INTO reviewer_role_assignments (campaign_role_id, reviewer_id)
+                SELECT $1, reviewer_id FROM UNNEST($2::BIGINT[]) AS reviewer_id
+                ON CONFLICT DO NOTHING
+            ",
+            role_id,
+            &reviewer_ids
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Replaces the reviewers assigned to a specific application.
+    ///
+    /// # Arguments
+    /// * `application_id` - The ID of the application
+    /// * `reviewer_ids` - The new reviewers
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the reviewers were assigned
+    /// * `Err(ChaosError)` - An error if a reviewer is not an organisation member,
+    ///   has a conflict of interest, or is the applicant
+    pub async fn set_application_reviewers(
+        application_id: i64,
+        reviewer_ids: Vec<i64>,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let application = sqlx::query!(
+            "SELECT campaign_id, user_id FROM applications WHERE id = $1",
+            application_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        Self::assert_reviewers_are_members(application.campaign_id, &reviewer_ids, transaction)
+            .await?;
+
+        if reviewer_ids.contains(&application.user_id) {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Applicants cannot review their own application".to_string(),
+            ));
+        }
+
+        let conflicted = sqlx::query!(
+            "
+                SELECT EXISTS(
+                    SELECT 1 FROM review_conflicts
+                    WHERE application_id = $1 AND reviewer_id = ANY($2)
+                )
+            ",
+            application_id,
+            &reviewer_ids
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .exists
+        .expect("`exists` should always exist in this query result");
+
+        if conflicted {
+            return Err(ChaosError::BadRequestWithMessage(
+                "A reviewer has a conflict of interest with this application".to_string(),
+            ));
+        }
+
+        // Only remove reviewers who are no longer listed, so kept assignments keep
+        // their original assignment time and how they were made.
+        sqlx::query!(
+            "
+                DELETE FROM reviewer_application_assignments
+                WHERE application_id = $1 AND reviewer_id <> ALL($2)
+            ",
+            application_id,
+            &reviewer_ids
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        sqlx::query!(
+            "
+                INSERT INTO reviewer_application_assignments (application_id, reviewer_id)
+                SELECT $1, reviewer_id FROM UNNEST($2::BIGINT[]) AS reviewer_id
+                ON CONFLICT DO NOTHING
+            ",
+            application_id,
+            &reviewer_ids
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Assigns reviewers so every submitted application in a campaign has the
+    /// requested number, balancing load across reviewers.
+    ///
+    /// Existing application and role assignments, and reviewers who have already
+    /// rated an application, count towards its total and towards each reviewer's
+    /// load. Applications with the fewest reviewers are filled first, each time
+    /// picking the eligible reviewer with the fewest assignments in the campaign.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign
+    /// * `request` - The number of reviewers per application and the reviewer pool
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(AutoAssignReport)` - The assignments made and the resulting loads
+    /// * `Err(ChaosError)` - An error if the request is invalid or assignment fails
+    pub async fn auto_assign(
+        campaign_id: i64,
+        request: AutoAssignRequest,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<AutoAssignReport, ChaosError> {
+        if request.reviewers_per_application == 0 {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Each application needs at least one reviewer".to_string(),
+            ));
+        }
+
+        let mut pool = request.reviewer_ids;
+        if pool.is_empty() {
+            pool = sqlx::query!(
+                "
+                    SELECT om.user_id
+                    FROM organisation_members om
+                    JOIN campaigns c ON c.organisation_id = om.organisation_id
+                    WHERE c.id = $1
+                    ORDER BY om.user_id
+                ",
+                campaign_id
+            )
+            .fetch_all(transaction.deref_mut())
+            .await?
+            .into_iter()
+            .map(|member| member.user_id)
+            .collect();
+        } else {
+            Self::assert_reviewers_are_members(campaign_id, &pool, transaction).await?;
+            pool.sort_unstable();
+            pool.dedup();
+        }
+
+        let applications = sqlx::query!(
+            "
+                SELECT id, user_id FROM applications
+                WHERE campaign_id = $1 AND submitted = true
+                ORDER BY id
+            ",
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        // Reviewers already covering each application, through an application or role
+        // assignment, or a rating.
+        let covering = sqlx::query!(
+            r#"
+                SELECT raa.application_id AS "application_id!", raa.reviewer_id AS "reviewer_id!"
+                FROM reviewer_application_assignments raa
+                JOIN applications a ON a.id = raa.application_id
+                WHERE a.campaign_id = $1 AND a.submitted = true
+                UNION
+                SELECT a.id, rra.reviewer_id
+                FROM reviewer_role_assignments rra
+                JOIN application_roles ar ON ar.campaign_role_id = rra.campaign_role_id
+                JOIN applications a ON a.id = ar.application_id
+                WHERE a.campaign_id = $1 AND a.submitted = true
+                AND a.user_id <> rra.reviewer_id
+                AND NOT EXISTS(
+                    SELECT 1 FROM review_conflicts rc
+                    WHERE rc.application_id = a.id AND rc.reviewer_id = rra.reviewer_id
+                )
+                UNION
+                SELECT ar.application_id, ar.rater_id
+                FROM application_ratings ar
+                JOIN applications a ON a.id = ar.application_id
+                WHERE a.campaign_id = $1 AND a.submitted = true
+            "#,
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let excluded: HashSet<(i64, i64)> = sqlx::query!(
+            "
+                SELECT rc.application_id, rc.reviewer_id
+                FROM review_conflicts rc
+                JOIN applications a ON a.id = rc.application_id
+                WHERE a.campaign_id = $1
+            ",
+            campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?
+        .into_iter()
+        .map(|conflict| (conflict.application_id, conflict.reviewer_id))
+        .collect();
+
+        let mut reviewers: HashMap<i64, HashSet<i64>> = HashMap::new();
+        let mut load: HashMap<i64, usize> = pool.iter().map(|&id| (id, 0)).collect();
+        for cover in covering {
+            reviewers
+                .entry(cover.application_id)
+                .or_default()
+                .insert(cover.reviewer_id);
+            if let Some(assigned) = load.get_mut(&cover.reviewer_id) {
+                *assigned += 1;
+            }
+        }
+
+        let mut order: Vec<(i64, i64)> = applications
+            .iter()
+            .map(|application| (application.id, application.user_id))
+            .collect();
+        order.sort_by_key(|(id, _)| (reviewers.get(id).map_or(0, HashSet::len), *id));
+
+        let mut new_applications = Vec::new();
+        let mut new_reviewers = Vec::new();
+        let mut understaffed_applications = Vec::new();
+        for (application_id, applicant_id) in order {
+            let current = reviewers.entry(application_id).or_default();
+            let needed = request
+                .reviewers_per_application
+                .saturating_sub(current.len());
+            if needed == 0 {
+                continue;
+            }
+
+            let mut candidates: Vec<i64> = pool
+                .iter()
+                .copied()
+                .filter(|&reviewer_id| {
+                    reviewer_id != applicant_id
+                        && !current.contains(&reviewer_id)
+                        && !excluded.contains(&(application_id, reviewer_id))
+                })
+                .collect();
+            candidates.sort_by_key(|reviewer_id| (load[reviewer_id], *reviewer_id));
+
+            if candidates.len() < needed {
+                understaffed_applications.push(application_id);
+            }
+
+            for reviewer_id in candidates.into_iter().take(needed) {
+                current.insert(reviewer_id);
+                *load
+                    .get_mut(&reviewer_id)
+                    .expect("candidates come from the pool") += 1;
+                new_applications.push(application_id);
+                new_reviewers.push(reviewer_id);
+            }
+        }
+
+        sqlx::query!(
+            "
+                INSERT INTO reviewer_application_assignments
+                    (application_id, reviewer_id, auto_assigned)
+                SELECT *, true FROM UNNEST($1::BIGINT[], $2::BIGINT[])
+                ON CONFLICT DO NOTHING
+            ",
+            &new_applications,
+            &new_reviewers
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        let reviewer_loads = pool
+            .iter()
+            .map(|&reviewer_id| ReviewerLoad {
+                reviewer_id,
+                assigned: load[&reviewer_id],
+            })
+            .collect();
+
+        Ok(AutoAssignReport {
+            assignments_created: new_applications.len(),
+            reviewer_loads,
+            understaffed_applications,
+        })
+    }
+
+    /// Records that a reviewer has a conflict of interest with an application.
+    ///
+    /// The reviewer is removed from the application's assigned reviewers. Recording
+    /// a conflict that already exists replaces its note.
+    ///
+    /// # Arguments
+    /// * `application_id` - The ID of the application
+    /// * `conflict` - The conflicted reviewer and an optional note
+    /// * `declared_by` - The user recording the conflict
+    /// * `snowflake_generator` - A generator for creating unique IDs
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(i64)` - The ID of the conflict
+    /// * `Err(ChaosError)` - An error if the reviewer is not an organisation member
+    pub async fn create_conflict(
+        application_id: i64,
+        conflict: NewReviewConflict,
+        declared_by: i64,
+        snowflake_generator: &mut SnowflakeIdGenerator,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<i64, ChaosError> {
+        let campaign_id = sqlx::query!(
+            "SELECT campaign_id FROM applications WHERE id = $1",
+            application_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .campaign_id;
+
+        Self::assert_reviewers_are_members(campaign_id, &[conflict.reviewer_id], transaction)
+            .await?;
+
+        let id = sqlx::query!(
+            "
+                INSERT INTO review_conflicts (id, application_id, reviewer_id, note, declared_by)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (application_id, reviewer_id)
+                DO UPDATE SET note = $4, declared_by = $5
+                RETURNING id
+            ",
+            snowflake_generator.real_time_generate(),
+            application_id,
+            conflict.reviewer_id,
+            conflict.note,
+            declared_by
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .id;
+
+        sqlx::query!(
+            "
+                DELETE FROM reviewer_application_assignments
+                WHERE application_id = $1 AND reviewer_id = $2
+            ",
+            application_id,
+            conflict.reviewer_id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Removes a conflict of interest from a campaign.
+    ///
+    /// # Arguments
+    /// * `campaign_id` - The ID of the campaign the conflict belongs to
+    /// * `conflict_id` - The ID of the conflict
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the conflict was removed
+    /// * `Err(ChaosError)` - An error if the conflict does not exist in the campaign
+    pub async fn delete_conflict(
+        campaign_id: i64,
+        conflict_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        sqlx::query!(
+            "
+                DELETE FROM review_conflicts rc
+                USING applications a
+                WHERE rc.id = $1 AND a.id = rc.application_id AND a.campaign_id = $2
+                RETURNING rc.id
+            ",
+            conflict_id,
+            campaign_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Retrieves the applications assigned to a reviewer that they have not rated.
+    ///
+    /// Includes applications assigned directly and those for roles the reviewer is
+    /// assigned to, skipping any they have a conflict of interest with or are no
+    /// longer an organisation member for.
+    ///
+    /// # Arguments
+    /// * `reviewer_id` - The ID of the reviewer
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Vec<ReviewQueueItem>)` - The queue, oldest assignment first
+    /// * `Err(ChaosError)` - An error if retrieval fails
+    pub async fn get_queue(
+        reviewer_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<ReviewQueueItem>, ChaosError> {
+        let mut queue = sqlx::query_as!(
+            ReviewQueueItem,
+            r#"
+                SELECT a.id AS application_id, a.campaign_id, c.name AS campaign_name,
+                    o.name AS organisation_name, u.name AS user_name,
+                    ARRAY(
+                        SELECT r.name FROM application_roles ar
+                        JOIN campaign_roles r ON r.id = ar.campaign_role_id
+                        WHERE ar.application_id = a.id
+                        ORDER BY ar.preference_percentage DESC
+                    ) AS "roles!",
+                    MIN(assigned.created_at) AS "assigned_at!"
+                FROM (
+                    SELECT application_id, created_at
+                    FROM reviewer_application_assignments
+                    WHERE reviewer_id = $1
+                    UNION ALL
+                    SELECT ar.application_id, rra.created_at
+                    FROM reviewer_role_assignments rra
+                    JOIN application_roles ar ON ar.campaign_role_id = rra.campaign_role_id
+                    WHERE rra.reviewer_id = $1
+                ) assigned
+                JOIN applications a ON a.id = assigned.application_id
+                JOIN campaigns c ON c.id = a.campaign_id
+                JOIN organisations o ON o.id = c.organisation_id
+                JOIN users u ON u.id = a.user_id
+                WHERE a.submitted = true AND a.user_id <> $1
+                AND EXISTS(
+                    SELECT 1 FROM organisation_members om
+                    WHERE om.organisation_id = c.organisation_id AND om.user_id = $1
+                )
+                AND NOT EXISTS(
+                    SELECT 1 FROM application_ratings rt
+                    WHERE rt.application_id = a.id AND rt.rater_id = $1
+                )
+                AND NOT EXISTS(
+                    SELECT 1 FROM review_conflicts rc
+                    WHERE rc.application_id = a.id AND rc.reviewer_id = $1
+                )
+                GROUP BY a.id, c.name, o.name, u.name
+                ORDER BY MIN(assigned.created_at), a.id
+            "#,
+            reviewer_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        let application_ids: Vec<i64> = queue.iter().map(|item| item.application_id).collect();
+        let blinded = BlindReview::blinded_applications(&application_ids, transaction).await?;
+        for item in queue.iter_mut() {
+            if let Some(pseudonym) = blinded.get(&item.application_id) {
+                item.user_name = pseudonym.clone();
+            }
+        }
+
+        Ok(queue)
+    }
+
+    /// Checks that every reviewer is a member of the campaign's organisation.
+    async fn assert_reviewers_are_members(
+        campaign_id: i64,
+        reviewer_ids: &[i64],
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let non_member = sqlx::query!(
+            "
+                SELECT EXISTS(
+                    SELECT 1 FROM UNNEST($2::BIGINT[]) AS reviewer(id)
+                    WHERE NOT EXISTS(
+                        SELECT 1 FROM organisation_members om
+                        JOIN campaigns c ON c.organisation_id = om.organisation_id
+                        WHERE c.id = $1 AND om.user_id = reviewer.id
+                    )
+                )
+            ",
+            campaign_id,
+            reviewer_ids
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .exists
+        .expect("`exists` should always exist in this query result");
+
+        if non_member {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Reviewers must be members of the campaign's organisation".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
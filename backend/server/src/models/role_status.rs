@@ -3,8 +3,10 @@
 //! This module provides database access for CRUD operations on per-campaign-role statuses.
 
 use crate::models::application::ApplicationStatus;
+use crate::models::application_event::{ApplicationEvent, ApplicationEventType};
 use crate::models::error::ChaosError;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use sqlx::{FromRow, Postgres, Transaction};
 use std::ops::DerefMut;
 
@@ -35,27 +37,48 @@ impl RoleStatus {
     /// * `application_id` - The application to modify the status for.
     /// * `campaign_role_id` - The role to modify the status for.
     /// * `new_status` - The new status for this applicant in this particular role.
+    /// * `changed_by` - The user making the change.
     /// * `transaction` - Database transaction to use.
     ///
     pub async fn update_status(
         application_id: i64,
         campaign_role_id: i64,
         new_status: ApplicationStatus,
+        changed_by: i64,
         transaction: &mut Transaction<'_, Postgres>,
     ) -> Result<(), ChaosError> {
-        sqlx::query!(
-            "
+        let old_status = sqlx::query!(
+            r#"
+                WITH old AS (
+                    SELECT role_status FROM application_roles
+                    WHERE application_id = $2 AND campaign_role_id = $3
+                    FOR UPDATE
+                )
                 UPDATE application_roles
                 SET role_status = $1
+                FROM old
                 WHERE application_id = $2 AND campaign_role_id = $3
-            ",
-            new_status as ApplicationStatus,
+                RETURNING old.role_status AS "old_status: ApplicationStatus"
+            "#,
+            new_status.clone() as ApplicationStatus,
             application_id,
             campaign_role_id,
         )
-        .execute(transaction.deref_mut())
+        .fetch_optional(transaction.deref_mut())
         .await?;
 
+        if let Some(old) = old_status {
+            ApplicationEvent::record(
+                application_id,
+                ApplicationEventType::RoleStatusChanged,
+                Some(changed_by),
+                Some(campaign_role_id),
+                json!({ "from": old.old_status, "to": new_status }),
+                transaction,
+            )
+            .await?;
+        }
+
         Ok(())
     }
 
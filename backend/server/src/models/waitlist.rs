@@ -0,0 +1,307 @@
+//! Role waitlists for Chaos.
+//!
+//! Each campaign role can keep a ranked waitlist of applicants. When an offer for the
+//! role is declined or expires, the highest-ranked applicant still without an offer
+//! is taken off the waitlist and given a new offer using the same email template, and
+//! the organisation's admins are notified.
+
+use crate::models::email::{EmailContext, EmailQueue};
+use crate::models::error::ChaosError;
+use crate::models::offer::Offer;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use snowflake::SnowflakeIdGenerator;
+use sqlx::{Postgres, Transaction};
+use std::collections::HashSet;
+use std::ops::DerefMut;
+
+/// An applicant on a role's waitlist.
+#[derive(Serialize)]
+pub struct WaitlistEntry {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub application_id: i64,
+    pub user_name: String,
+    pub user_email: String,
+    /// Position on the waitlist, starting at 1
+    pub rank: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A role's waitlist.
+#[derive(Serialize)]
+pub struct Waitlist {
+    #[serde(serialize_with = "crate::models::serde_string::serialize")]
+    pub role_id: i64,
+    /// Whether offers made from the waitlist are sent straight away, or left as drafts
+    pub auto_send: bool,
+    /// Applicants on the waitlist, highest ranked first
+    pub entries: Vec<WaitlistEntry>,
+}
+
+/// Data structure for replacing a role's waitlist.
+#[derive(Deserialize)]
+pub struct WaitlistUpdate {
+    /// Applications on the waitlist, highest ranked first
+    #[serde(deserialize_with = "crate::models::serde_string::deserialize_vec")]
+    pub application_ids: Vec<i64>,
+    /// Whether offers made from the waitlist are sent straight away
+    pub auto_send: bool,
+}
+
+/// Why an offer's role is being offered to the next applicant.
+#[derive(Clone, Copy, Debug)]
+pub enum CascadeReason {
+    Declined,
+    Expired,
+}
+
+impl Waitlist {
+    /// Retrieves a role's waitlist.
+    ///
+    /// # Arguments
+    /// * `role_id` - The ID of the role
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Waitlist)` - The role's waitlist
+    /// * `Err(ChaosError)` - An error if the role does not exist or retrieval fails
+    pub async fn get(
+        role_id: i64,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Waitlist, ChaosError> {
+        let auto_send = sqlx::query!(
+            "SELECT waitlist_auto_send FROM campaign_roles WHERE id = $1",
+            role_id
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .waitlist_auto_send;
+
+        let entries = sqlx::query_as!(
+            WaitlistEntry,
+            "
+                SELECT w.application_id, u.name AS user_name, u.email AS user_email,
+                    w.rank, w.created_at
+                FROM campaign_role_waitlist w
+                JOIN applications a ON a.id = w.application_id
+                JOIN users u ON u.id = a.user_id
+                WHERE w.campaign_role_id = $1
+                ORDER BY w.rank ASC
+            ",
+            role_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        Ok(Waitlist {
+            role_id,
+            auto_send,
+            entries,
+        })
+    }
+
+    /// Replaces a role's waitlist.
+    ///
+    /// # Arguments
+    /// * `role_id` - The ID of the role
+    /// * `update` - The new waitlist, highest ranked first
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(())` - If the waitlist was replaced
+    /// * `Err(ChaosError)` - An error if an application did not apply for the role
+    pub async fn set(
+        role_id: i64,
+        update: WaitlistUpdate,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ChaosError> {
+        let unique: HashSet<i64> = update.application_ids.iter().copied().collect();
+        if unique.len() != update.application_ids.len() {
+            return Err(ChaosError::BadRequestWithMessage(
+                "An application can only appear on the waitlist once".to_string(),
+            ));
+        }
+
+        let applied = sqlx::query!(
+            r#"
+                SELECT COUNT(*) AS "count!"
+                FROM application_roles ar
+                JOIN applications a ON a.id = ar.application_id
+                WHERE ar.campaign_role_id = $1 AND a.submitted = true
+                AND ar.application_id = ANY($2)
+            "#,
+            role_id,
+            &update.application_ids
+        )
+        .fetch_one(transaction.deref_mut())
+        .await?
+        .count;
+
+        if applied != update.application_ids.len() as i64 {
+            return Err(ChaosError::BadRequestWithMessage(
+                "Every waitlisted application must have applied for the role".to_string(),
+            ));
+        }
+
+        sqlx::query!(
+            "UPDATE campaign_roles SET waitlist_auto_send = $2 WHERE id = $1",
+            role_id,
+            update.auto_send
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM campaign_role_waitlist WHERE campaign_role_id = $1",
+            role_id
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        sqlx::query!(
+            "
+                INSERT INTO campaign_role_waitlist (campaign_role_id, application_id, rank)
+                SELECT $1, application_id, rank::int
+                FROM UNNEST($2::bigint[]) WITH ORDINALITY AS w(application_id, rank)
+            ",
+            role_id,
+            &update.application_ids
+        )
+        .execute(transaction.deref_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Offers a declined or expired offer's role to the next applicant on its waitlist.
+    ///
+    /// Applicants who already have an offer that has not been declined or expired are
+    /// skipped. The new offer uses the same email template and gives the applicant as
+    /// long to reply as the original offer did. Admins of the campaign's organisation are
+    /// notified either way.
+    ///
+    /// # Arguments
+    /// * `offer_id` - The ID of the declined or expired offer
+    /// * `reason` - Why the offer is being replaced
+    /// * `snowflake_generator` - Generator for the new offer ID
+    /// * `transaction` - A mutable reference to the database transaction
+    ///
+    /// # Returns
+    /// Returns a `Result` containing either:
+    /// * `Ok(Option<i64>)` - The ID of the new offer, or `None` if the waitlist was empty
+    /// * `Err(ChaosError)` - An error if the offer could not be made
+    pub async fn cascade(
+        offer_id: i64,
+        reason: CascadeReason,
+        snowflake_generator: &mut SnowflakeIdGenerator,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Option<i64>, ChaosError> {
+        let offer = Offer::get(offer_id, transaction).await?;
+
+        let next = sqlx::query!(
+            "
+                DELETE FROM campaign_role_waitlist
+                WHERE campaign_role_id = $1 AND application_id = (
+                    SELECT w.application_id
+                    FROM campaign_role_waitlist w
+                    WHERE w.campaign_role_id = $1
+                    AND NOT EXISTS (
+                        SELECT 1 FROM offers o
+                        WHERE o.application_id = w.application_id
+                        AND o.status NOT IN ('Declined', 'Expired', 'Withdrawn')
+                    )
+                    ORDER BY w.rank ASC
+                    LIMIT 1
+                    FOR UPDATE
+                )
+                RETURNING application_id,
+                    (SELECT waitlist_auto_send FROM campaign_roles WHERE id = $1) AS auto_send
+            ",
+            offer.role_id
+        )
+        .fetch_optional(transaction.deref_mut())
+        .await?;
+
+        let what_happened = match reason {
+            CascadeReason::Declined => "declined",
+            CascadeReason::Expired => "expired",
+        };
+        let mut body = format!(
+            "{}'s offer for the {} role in {} {}.\n\n",
+            offer.user_name, offer.role_name, offer.campaign_name, what_happened
+        );
+
+        let new_offer_id = match next {
+            Some(next) => {
+                let reply_window = offer.expiry - offer.created_at;
+                let new_offer_id = Offer::create(
+                    offer.campaign_id,
+                    next.application_id,
+                    offer.email_template_id,
+                    offer.role_id,
+                    Utc::now() + reply_window,
+                    transaction,
+                    snowflake_generator,
+                )
+                .await?;
+
+                let new_offer = Offer::get(new_offer_id, transaction).await?;
+                if next.auto_send.unwrap_or(false) {
+                    Offer::send_offer(new_offer_id, transaction).await?;
+                    body.push_str(&format!(
+                        "The next applicant on the waitlist, {}, has been sent an offer.\n",
+                        new_offer.user_name
+                    ));
+                } else {
+                    body.push_str(&format!(
+                        "A draft offer has been created for the next applicant on the waitlist, {}. Review and send it from the offers page.\n",
+                        new_offer.user_name
+                    ));
+                }
+
+                Some(new_offer_id)
+            }
+            None => {
+                body.push_str("There is no one left on the waitlist for this role.\n");
+                None
+            }
+        };
+
+        let admins = sqlx::query!(
+            "
+                SELECT u.name, u.email
+                FROM organisation_members om
+                JOIN users u ON u.id = om.user_id
+                JOIN campaigns c ON c.organisation_id = om.organisation_id
+                WHERE c.id = $1 AND om.role = 'Admin'
+            ",
+            offer.campaign_id
+        )
+        .fetch_all(transaction.deref_mut())
+        .await?;
+
+        for admin in admins {
+            EmailQueue::add_to_queue(
+                Some(admin.name),
+                admin.email,
+                format!(
+                    "Offer {what_happened} - {} {}",
+                    offer.campaign_name, offer.role_name
+                ),
+                body.clone(),
+                EmailContext {
+                    campaign_id: Some(offer.campaign_id),
+                    application_id: None,
+                    offer_id: new_offer_id,
+                    hold_for_release: false,
+                },
+                transaction,
+            )
+            .await?;
+        }
+
+        Ok(new_offer_id)
+    }
+}
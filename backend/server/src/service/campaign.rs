@@ -2,6 +2,7 @@
 //!
 //! This module provides functionality for managing campaigns, including:
 //! - Verifying campaign admin privileges
+//! - Verifying campaign applicants
 //! - Checking campaign status and deadlines
 
 use crate::models::error::ChaosError;
@@ -78,6 +79,46 @@ pub async fn user_is_campaign_org_member(
     Ok(())
 }
 
+/// Verifies if a user has applied to a campaign.
+///
+/// This function checks if the user has submitted an application to the campaign.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the user to check
+/// * `campaign_id` - The ID of the campaign
+/// * `pool` - Database connection pool
+///
+/// # Returns
+///
+/// * `Result<(), ChaosError>` - Ok if the user is an applicant, Unauthorized error otherwise
+pub async fn user_is_campaign_applicant(
+    user_id: i64,
+    campaign_id: i64,
+    transaction: &mut Transaction<'_, Postgres>,
+) -> Result<(), ChaosError> {
+    let is_applicant = sqlx::query!(
+        "
+            SELECT EXISTS(
+                SELECT 1 FROM applications
+                WHERE campaign_id = $1 AND user_id = $2 AND submitted = true
+            )
+        ",
+        campaign_id,
+        user_id
+    )
+    .fetch_one(transaction.deref_mut())
+    .await?
+    .exists
+    .expect("`exists` should always exist in this query result");
+
+    if !is_applicant {
+        return Err(ChaosError::Unauthorized);
+    }
+
+    Ok(())
+}
+
 /// Verifies if a campaign is still open for applications.
 ///
 /// This function checks if the campaign deadline has not passed.
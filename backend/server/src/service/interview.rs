@@ -0,0 +1,51 @@
+//! Interview service for the Chaos application.
+//!
+//! This module provides functionality for managing interviews, including:
+//! - Verifying interview timeslot admin privileges
+
+use crate::models::error::ChaosError;
+use sqlx::{Postgres, Transaction};
+use std::ops::DerefMut;
+
+/// Verifies if a user has admin privileges for an interview timeslot.
+///
+/// This function checks if the user is an admin of the organisation that owns the campaign
+/// the timeslot belongs to.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the user to check
+/// * `timeslot_id` - The ID of the interview timeslot
+/// * `pool` - Database connection pool
+///
+/// # Returns
+///
+/// * `Result<(), ChaosError>` - Ok if the user is an admin, Unauthorized error otherwise
+pub async fn user_is_interview_timeslot_admin(
+    user_id: i64,
+    timeslot_id: i64,
+    transaction: &mut Transaction<'_, Postgres>,
+) -> Result<(), ChaosError> {
+    let is_admin = sqlx::query!(
+        "
+            SELECT EXISTS(
+                SELECT 1 FROM interview_timeslots t
+                JOIN campaigns c ON c.id = t.campaign_id
+                JOIN organisation_members m on c.organisation_id = m.organisation_id
+                WHERE t.id = $1 AND m.user_id = $2 AND m.role = 'Admin'
+            )
+        ",
+        timeslot_id,
+        user_id
+    )
+    .fetch_one(transaction.deref_mut())
+    .await?
+    .exists
+    .expect("`exists` should always exist in this query result");
+
+    if !is_admin {
+        return Err(ChaosError::Unauthorized);
+    }
+
+    Ok(())
+}
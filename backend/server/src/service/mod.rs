@@ -9,12 +9,14 @@
 //! - `campaign`: Handles campaign-related operations
 //! - `comment`: Handles comment-related operations
 //! - `email_template`: Manages email template operations
+//! - `interview`: Manages interview timeslot permissions
 //! - `jwt`: Handles JWT token generation and validation
 //! - `oauth2`: Manages OAuth2 authentication flow
 //! - `offer`: Handles offer creation and management
 //! - `organisation`: Manages organisation-related operations
 //! - `question`: Handles question management for applications
 //! - `rating`: Manages application ratings
+//! - `review_conflict`: Enforces reviewers' conflicts of interest
 //! - `role`: Handles role management within campaigns
 
 pub mod answer;
@@ -23,11 +25,13 @@ pub mod auth;
 pub mod campaign;
 pub mod comment;
 pub mod email_template;
+pub mod interview;
 pub mod jwt;
 pub mod oauth2;
 pub mod offer;
 pub mod organisation;
 pub mod question;
 pub mod rating;
+pub mod review_conflict;
 pub mod role;
 pub mod user;
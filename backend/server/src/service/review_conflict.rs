@@ -0,0 +1,88 @@
+//! Review conflict service for the Chaos application.
+//!
+//! This module provides functionality for enforcing conflicts of interest, including:
+//! - Blocking conflicted reviewers from an application
+//! - Blocking conflicted reviewers from an application's ratings
+use crate::models::error::ChaosError;
+use sqlx::{Postgres, Transaction};
+use std::ops::DerefMut;
+
+/// Verifies that a user has not declared a conflict of interest on an application.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the user to check
+/// * `application_id` - The ID of the application
+/// * `transaction` - Database transaction
+///
+/// # Returns
+///
+/// * `Result<(), ChaosError>` - Ok if the user is not conflicted, ForbiddenOperation error otherwise
+pub async fn assert_user_is_not_conflicted(
+    user_id: i64,
+    application_id: i64,
+    transaction: &mut Transaction<'_, Postgres>,
+) -> Result<(), ChaosError> {
+    let is_conflicted = sqlx::query!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM review_conflicts
+            WHERE application_id = $1 AND reviewer_id = $2
+        )
+        "#,
+        application_id,
+        user_id
+    )
+    .fetch_one(transaction.deref_mut())
+    .await?
+    .exists
+    .expect("`exists` should always exist in this query result");
+
+    if is_conflicted {
+        return Err(ChaosError::ForbiddenOperation);
+    }
+
+    Ok(())
+}
+
+/// Verifies that a user has not declared a conflict of interest on the application
+/// a rating belongs to.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the user to check
+/// * `rating_id` - The ID of the rating
+/// * `transaction` - Database transaction
+///
+/// # Returns
+///
+/// * `Result<(), ChaosError>` - Ok if the user is not conflicted, ForbiddenOperation error otherwise
+pub async fn assert_user_is_not_conflicted_given_rating_id(
+    user_id: i64,
+    rating_id: i64,
+    transaction: &mut Transaction<'_, Postgres>,
+) -> Result<(), ChaosError> {
+    let is_conflicted = sqlx::query!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM review_conflicts rc
+            JOIN application_ratings ar ON ar.application_id = rc.application_id
+            WHERE ar.id = $1 AND rc.reviewer_id = $2
+        )
+        "#,
+        rating_id,
+        user_id
+    )
+    .fetch_one(transaction.deref_mut())
+    .await?
+    .exists
+    .expect("`exists` should always exist in this query result");
+
+    if is_conflicted {
+        return Err(ChaosError::ForbiddenOperation);
+    }
+
+    Ok(())
+}
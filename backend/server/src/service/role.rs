@@ -2,6 +2,7 @@
 //!
 //! This module provides functionality for managing campaign roles, including:
 //! - Verifying role admin privileges
+//! - Verifying a user applied to a role
 
 use crate::models::error::ChaosError;
 use sqlx::{Postgres, Transaction};
@@ -52,3 +53,46 @@ pub async fn user_is_role_admin(
 
     Ok(())
 }
+
+/// Verifies if a user applied to a role or has admin privileges for it.
+///
+/// This function checks if the user has submitted an application to the role, or is an
+/// admin of the organisation that owns the campaign the role belongs to.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the user to check
+/// * `role_id` - The ID of the role
+/// * `transaction` - A mutable reference to the database transaction
+///
+/// # Returns
+///
+/// * `Result<(), ChaosError>` - Ok if the user applied to the role or is an admin,
+///   Unauthorized error otherwise
+pub async fn user_is_role_applicant_or_admin(
+    user_id: i64,
+    role_id: i64,
+    transaction: &mut Transaction<'_, Postgres>,
+) -> Result<(), ChaosError> {
+    let is_applicant = sqlx::query!(
+        "
+            SELECT EXISTS(
+                SELECT 1 FROM application_roles ar
+                JOIN applications a ON a.id = ar.application_id
+                WHERE ar.campaign_role_id = $1 AND a.user_id = $2 AND a.submitted = true
+            )
+        ",
+        role_id,
+        user_id
+    )
+    .fetch_one(transaction.deref_mut())
+    .await?
+    .exists
+    .expect("`exists` should always exist in this query result");
+
+    if is_applicant {
+        return Ok(());
+    }
+
+    user_is_role_admin(user_id, role_id, transaction).await
+}
@@ -31,9 +31,7 @@ import {
   SelectTrigger,
   SelectValue,
 } from "@/components/ui/select";
-import { getCampaign } from "@/models/campaign";
 import { SendEmailsApplicant, SendEmailsModal } from "./send-email-modal";
-import { useQuery } from "@tanstack/react-query";
 import { queueCampaignOutcomeEmails } from "@/models/email";
 
 interface DataTableProps<TData, TValue> {
@@ -147,11 +145,6 @@ export function ApplicationSummaryDataTable<TData, TValue>({
 
   const borderColor = colorMap[color] || "border-gray-200";
 
-  const { data: campaign } = useQuery({
-    queryKey: [`${campaignId}-campaign-details`],
-    queryFn: () => getCampaign(campaignId),
-  });
-
   return (
     <div>
       <div className="flex justify-between items-end">
@@ -182,9 +175,8 @@ export function ApplicationSummaryDataTable<TData, TValue>({
                 open={sendModalOpen}
                 onOpenChange={setSendModalOpen}
                 orgId={orgId}
+                campaignId={campaignId}
                 recipients={selectedApplicants}
-                organisationName={campaign?.organisation_name}
-                campaignName={campaign?.name}
                 onSend={async (payload) => {
                   await queueCampaignOutcomeEmails(campaignId, payload);
                 }}
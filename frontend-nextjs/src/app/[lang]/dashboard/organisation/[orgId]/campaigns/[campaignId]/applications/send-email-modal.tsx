@@ -1,6 +1,6 @@
 "use client";
 
-import { useEffect, useMemo, useState } from "react";
+import { useEffect, useState } from "react";
 import { useQuery } from "@tanstack/react-query";
 import {
   Dialog,
@@ -13,13 +13,20 @@ import {
 import { Button } from "@/components/ui/button";
 import { Badge } from "@/components/ui/badge";
 import { Label } from "@/components/ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
 import { toast } from "sonner";
 import { Send, Loader2 } from "lucide-react";
 import {
   getOrganisationEmailTemplates,
+  previewCampaignOutcomeEmails,
   type QueueOutcomeEmailsPayload,
 } from "@/models/email";
-import { renderAcceptanceEmail } from "@/emails";
 
 export interface SendEmailsApplicant {
   id: string;
@@ -35,28 +42,18 @@ export interface SendEmailsModalProps {
   open: boolean;
   onOpenChange: (open: boolean) => void;
   orgId: string;
-  /** Selected recipients — thank-you email goes only to these. */
+  campaignId: string;
+  /** Selected recipients — the chosen template goes only to these. */
   recipients: SendEmailsApplicant[];
-  organisationName?: string;
-  campaignName?: string;
   onSend?: (payload: QueueOutcomeEmailsPayload) => Promise<void>;
 }
 
-/** Demo modal: hardcoded thank-you React Email → selected people only. */
+/** Sends an email template to the selected people, previewed as the server renders it. */
 export function SendEmailsModal(props: SendEmailsModalProps) {
-  const {
-    open,
-    onOpenChange,
-    orgId,
-    recipients,
-    organisationName = "",
-    campaignName = "",
-    onSend,
-  } = props;
+  const { open, onOpenChange, orgId, campaignId, recipients, onSend } = props;
   const [sending, setSending] = useState(false);
-  const [previewHtml, setPreviewHtml] = useState("");
-  const [previewSubject, setPreviewSubject] = useState("");
-  const [previewLoading, setPreviewLoading] = useState(false);
+  const [templateId, setTemplateId] = useState("");
+  const [expiryIso, setExpiryIso] = useState("");
 
   const { data: templates = [] } = useQuery({
     queryKey: [`${orgId}-email-templates`],
@@ -64,49 +61,56 @@ export function SendEmailsModal(props: SendEmailsModalProps) {
     enabled: open && !!orgId,
   });
 
-  const previewVars = useMemo(() => {
-    const sample = recipients[0];
-    return {
-      name: sample?.name ?? "Applicant",
-      role: sample?.roles?.length ? sample.roles.join(", ") : "Role",
-      organisation_name: organisationName || "Organisation",
-      campaign_name: campaignName || "Campaign",
-      expiry_date: "soon",
-    };
-  }, [recipients, organisationName, campaignName]);
+  useEffect(() => {
+    if (!templates.some((t) => t.id === templateId)) {
+      setTemplateId(templates[0]?.id ?? "");
+    }
+  }, [templates, templateId]);
 
+  // Fixed while the modal is open so the preview and the queued emails match.
   useEffect(() => {
-    if (!open) {
-      setPreviewHtml("");
-      setPreviewSubject("");
-      return;
+    if (open) {
+      setExpiryIso(
+        new Date(Date.now() + 3 * 24 * 60 * 60 * 1000).toISOString(),
+      );
     }
+  }, [open]);
 
-    let cancelled = false;
-    setPreviewLoading(true);
+  const sample = recipients[0];
+  const sampleRoleId = sample?.roleIds[0];
 
-    renderAcceptanceEmail(previewVars)
-      .then(({ subject, html }) => {
-        if (cancelled) return;
-        setPreviewSubject(subject);
-        setPreviewHtml(html);
-      })
-      .catch((err) => {
-        console.error(err);
-        if (!cancelled) {
-          setPreviewHtml("");
-          setPreviewSubject("");
-          toast.error("Failed to render email preview.");
-        }
-      })
-      .finally(() => {
-        if (!cancelled) setPreviewLoading(false);
+  const {
+    data: preview,
+    isFetching: previewLoading,
+    error: previewError,
+  } = useQuery({
+    queryKey: [
+      `${campaignId}-outcome-email-preview`,
+      templateId,
+      sample?.id,
+      sampleRoleId,
+      expiryIso,
+    ],
+    queryFn: async () => {
+      const report = await previewCampaignOutcomeEmails(campaignId, {
+        items: [
+          {
+            application_id: sample.id,
+            role_id: sampleRoleId,
+            outcome: "Reject",
+          },
+        ],
+        reject_template_id: templateId,
+        expiry: expiryIso,
       });
+      return report.recipients[0] ?? null;
+    },
+    enabled:
+      open && !!templateId && !!expiryIso && !!sample && !!sampleRoleId,
+    retry: false,
+  });
 
-    return () => {
-      cancelled = true;
-    };
-  }, [open, previewVars]);
+  const previewIsHtml = preview?.body.trimStart().startsWith("<") ?? false;
 
   const handleSend = async () => {
     if (sending) return;
@@ -127,46 +131,26 @@ export function SendEmailsModal(props: SendEmailsModalProps) {
       return;
     }
 
-    // Dummy template id for the queue payload (Reject path does not create offers).
-    const templateId = templates[0]?.id ?? "0";
-    const expiryIso = new Date(
-      Date.now() + 3 * 24 * 60 * 60 * 1000,
-    ).toISOString();
+    if (!templateId) {
+      toast.error("Create an email template first.");
+      return;
+    }
 
     setSending(true);
     try {
-      const emails: QueueOutcomeEmailsPayload["emails"] = [];
-
-      for (const a of recipients) {
-        const roleStr = a.roles.length ? a.roles.join(", ") : "";
-        const { subject, html } = await renderAcceptanceEmail({
-          name: a.name,
-          role: roleStr,
-          organisation_name: organisationName,
-          campaign_name: campaignName,
-          expiry_date: "soon",
-        });
-
-        emails.push({
-          id: a.id,
+      // The server renders each email from the template; Reject avoids creating offer records.
+      const emails: QueueOutcomeEmailsPayload["emails"] = recipients.map(
+        (a) => ({
           application_id: a.id,
-          name: a.name,
-          email: a.email,
-          // Reject avoids creating offer records; body is still the thank-you HTML.
           email_type: "Reject",
-          role: roleStr,
           role_id: a.roleIds[0],
           email_template_id: templateId,
           expiry: expiryIso,
-          subject,
-          body: html,
-        });
-      }
+        }),
+      );
 
       await onSend({ emails });
-      toast.success(
-        `Queued thank-you email${recipients.length !== 1 ? "s" : ""}`,
-      );
+      toast.success(`Queued email${recipients.length !== 1 ? "s" : ""}`);
       onOpenChange(false);
     } catch (e) {
       console.error(e);
@@ -187,7 +171,7 @@ export function SendEmailsModal(props: SendEmailsModalProps) {
             Send emails
           </DialogTitle>
           <DialogDescription>
-            Sends the thank-you email to the selected applicants.
+            Sends the chosen email template to the selected applicants.
           </DialogDescription>
         </DialogHeader>
 
@@ -206,31 +190,59 @@ export function SendEmailsModal(props: SendEmailsModalProps) {
             </div>
           </div>
 
+          <div className="flex flex-col gap-2">
+            <Label>Template</Label>
+            <Select
+              value={templateId}
+              onValueChange={setTemplateId}
+              disabled={templates.length === 0}
+            >
+              <SelectTrigger className="w-full">
+                <SelectValue placeholder="No email templates" />
+              </SelectTrigger>
+              <SelectContent>
+                {templates.map((t) => (
+                  <SelectItem key={t.id} value={t.id}>
+                    {t.name}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+
           <div className="flex flex-col gap-2">
             <Label>Subject</Label>
             <p className="rounded-md border bg-muted/30 px-3 py-2 text-sm">
-              {previewLoading ? "Rendering…" : previewSubject || "—"}
+              {previewLoading ? "Rendering…" : preview?.subject || "—"}
             </p>
           </div>
 
           <div className="flex flex-col gap-2">
-            <Label>Preview</Label>
+            <Label>
+              Preview{sample ? ` (as sent to ${sample.name})` : ""}
+            </Label>
             <div className="overflow-hidden rounded-md border bg-white">
               {previewLoading ? (
                 <div className="flex h-[320px] items-center justify-center text-sm text-muted-foreground">
                   <Loader2 className="mr-2 size-4 animate-spin" />
                   Rendering preview…
                 </div>
-              ) : previewHtml ? (
+              ) : preview && previewIsHtml ? (
                 <iframe
-                  title="Thank-you email preview"
-                  srcDoc={previewHtml}
+                  title="Email preview"
+                  srcDoc={preview.body}
                   sandbox=""
                   className="h-[320px] w-full border-0 bg-white"
                 />
+              ) : preview ? (
+                <pre className="h-[320px] overflow-auto whitespace-pre-wrap p-3 font-sans text-sm text-black">
+                  {preview.body}
+                </pre>
               ) : (
-                <div className="flex h-[320px] items-center justify-center text-sm text-muted-foreground">
-                  No preview available
+                <div className="flex h-[320px] items-center justify-center px-6 text-center text-sm text-muted-foreground">
+                  {previewError instanceof Error
+                    ? previewError.message
+                    : "No preview available"}
                 </div>
               )}
             </div>
@@ -250,8 +262,9 @@ export function SendEmailsModal(props: SendEmailsModalProps) {
             disabled={
               sending ||
               recipients.length === 0 ||
+              !templateId ||
               previewLoading ||
-              !previewHtml
+              !preview
             }
             className="gap-2"
           >
@@ -133,20 +133,17 @@ export async function deleteEmailTemplate(
   });
 }
 
-/** Batch queue for `POST /api/v1/campaign/:id/outcome-emails/queue` (worker sends via `EmailQueue`). */
+/**
+ * Batch queue for `POST /api/v1/campaign/:id/outcome-emails/queue` (worker sends via `EmailQueue`).
+ * The server renders each email from its template and sends it to the applicant's own address.
+ */
 export interface QueueOutcomeEmailsPayload {
   emails: {
-    id: string;
     application_id: string;
-    name: string;
-    email: string;
-    email_type: "Interview" | "Accept" | "Reject";
-    role: string;
+    email_type: "Accept" | "Reject";
     role_id: string;
     email_template_id: string;
     expiry: string;
-    subject: string;
-    body: string;
   }[];
 }
 
@@ -163,3 +160,50 @@ export async function queueCampaignOutcomeEmails(
     }
   );
 }
+
+/**
+ * Body for `POST /api/v1/campaign/:id/offers/bulk`. With `dry_run` set, nothing is
+ * created or queued and the server only renders the emails.
+ */
+export interface BulkOutcomePayload {
+  items: {
+    application_id: string;
+    role_id: string;
+    outcome: "Accept" | "Reject";
+  }[];
+  accept_template_id?: string;
+  reject_template_id?: string;
+  expiry: string;
+  dry_run: boolean;
+}
+
+export interface OutcomeEmailRecipient {
+  application_id: string;
+  role_id: string;
+  role_name: string;
+  outcome: "Accept" | "Reject";
+  recipient_name: string;
+  recipient_email_address: string;
+  email_template_id: string;
+  subject: string;
+  body: string;
+  offer_id: string | null;
+}
+
+export interface BulkOutcomeReport {
+  dry_run: boolean;
+  recipients: OutcomeEmailRecipient[];
+}
+
+export async function previewCampaignOutcomeEmails(
+  campaignId: string,
+  payload: Omit<BulkOutcomePayload, "dry_run">
+): Promise<BulkOutcomeReport> {
+  return await apiRequest<BulkOutcomeReport>(
+    `/api/v1/campaign/${campaignId}/offers/bulk`,
+    {
+      method: "POST",
+      body: { ...payload, dry_run: true },
+    }
+  );
+}
@@ -3,12 +3,25 @@ import { apiRequest } from "@/lib/api";
 
 export interface NewCateogry {
     name: string;
+    weight?: number;
+    min_rating?: number;
+    max_rating?: number;
+    rubric?: RubricDescriptor[];
+}
+
+export interface RubricDescriptor {
+    score: number;
+    description: string;
 }
 
 export interface RatingCategory {
     id: string;
     campaign_id: string;
     name: string;
+    weight: number;
+    min_rating: number;
+    max_rating: number;
+    rubric: RubricDescriptor[];
 }
 
 export interface RatingDetails {